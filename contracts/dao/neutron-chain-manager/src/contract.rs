@@ -1,24 +1,42 @@
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
 use cosmwasm_std::{
-    to_json_binary, Addr, Binary, CosmosMsg, Deps, DepsMut, Env, MessageInfo, Response, StdResult,
+    to_json_binary, to_json_string, Addr, BankMsg, Binary, Coin, CosmosMsg, Decimal, Deps, DepsMut,
+    Empty, Env, MessageInfo, Order, QueryRequest, Response, StdResult, Storage, WasmMsg,
 };
 use cw2::set_contract_version;
+use cw_storage_plus::Bound;
+use cw_utils::Expiration;
 use neutron_sdk::bindings::msg::{AdminProposal, NeutronMsg, ProposalExecuteMessage};
-use neutron_std::types::cosmos::upgrade::v1beta1::{MsgCancelUpgrade, MsgSoftwareUpgrade};
+use neutron_std::shim::Duration;
+use neutron_std::types::cosmos::base::v1beta1::DecCoin;
+use neutron_std::types::cosmos::upgrade::v1beta1::{
+    MsgCancelUpgrade, MsgSoftwareUpgrade, QueryCurrentPlanResponse, UpgradeQuerier,
+};
 use neutron_std::types::gaia::globalfee;
 use neutron_std::types::interchain_security::ccv::consumer;
 use neutron_std::types::neutron::cron;
 use neutron_std::types::neutron::dex;
 use neutron_std::types::neutron::dynamicfees;
 use neutron_std::types::osmosis::tokenfactory;
+use std::collections::BTreeSet;
+use std::str::FromStr;
 
 use crate::error::ContractError;
 use crate::msg::{
-    ExecuteMsg, InstantiateMsg, MigrateMsg, Permission, ProposalExecuteMessageJSON, QueryMsg,
-    Strategy, StrategyMsg,
+    AuditLogEntry, BankSendAllowanceResponse, BankSendPermission, Batch, BatchStatus, CoSigning,
+    CronExecutionStage, CronPermissionScope, ExecuteMsg, InstantiateMsg, MessageCheckResponse,
+    MigrateMsg, NumericConstraint, ParamUpdateRecord, Permission, PriceConstraint,
+    ProposalExecuteMessageJSON, QueryMsg, RateLimit, Strategy, StrategyMsg, StrategyResponse,
+};
+use crate::state::{
+    BankSendSpending, ExecutionRecord, AUDIT_LOG, AUDIT_LOG_BY_ADDRESS, AUDIT_LOG_BY_TYPE_URL,
+    AUDIT_LOG_NEXT_ID, BANK_SEND_SPENDINGS, BATCHES, BATCH_NEXT_ID, DEFAULT_LIMIT,
+    MAX_DELEGATED_STRATEGIES, MAX_DELEGATION_DEPTH, MAX_LIMIT, STRATEGIES, STRATEGY_CHILDREN,
+    STRATEGY_CO_SIGNING, STRATEGY_EXECUTIONS, STRATEGY_EXPIRATIONS, STRATEGY_PARENTS,
+    STRATEGY_RATE_LIMITS,
 };
-use crate::state::STRATEGIES;
+use crate::utils::ParamsJSON;
 
 pub(crate) const CONTRACT_NAME: &str = "crates.io:neutron-chain-manager";
 pub(crate) const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
@@ -46,27 +64,114 @@ pub fn instantiate(
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn execute(
     deps: DepsMut,
-    _env: Env,
+    env: Env,
     info: MessageInfo,
     msg: ExecuteMsg,
 ) -> Result<Response<NeutronMsg>, ContractError> {
     match msg {
-        ExecuteMsg::AddStrategy { address, strategy } => {
-            execute_add_strategy(deps, info, address, strategy)
-        }
+        ExecuteMsg::AddStrategy {
+            address,
+            strategy,
+            expiration,
+            rate_limit,
+            co_signing,
+        } => execute_add_strategy(
+            deps, env, info, address, strategy, expiration, rate_limit, co_signing,
+        ),
         ExecuteMsg::RemoveStrategy { address } => execute_remove_strategy(deps, info, address),
-        ExecuteMsg::ExecuteMessages { messages } => execute_execute_messages(deps, info, messages),
+        ExecuteMsg::ExecuteMessages { messages } => {
+            execute_execute_messages(deps, env, info, messages)
+        }
+        ExecuteMsg::PruneExpiredStrategies { start_after, limit } => {
+            execute_prune_expired_strategies(deps, env, start_after, limit)
+        }
+        ExecuteMsg::ApproveBatch { batch_id } => execute_approve_batch(deps, env, info, batch_id),
+        ExecuteMsg::RejectBatch { batch_id } => execute_reject_batch(deps, env, info, batch_id),
+        ExecuteMsg::ExpireBatch { batch_id } => execute_expire_batch(deps, env, batch_id),
+        ExecuteMsg::DelegateStrategy {
+            address,
+            permissions,
+            rate_limit,
+        } => execute_delegate_strategy(deps, env, info, address, permissions, rate_limit),
+        ExecuteMsg::RevokeDelegatedStrategy { address } => {
+            execute_revoke_delegated_strategy(deps, info, address)
+        }
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn execute_add_strategy(
     deps: DepsMut,
+    env: Env,
     info: MessageInfo,
     address: Addr,
     strategy: StrategyMsg,
+    expiration: Option<Expiration>,
+    rate_limit: Option<RateLimit>,
+    co_signing: Option<CoSigning>,
 ) -> Result<Response<NeutronMsg>, ContractError> {
     is_authorized(deps.as_ref(), info.sender.clone())?;
 
+    // The strategies delegated from the previous strategy might not be a
+    // subset of the new one, so they are removed. A strategy added by an
+    // ALLOW_ALL address is no longer a delegated one.
+    remove_delegated_strategies(deps.storage, address.clone())?;
+    remove_strategy_parent(deps.storage, address.clone())?;
+
+    // Only ALLOW_ONLY strategies can expire, otherwise we could end up with
+    // no usable ALLOW_ALL strategies left. Adding a strategy without an
+    // expiration clears the expiration of the previous strategy, if any.
+    match expiration {
+        Some(expiration) => {
+            if matches!(strategy, StrategyMsg::AllowAll) || expiration.is_expired(&env.block) {
+                return Err(ContractError::InvalidExpiration {});
+            }
+            STRATEGY_EXPIRATIONS.save(deps.storage, address.clone(), &expiration)?;
+        }
+        None => STRATEGY_EXPIRATIONS.remove(deps.storage, address.clone()),
+    }
+
+    // The same applies to rate limits. The recent executions are kept, so
+    // re-adding a strategy does not reset the rate limit window.
+    match rate_limit {
+        Some(rate_limit) => {
+            if matches!(strategy, StrategyMsg::AllowAll) || rate_limit.window_blocks == 0 {
+                return Err(ContractError::InvalidRateLimit {});
+            }
+            STRATEGY_RATE_LIMITS.save(deps.storage, address.clone(), &rate_limit)?;
+        }
+        None => STRATEGY_RATE_LIMITS.remove(deps.storage, address.clone()),
+    }
+
+    // And to co-signing, because the approvals of an ALLOW_ALL strategy could
+    // be bypassed by adding another strategy. Batches that are already pending
+    // keep the co-signers they were created with.
+    match co_signing {
+        Some(co_signing) => {
+            let mut co_signers = co_signing.co_signers.clone();
+            co_signers.sort();
+            co_signers.dedup();
+            if matches!(strategy, StrategyMsg::AllowAll)
+                || co_signing.threshold == 0
+                || co_signing.threshold > co_signers.len() as u64
+                || co_signers.len() != co_signing.co_signers.len()
+                || co_signing.deadline_blocks == 0
+            {
+                return Err(ContractError::InvalidCoSigning {});
+            }
+            STRATEGY_CO_SIGNING.save(deps.storage, address.clone(), &co_signing)?;
+        }
+        None => STRATEGY_CO_SIGNING.remove(deps.storage, address.clone()),
+    }
+
+    if let StrategyMsg::AllowOnly(permissions) = &strategy {
+        if permissions.iter().any(|permission| {
+            matches!(permission, Permission::BankSendPermission(p) if p.period_blocks == 0)
+        }) {
+            return Err(ContractError::InvalidBankSendPermission {});
+        }
+    }
+
     // We add the new strategy, and then we check that it did not replace
     // the only existing ALLOW_ALL strategy.
     STRATEGIES.save(deps.storage, address.clone(), &strategy.clone().into())?;
@@ -90,7 +195,7 @@ pub fn execute_remove_strategy(
 
     // First we remove the strategy, then we check that it was not the only
     // ALLOW_ALL strategy we had.
-    STRATEGIES.remove(deps.storage, address.clone());
+    remove_strategy(deps.storage, address.clone())?;
     if no_admins_left(deps.as_ref())? {
         return Err(ContractError::InvalidDemotion {});
     }
@@ -101,7 +206,8 @@ pub fn execute_remove_strategy(
 }
 
 pub fn execute_execute_messages(
-    deps: DepsMut,
+    mut deps: DepsMut,
+    env: Env,
     info: MessageInfo,
     messages: Vec<CosmosMsg<NeutronMsg>>,
 ) -> Result<Response<NeutronMsg>, ContractError> {
@@ -109,25 +215,745 @@ pub fn execute_execute_messages(
     if !STRATEGIES.has(deps.storage, info.sender.clone()) {
         return Err(ContractError::Unauthorized {});
     }
+    check_strategy_not_expired(deps.as_ref(), &env, info.sender.clone())?;
 
     let response = Response::new()
         .add_attribute("action", "execute_execute_messages")
         .add_attribute("address", info.sender.clone());
 
-    let strategy = STRATEGIES.load(deps.storage, info.sender)?;
+    let strategy = STRATEGIES.load(deps.storage, info.sender.clone())?;
     match strategy {
         Strategy::AllowAll => Ok(response
             .add_attribute("strategy", "allow_all")
             .add_messages(messages)),
         Strategy::AllowOnly(_) => {
-            check_allow_only_permissions(deps.as_ref(), strategy.clone(), messages.clone())?;
+            check_allow_only_permissions(deps.as_ref(), &env, strategy.clone(), messages.clone())?;
+
+            // The messages of co-signed strategies are only dispatched once
+            // enough co-signers have approved them.
+            if let Some(co_signing) =
+                STRATEGY_CO_SIGNING.may_load(deps.storage, info.sender.clone())?
+            {
+                let batch_id = create_batch(deps, &env, info.sender, co_signing, messages)?;
+                return Ok(response
+                    .add_attribute("strategy", "allow_only")
+                    .add_attribute("batch_id", batch_id.to_string()));
+            }
+
+            let audit_log_id =
+                dispatch_allow_only_messages(deps, &env, info.sender, &strategy, &messages)?;
             Ok(response
                 .add_attribute("strategy", "allow_only")
+                .add_attribute("audit_log_id", audit_log_id.to_string())
                 .add_messages(messages))
         }
     }
 }
 
+/// Applies the limits of an ALLOW_ONLY strategy that depend on the previous
+/// executions to the messages that are about to be dispatched, and records
+/// them in the audit log. Returns the ID of the audit log entry.
+fn dispatch_allow_only_messages(
+    mut deps: DepsMut,
+    env: &Env,
+    address: Addr,
+    strategy: &Strategy,
+    messages: &[CosmosMsg<NeutronMsg>],
+) -> Result<u64, ContractError> {
+    spend_bank_send_allowance(deps.branch(), env, address.clone(), strategy, messages)?;
+    check_rate_limit(deps.branch(), env, address.clone(), messages)?;
+    record_audit_log_entry(deps, env, address, strategy, messages)
+}
+
+/// Creates a pending batch with the messages of a co-signed strategy, and
+/// returns the ID of the batch.
+fn create_batch(
+    deps: DepsMut,
+    env: &Env,
+    proposer: Addr,
+    co_signing: CoSigning,
+    messages: Vec<CosmosMsg<NeutronMsg>>,
+) -> Result<u64, ContractError> {
+    let id = BATCH_NEXT_ID.may_load(deps.storage)?.unwrap_or_default();
+    BATCH_NEXT_ID.save(deps.storage, &(id + 1))?;
+
+    BATCHES.save(
+        deps.storage,
+        id,
+        &Batch {
+            id,
+            proposer,
+            messages,
+            co_signers: co_signing.co_signers,
+            threshold: co_signing.threshold,
+            approvals: vec![],
+            rejections: vec![],
+            expiration: Expiration::AtHeight(env.block.height + co_signing.deadline_blocks),
+            status: BatchStatus::Pending,
+        },
+    )?;
+
+    Ok(id)
+}
+
+pub fn execute_approve_batch(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    batch_id: u64,
+) -> Result<Response<NeutronMsg>, ContractError> {
+    let mut batch = load_batch_for_vote(deps.storage, &env, &info.sender, batch_id)?;
+    batch.approvals.push(info.sender.clone());
+
+    let mut response = Response::new()
+        .add_attribute("action", "execute_approve_batch")
+        .add_attribute("batch_id", batch_id.to_string())
+        .add_attribute("address", info.sender);
+
+    if batch.approvals.len() as u64 >= batch.threshold {
+        // The strategy of the proposer might have changed since the batch was
+        // created, so we check the messages once again before dispatching them.
+        let strategy = STRATEGIES
+            .may_load(deps.storage, batch.proposer.clone())?
+            .ok_or(ContractError::Unauthorized {})?;
+        check_strategy_not_expired(deps.as_ref(), &env, batch.proposer.clone())?;
+        check_allow_only_permissions(
+            deps.as_ref(),
+            &env,
+            strategy.clone(),
+            batch.messages.clone(),
+        )?;
+        let audit_log_id = dispatch_allow_only_messages(
+            deps.branch(),
+            &env,
+            batch.proposer.clone(),
+            &strategy,
+            &batch.messages,
+        )?;
+
+        batch.status = BatchStatus::Executed;
+        response = response
+            .add_attribute("audit_log_id", audit_log_id.to_string())
+            .add_messages(batch.messages.clone());
+    }
+
+    BATCHES.save(deps.storage, batch_id, &batch)?;
+
+    Ok(response)
+}
+
+pub fn execute_reject_batch(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    batch_id: u64,
+) -> Result<Response<NeutronMsg>, ContractError> {
+    let mut batch = load_batch_for_vote(deps.storage, &env, &info.sender, batch_id)?;
+    batch.rejections.push(info.sender.clone());
+
+    // The batch is rejected as soon as the co-signers that have not rejected it
+    // are not enough to reach the threshold.
+    if ((batch.co_signers.len() - batch.rejections.len()) as u64) < batch.threshold {
+        batch.status = BatchStatus::Rejected;
+    }
+
+    BATCHES.save(deps.storage, batch_id, &batch)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "execute_reject_batch")
+        .add_attribute("batch_id", batch_id.to_string())
+        .add_attribute("address", info.sender))
+}
+
+/// Marks a pending batch as expired. Expired batches can not be approved
+/// anyway, so anyone is allowed to do this.
+pub fn execute_expire_batch(
+    deps: DepsMut,
+    env: Env,
+    batch_id: u64,
+) -> Result<Response<NeutronMsg>, ContractError> {
+    let mut batch = BATCHES.load(deps.storage, batch_id)?;
+    if batch.status != BatchStatus::Pending {
+        return Err(ContractError::BatchNotPending {});
+    }
+    if !batch.expiration.is_expired(&env.block) {
+        return Err(ContractError::BatchNotExpired {});
+    }
+
+    batch.status = BatchStatus::Expired;
+    BATCHES.save(deps.storage, batch_id, &batch)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "execute_expire_batch")
+        .add_attribute("batch_id", batch_id.to_string()))
+}
+
+/// Loads a batch that the co-signer is about to approve or reject, and checks
+/// that the batch is still open and that the co-signer has not voted yet.
+fn load_batch_for_vote(
+    storage: &dyn Storage,
+    env: &Env,
+    co_signer: &Addr,
+    batch_id: u64,
+) -> Result<Batch, ContractError> {
+    let batch = BATCHES.load(storage, batch_id)?;
+    if batch.status != BatchStatus::Pending {
+        return Err(ContractError::BatchNotPending {});
+    }
+    if batch.expiration.is_expired(&env.block) {
+        return Err(ContractError::BatchExpired {});
+    }
+    if !batch.co_signers.contains(co_signer) {
+        return Err(ContractError::Unauthorized {});
+    }
+    if batch.approvals.contains(co_signer) || batch.rejections.contains(co_signer) {
+        return Err(ContractError::AlreadyVoted {});
+    }
+
+    Ok(batch)
+}
+
+/// Removes the strategies that have expired, along with their expirations,
+/// among the `limit` strategies with an expiration after `start_after`.
+/// Expired strategies can not be used anyway, so anyone is allowed to do this.
+pub fn execute_prune_expired_strategies(
+    deps: DepsMut,
+    env: Env,
+    start_after: Option<Addr>,
+    limit: Option<u32>,
+) -> Result<Response<NeutronMsg>, ContractError> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT);
+    let expirations: Vec<(Addr, Expiration)> = STRATEGY_EXPIRATIONS
+        .range(
+            deps.storage,
+            start_after.map(Bound::exclusive),
+            None,
+            Order::Ascending,
+        )
+        .take(limit as usize)
+        .collect::<StdResult<Vec<(Addr, Expiration)>>>()?;
+    let last_address = expirations.last().map(|(address, _)| address.clone());
+    let expired: Vec<Addr> = expirations
+        .into_iter()
+        .filter(|(_, expiration)| expiration.is_expired(&env.block))
+        .map(|(address, _)| address)
+        .collect();
+
+    for address in expired.iter() {
+        remove_strategy(deps.storage, address.clone())?;
+    }
+
+    let mut response = Response::new()
+        .add_attribute("action", "execute_prune_expired_strategies")
+        .add_attribute("pruned_count", expired.len().to_string());
+    if let Some(last_address) = last_address {
+        response = response.add_attribute("last_address", last_address);
+    }
+
+    Ok(response)
+}
+
+pub fn execute_delegate_strategy(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    address: Addr,
+    permissions: Vec<Permission>,
+    rate_limit: Option<RateLimit>,
+) -> Result<Response<NeutronMsg>, ContractError> {
+    let strategy = STRATEGIES
+        .may_load(deps.storage, info.sender.clone())?
+        .ok_or(ContractError::Unauthorized {})?;
+    check_strategy_not_expired(deps.as_ref(), &env, info.sender.clone())?;
+
+    // A strategy can only be delegated to an address without a strategy, or
+    // replace a strategy previously delegated by the sender.
+    if STRATEGIES.has(deps.storage, address.clone())
+        && STRATEGY_PARENTS.may_load(deps.storage, address.clone())? != Some(info.sender.clone())
+    {
+        return Err(ContractError::Unauthorized {});
+    }
+    if !permissions
+        .iter()
+        .all(|permission| strategy.contains_permission(permission))
+    {
+        return Err(ContractError::InvalidDelegation {});
+    }
+    if rate_limit
+        .as_ref()
+        .is_some_and(|rate_limit| rate_limit.window_blocks == 0)
+    {
+        return Err(ContractError::InvalidRateLimit {});
+    }
+
+    // The delegated strategies are removed along with the strategies they were
+    // delegated from, so their number is capped to keep the removal cheap.
+    if get_delegation_depth(deps.storage, info.sender.clone())? >= MAX_DELEGATION_DEPTH {
+        return Err(ContractError::DelegationTooDeep {
+            max: MAX_DELEGATION_DEPTH,
+        });
+    }
+    if !STRATEGY_CHILDREN.has(deps.storage, (info.sender.clone(), address.clone()))
+        && STRATEGY_CHILDREN
+            .prefix(info.sender.clone())
+            .keys(deps.storage, None, None, Order::Ascending)
+            .take(MAX_DELEGATED_STRATEGIES as usize)
+            .count()
+            >= MAX_DELEGATED_STRATEGIES as usize
+    {
+        return Err(ContractError::TooManyDelegatedStrategies {
+            max: MAX_DELEGATED_STRATEGIES,
+        });
+    }
+
+    // The strategies delegated from the previous delegated strategy might not
+    // be a subset of the new one, so we start from scratch.
+    remove_strategy(deps.storage, address.clone())?;
+    STRATEGIES.save(
+        deps.storage,
+        address.clone(),
+        &StrategyMsg::AllowOnly(permissions).into(),
+    )?;
+    STRATEGY_PARENTS.save(deps.storage, address.clone(), &info.sender)?;
+    STRATEGY_CHILDREN.save(
+        deps.storage,
+        (info.sender.clone(), address.clone()),
+        &Empty {},
+    )?;
+
+    // Otherwise the limits of the sender's strategy could be bypassed. The
+    // executions of delegated strategies are counted against the rate limits
+    // of all the strategies they were delegated from, so the rate limit can't
+    // be multiplied by delegating it several times. A delegated strategy can
+    // have a narrower rate limit of its own.
+    if let Some(expiration) = STRATEGY_EXPIRATIONS.may_load(deps.storage, info.sender.clone())? {
+        STRATEGY_EXPIRATIONS.save(deps.storage, address.clone(), &expiration)?;
+    }
+    let rate_limit = match rate_limit {
+        Some(rate_limit) => Some(rate_limit),
+        None => STRATEGY_RATE_LIMITS.may_load(deps.storage, info.sender.clone())?,
+    };
+    if let Some(rate_limit) = rate_limit {
+        STRATEGY_RATE_LIMITS.save(deps.storage, address.clone(), &rate_limit)?;
+    }
+    if let Some(co_signing) = STRATEGY_CO_SIGNING.may_load(deps.storage, info.sender.clone())? {
+        STRATEGY_CO_SIGNING.save(deps.storage, address.clone(), &co_signing)?;
+    }
+
+    Ok(Response::new()
+        .add_attribute("action", "execute_delegate_strategy")
+        .add_attribute("address", address)
+        .add_attribute("parent", info.sender))
+}
+
+pub fn execute_revoke_delegated_strategy(
+    deps: DepsMut,
+    info: MessageInfo,
+    address: Addr,
+) -> Result<Response<NeutronMsg>, ContractError> {
+    if STRATEGY_PARENTS.may_load(deps.storage, address.clone())? != Some(info.sender.clone()) {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    remove_strategy(deps.storage, address.clone())?;
+
+    Ok(Response::new()
+        .add_attribute("action", "execute_revoke_delegated_strategy")
+        .add_attribute("address", address))
+}
+
+/// Removes the strategy associated with the address along with all the data
+/// stored for it, and the strategies delegated from it.
+fn remove_strategy(storage: &mut dyn Storage, address: Addr) -> StdResult<()> {
+    remove_delegated_strategies(storage, address.clone())?;
+    remove_strategy_parent(storage, address.clone())?;
+    STRATEGIES.remove(storage, address.clone());
+    STRATEGY_EXPIRATIONS.remove(storage, address.clone());
+    STRATEGY_RATE_LIMITS.remove(storage, address.clone());
+    STRATEGY_EXECUTIONS.remove(storage, address.clone());
+    BANK_SEND_SPENDINGS.remove(storage, address.clone());
+    STRATEGY_CO_SIGNING.remove(storage, address);
+
+    Ok(())
+}
+
+/// Removes the strategies delegated from the address, recursively.
+fn remove_delegated_strategies(storage: &mut dyn Storage, address: Addr) -> StdResult<()> {
+    let children: Vec<Addr> = STRATEGY_CHILDREN
+        .prefix(address)
+        .keys(storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<Addr>>>()?;
+    for child in children {
+        remove_strategy(storage, child)?;
+    }
+
+    Ok(())
+}
+
+/// Removes the record of the address the strategy associated with the address
+/// was delegated from, if any.
+fn remove_strategy_parent(storage: &mut dyn Storage, address: Addr) -> StdResult<()> {
+    if let Some(parent) = STRATEGY_PARENTS.may_load(storage, address.clone())? {
+        STRATEGY_CHILDREN.remove(storage, (parent, address.clone()));
+        STRATEGY_PARENTS.remove(storage, address);
+    }
+
+    Ok(())
+}
+
+/// Records the tokens sent with the bank send permission of the strategy, and
+/// returns an error if the limits of the current period are exceeded.
+fn spend_bank_send_allowance(
+    deps: DepsMut,
+    env: &Env,
+    address: Addr,
+    strategy: &Strategy,
+    messages: &[CosmosMsg<NeutronMsg>],
+) -> Result<(), ContractError> {
+    let Some(permission) = strategy.get_bank_send_permission() else {
+        return Ok(());
+    };
+
+    let mut spending = load_bank_send_spending(deps.storage, env, address.clone(), &permission)?;
+    let mut has_sends = false;
+    for msg in messages {
+        if let CosmosMsg::Bank(BankMsg::Send { amount, .. }) = msg {
+            has_sends = true;
+            for coin in amount {
+                match spending
+                    .spent
+                    .iter_mut()
+                    .find(|spent| spent.denom == coin.denom)
+                {
+                    Some(spent) => spent.amount += coin.amount,
+                    None => spending.spent.push(coin.clone()),
+                }
+            }
+        }
+    }
+    if !has_sends {
+        return Ok(());
+    }
+
+    for spent in spending.spent.iter() {
+        let limit = permission
+            .limits
+            .iter()
+            .find(|limit| limit.denom == spent.denom)
+            .map(|limit| limit.amount)
+            .unwrap_or_default();
+        if spent.amount > limit {
+            return Err(ContractError::BankSendLimitExceeded {
+                denom: spent.denom.clone(),
+            });
+        }
+    }
+
+    BANK_SEND_SPENDINGS.save(deps.storage, address, &spending)?;
+
+    Ok(())
+}
+
+/// Loads the amounts spent in the current period. A new period starts once
+/// the previous one is over.
+fn load_bank_send_spending(
+    storage: &dyn Storage,
+    env: &Env,
+    address: Addr,
+    permission: &BankSendPermission,
+) -> StdResult<BankSendSpending> {
+    match BANK_SEND_SPENDINGS.may_load(storage, address)? {
+        Some(spending) if env.block.height < spending.period_start + permission.period_blocks => {
+            Ok(spending)
+        }
+        _ => Ok(BankSendSpending {
+            period_start: env.block.height,
+            spent: vec![],
+        }),
+    }
+}
+
+/// Returns the number of delegations the strategy associated with the address
+/// is away from a strategy added by an ALLOW_ALL address.
+fn get_delegation_depth(storage: &dyn Storage, address: Addr) -> StdResult<u32> {
+    let mut address = address;
+    let mut depth = 0;
+    while let Some(parent) = STRATEGY_PARENTS.may_load(storage, address.clone())? {
+        address = parent;
+        depth += 1;
+    }
+
+    Ok(depth)
+}
+
+/// Returns the address along with the addresses of the strategies the strategy
+/// associated with it was delegated from, up to the root strategy.
+fn get_delegation_chain(storage: &dyn Storage, address: Addr) -> StdResult<Vec<Addr>> {
+    let mut chain = vec![address.clone()];
+    let mut address = address;
+    while let Some(parent) = STRATEGY_PARENTS.may_load(storage, address.clone())? {
+        chain.push(parent.clone());
+        address = parent;
+    }
+
+    Ok(chain)
+}
+
+/// Records the execution for every rate limited strategy in the delegation
+/// chain, and returns an error if the execution does not fit into the rate
+/// limit window of any of them.
+fn check_rate_limit(
+    deps: DepsMut,
+    env: &Env,
+    address: Addr,
+    messages: &[CosmosMsg<NeutronMsg>],
+) -> Result<(), ContractError> {
+    let param_updates = count_param_updates(messages)?;
+
+    // Delegated strategies share the rate limit windows of the strategies they
+    // were delegated from, on top of their own one.
+    let mut updated_executions = vec![];
+    for address in get_delegation_chain(deps.storage, address)? {
+        let Some(rate_limit) = STRATEGY_RATE_LIMITS.may_load(deps.storage, address.clone())? else {
+            continue;
+        };
+
+        // The window consists of the last `window_blocks` blocks, including the
+        // current one.
+        let window_start = env.block.height.saturating_sub(rate_limit.window_blocks);
+        let mut executions: Vec<ExecutionRecord> = STRATEGY_EXECUTIONS
+            .may_load(deps.storage, address.clone())?
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|execution| execution.height > window_start)
+            .collect();
+        executions.push(ExecutionRecord {
+            height: env.block.height,
+            param_updates,
+        });
+
+        if let Some(max_executions) = rate_limit.max_executions {
+            if executions.len() as u64 > max_executions {
+                return Err(ContractError::RateLimitExceeded {});
+            }
+        }
+        if let Some(max_param_updates) = rate_limit.max_param_updates {
+            let param_updates: u64 = executions
+                .iter()
+                .map(|execution| execution.param_updates)
+                .sum();
+            if param_updates > max_param_updates {
+                return Err(ContractError::RateLimitExceeded {});
+            }
+        }
+
+        updated_executions.push((address, executions));
+    }
+
+    for (address, executions) in updated_executions {
+        STRATEGY_EXECUTIONS.save(deps.storage, address, &executions)?;
+    }
+
+    Ok(())
+}
+
+/// Counts the parameter updates in the messages: every `MsgUpdateParams`
+/// message and every legacy param change counts as one update.
+fn count_param_updates(messages: &[CosmosMsg<NeutronMsg>]) -> Result<u64, ContractError> {
+    let mut param_updates: u64 = 0;
+    for msg in messages {
+        if let CosmosMsg::Custom(NeutronMsg::SubmitAdminProposal { admin_proposal }) = msg {
+            match admin_proposal {
+                AdminProposal::ParamChangeProposal(proposal) => {
+                    param_updates += proposal.param_changes.len() as u64;
+                }
+                AdminProposal::ProposalExecuteMessage(proposal) => {
+                    let typed_proposal: ProposalExecuteMessageJSON =
+                        serde_json_wasm::from_str(proposal.message.as_str())?;
+                    if typed_proposal.type_field.ends_with(".MsgUpdateParams") {
+                        param_updates += 1;
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    Ok(param_updates)
+}
+
+/// Persists an audit log entry for the messages executed with an ALLOW_ONLY
+/// strategy, and returns the ID of the entry. The current parameter values are
+/// queried before the messages are dispatched, so they are the values before
+/// the update.
+fn record_audit_log_entry(
+    deps: DepsMut,
+    env: &Env,
+    sender: Addr,
+    strategy: &Strategy,
+    messages: &[CosmosMsg<NeutronMsg>],
+) -> Result<u64, ContractError> {
+    let mut type_urls: Vec<String> = vec![];
+    let mut param_updates: Vec<ParamUpdateRecord> = vec![];
+    for msg in messages {
+        type_urls.push(get_message_type_url(msg)?);
+        param_updates.extend(get_param_updates(deps.as_ref(), strategy, msg)?);
+    }
+
+    let id = AUDIT_LOG_NEXT_ID
+        .may_load(deps.storage)?
+        .unwrap_or_default();
+    AUDIT_LOG_NEXT_ID.save(deps.storage, &(id + 1))?;
+
+    AUDIT_LOG_BY_ADDRESS.save(deps.storage, (sender.clone(), id), &Empty {})?;
+    for type_url in type_urls.iter() {
+        AUDIT_LOG_BY_TYPE_URL.save(deps.storage, (type_url.clone(), id), &Empty {})?;
+    }
+    AUDIT_LOG.save(
+        deps.storage,
+        id,
+        &AuditLogEntry {
+            id,
+            sender,
+            height: env.block.height,
+            type_urls,
+            param_updates,
+        },
+    )?;
+
+    Ok(id)
+}
+
+/// Returns the type URL of a message that can be executed with an ALLOW_ONLY
+/// strategy.
+fn get_message_type_url(msg: &CosmosMsg<NeutronMsg>) -> Result<String, ContractError> {
+    let type_url = match msg {
+        CosmosMsg::Custom(NeutronMsg::AddSchedule { .. }) => cron::MsgAddSchedule::TYPE_URL,
+        CosmosMsg::Custom(NeutronMsg::RemoveSchedule { .. }) => cron::MsgRemoveSchedule::TYPE_URL,
+        CosmosMsg::Custom(NeutronMsg::SubmitAdminProposal { admin_proposal }) => {
+            match admin_proposal {
+                AdminProposal::ParamChangeProposal(_) => {
+                    "/cosmos.params.v1beta1.ParameterChangeProposal"
+                }
+                AdminProposal::ProposalExecuteMessage(proposal) => {
+                    let typed_proposal: ProposalExecuteMessageJSON =
+                        serde_json_wasm::from_str(proposal.message.as_str())?;
+                    return Ok(typed_proposal.type_field);
+                }
+                _ => return Err(ContractError::Unauthorized {}),
+            }
+        }
+        CosmosMsg::Bank(BankMsg::Send { .. }) => "/cosmos.bank.v1beta1.MsgSend",
+        CosmosMsg::Wasm(WasmMsg::Migrate { .. }) => "/cosmwasm.wasm.v1.MsgMigrateContract",
+        CosmosMsg::Wasm(WasmMsg::UpdateAdmin { .. }) => "/cosmwasm.wasm.v1.MsgUpdateAdmin",
+        CosmosMsg::Wasm(WasmMsg::ClearAdmin { .. }) => "/cosmwasm.wasm.v1.MsgClearAdmin",
+        _ => return Err(ContractError::Unauthorized {}),
+    };
+
+    Ok(type_url.to_string())
+}
+
+/// Returns the parameter values changed by the message, both the current ones
+/// and the proposed ones. The current parameters of modules without a dedicated
+/// permission are queried with the generic update params permission.
+fn get_param_updates(
+    deps: Deps,
+    strategy: &Strategy,
+    msg: &CosmosMsg<NeutronMsg>,
+) -> Result<Vec<ParamUpdateRecord>, ContractError> {
+    let CosmosMsg::Custom(NeutronMsg::SubmitAdminProposal { admin_proposal }) = msg else {
+        return Ok(vec![]);
+    };
+
+    match admin_proposal {
+        AdminProposal::ParamChangeProposal(proposal) => Ok(proposal
+            .param_changes
+            .iter()
+            .map(|param_change| ParamUpdateRecord {
+                param: format!("{}.{}", param_change.subspace, param_change.key),
+                before: None,
+                after: param_change.value.clone(),
+            })
+            .collect()),
+        AdminProposal::ProposalExecuteMessage(proposal) => {
+            let message = proposal.message.as_str();
+            let typed_proposal: ProposalExecuteMessageJSON = serde_json_wasm::from_str(message)?;
+            let (before, after) = match typed_proposal.type_field.as_str() {
+                cron::MsgUpdateParams::TYPE_URL => (
+                    to_json_string(&get_cron_params(deps)?.params)?,
+                    to_json_string(
+                        &serde_json_wasm::from_str::<cron::MsgUpdateParams>(message)?.params,
+                    )?,
+                ),
+                tokenfactory::v1beta1::MsgUpdateParams::TYPE_URL => (
+                    to_json_string(&get_tokenfactory_params(deps)?.params)?,
+                    to_json_string(
+                        &serde_json_wasm::from_str::<tokenfactory::v1beta1::MsgUpdateParams>(
+                            message,
+                        )?
+                        .params,
+                    )?,
+                ),
+                dex::MsgUpdateParams::TYPE_URL => (
+                    to_json_string(&get_dex_params(deps)?.params)?,
+                    to_json_string(
+                        &serde_json_wasm::from_str::<dex::MsgUpdateParams>(message)?.params,
+                    )?,
+                ),
+                dynamicfees::v1::MsgUpdateParams::TYPE_URL => (
+                    to_json_string(&get_dynamicfees_params(deps)?.params)?,
+                    to_json_string(
+                        &serde_json_wasm::from_str::<dynamicfees::v1::MsgUpdateParams>(message)?
+                            .params,
+                    )?,
+                ),
+                globalfee::v1beta1::MsgUpdateParams::TYPE_URL => (
+                    to_json_string(&get_globalfee_params(deps)?.params)?,
+                    to_json_string(
+                        &serde_json_wasm::from_str::<globalfee::v1beta1::MsgUpdateParams>(message)?
+                            .params,
+                    )?,
+                ),
+                consumer::v1::MsgUpdateParams::TYPE_URL => (
+                    to_json_string(&get_ccv_params(deps)?.params)?,
+                    to_json_string(
+                        &serde_json_wasm::from_str::<consumer::v1::MsgUpdateParams>(message)?
+                            .params,
+                    )?,
+                ),
+                type_url => match strategy.get_update_params_permission(type_url) {
+                    Some(update_params_permission) => (
+                        to_json_string(
+                            &get_generic_params(deps, update_params_permission.params_query_path)?
+                                .params,
+                        )?,
+                        to_json_string(&serde_json_wasm::from_str::<ParamsJSON>(message)?.params)?,
+                    ),
+                    None => return Ok(vec![]),
+                },
+            };
+
+            Ok(vec![ParamUpdateRecord {
+                param: typed_proposal.type_field,
+                before: Some(before),
+                after,
+            }])
+        }
+        _ => Ok(vec![]),
+    }
+}
+
+/// Returns an error if the strategy associated with the address has expired.
+fn check_strategy_not_expired(deps: Deps, env: &Env, address: Addr) -> Result<(), ContractError> {
+    if let Some(expiration) = STRATEGY_EXPIRATIONS.may_load(deps.storage, address)? {
+        if expiration.is_expired(&env.block) {
+            return Err(ContractError::StrategyExpired {});
+        }
+    }
+
+    Ok(())
+}
+
 fn is_authorized(deps: Deps, address: Addr) -> Result<(), ContractError> {
     match STRATEGIES.load(deps.storage, address) {
         Ok(Strategy::AllowAll) => Ok(()),
@@ -138,7 +964,7 @@ fn is_authorized(deps: Deps, address: Addr) -> Result<(), ContractError> {
 /// This function returns true if there is no more allow_all strategies left.
 fn no_admins_left(deps: Deps) -> Result<bool, ContractError> {
     let not_found: bool = !STRATEGIES
-        .range(deps.storage, None, None, cosmwasm_std::Order::Ascending)
+        .range(deps.storage, None, None, Order::Ascending)
         .collect::<Result<Vec<(Addr, Strategy)>, _>>()?
         .into_iter()
         .any(|(_, strategy)| matches!(strategy, Strategy::AllowAll));
@@ -151,38 +977,119 @@ fn no_admins_left(deps: Deps) -> Result<bool, ContractError> {
 /// unknown message aborts the execution.
 fn check_allow_only_permissions(
     deps: Deps,
+    env: &Env,
     strategy: Strategy,
     messages: Vec<CosmosMsg<NeutronMsg>>,
 ) -> Result<(), ContractError> {
     for msg in messages.clone() {
-        if let CosmosMsg::Custom(neutron_msg) = msg {
-            check_neutron_msg(deps, strategy.clone(), neutron_msg)?
-        } else {
-            return Err(ContractError::Unauthorized {});
+        check_allow_only_permission(deps, env, strategy.clone(), msg).map_err(to_execution_error)?
+    }
+
+    Ok(())
+}
+
+/// Checks whether we have the permission to execute a single message.
+fn check_allow_only_permission(
+    deps: Deps,
+    env: &Env,
+    strategy: Strategy,
+    msg: CosmosMsg<NeutronMsg>,
+) -> Result<(), ContractError> {
+    match msg {
+        CosmosMsg::Custom(neutron_msg) => check_neutron_msg(deps, env, strategy, neutron_msg),
+        CosmosMsg::Wasm(wasm_msg) => check_wasm_msg(strategy, wasm_msg),
+        CosmosMsg::Bank(BankMsg::Send { to_address, amount }) => {
+            check_bank_send_msg(strategy, to_address, amount)
+        }
+        _ => Err(ContractError::Unauthorized {}),
+    }
+}
+
+/// Checks the recipient and the denoms of a bank send message. The amounts are
+/// checked against the limits of the period when the message is executed.
+fn check_bank_send_msg(
+    strategy: Strategy,
+    to_address: String,
+    amount: Vec<Coin>,
+) -> Result<(), ContractError> {
+    let permission =
+        strategy
+            .get_bank_send_permission()
+            .ok_or_else(|| ContractError::MissingPermission {
+                permission: "bank_send_permission".to_string(),
+            })?;
+
+    if let Some(allowed_recipients) = permission.allowed_recipients {
+        if !allowed_recipients.contains(&to_address) {
+            return Err(ContractError::MissingPermission {
+                permission: "bank_send_permission.allowed_recipients".to_string(),
+            });
+        }
+    }
+
+    for coin in amount {
+        if !permission
+            .limits
+            .iter()
+            .any(|limit| limit.denom == coin.denom)
+        {
+            return Err(ContractError::MissingPermission {
+                permission: "bank_send_permission.limits".to_string(),
+            });
         }
     }
 
     Ok(())
 }
 
+/// Only contract admin messages are supported, for the contracts and
+/// code IDs listed in the wasm admin permission.
+fn check_wasm_msg(strategy: Strategy, wasm_msg: WasmMsg) -> Result<(), ContractError> {
+    let permission = match wasm_msg {
+        WasmMsg::Migrate { .. } => "wasm_admin_permission.migrate",
+        WasmMsg::UpdateAdmin { .. } => "wasm_admin_permission.update_admin",
+        WasmMsg::ClearAdmin { .. } => "wasm_admin_permission.clear_admin",
+        _ => return Err(ContractError::Unauthorized {}),
+    };
+
+    if !strategy.has_wasm_admin_permission(&wasm_msg) {
+        return Err(ContractError::MissingPermission {
+            permission: permission.to_string(),
+        });
+    }
+
+    Ok(())
+}
+
 fn check_neutron_msg(
     deps: Deps,
+    env: &Env,
     strategy: Strategy,
     neutron_msg: NeutronMsg,
 ) -> Result<(), ContractError> {
     match neutron_msg {
-        NeutronMsg::AddSchedule { .. } => {
-            if !strategy.has_cron_add_schedule_permission() {
-                return Err(ContractError::Unauthorized {});
-            }
+        NeutronMsg::AddSchedule {
+            name,
+            period,
+            msgs,
+            execution_stage,
+        } => {
+            let contracts: Vec<String> = msgs.into_iter().map(|msg| msg.contract).collect();
+            // Stages other than the begin blocker one are checked as the default end blocker stage.
+            let execution_stage = if cron::ExecutionStage::from_str_name(&execution_stage)
+                == Some(cron::ExecutionStage::BeginBlocker)
+            {
+                CronExecutionStage::BeginBlocker
+            } else {
+                CronExecutionStage::EndBlocker
+            };
+            check_cron_add_schedule(strategy, &name, period, &contracts, execution_stage)?;
         }
-        NeutronMsg::RemoveSchedule { name: _ } => {
-            if !strategy.has_cron_remove_schedule_permission() {
-                return Err(ContractError::Unauthorized {});
-            }
+        NeutronMsg::RemoveSchedule { name } => {
+            check_cron_remove_schedule(strategy, &name)?;
         }
         NeutronMsg::SubmitAdminProposal { admin_proposal } => {
-            check_submit_admin_proposal_message(deps, strategy, admin_proposal)?;
+            check_submit_admin_proposal_message(deps, env, strategy, admin_proposal)?;
         }
         _ => {
             return Err(ContractError::Unauthorized {});
@@ -194,19 +1101,25 @@ fn check_neutron_msg(
 
 fn check_submit_admin_proposal_message(
     deps: Deps,
+    env: &Env,
     strategy: Strategy,
     proposal: AdminProposal,
 ) -> Result<(), ContractError> {
     match proposal {
         AdminProposal::ParamChangeProposal(proposal) => {
             for param_change in proposal.param_changes {
-                if !strategy.has_param_change_permission(param_change) {
-                    return Err(ContractError::Unauthorized {});
+                if !strategy.has_param_change_permission(param_change.clone()) {
+                    return Err(ContractError::MissingPermission {
+                        permission: format!(
+                            "param_change_permission.{}.{}",
+                            param_change.subspace, param_change.key
+                        ),
+                    });
                 }
             }
         }
         AdminProposal::ProposalExecuteMessage(proposal) => {
-            check_proposal_execute_message(deps, strategy.clone(), proposal)?;
+            check_proposal_execute_message(deps, env, strategy.clone(), proposal)?;
         }
         _ => {
             return Err(ContractError::Unauthorized {});
@@ -221,6 +1134,7 @@ fn check_submit_admin_proposal_message(
 /// message.
 fn check_proposal_execute_message(
     deps: Deps,
+    env: &Env,
     strategy: Strategy,
     proposal: ProposalExecuteMessage,
 ) -> Result<(), ContractError> {
@@ -251,24 +1165,242 @@ fn check_proposal_execute_message(
             check_ccv_update_msg_params(deps, strategy, proposal)?;
             Ok(())
         }
-        cron::MsgAddSchedule::TYPE_URL => match strategy.has_cron_add_schedule_permission() {
-            true => Ok(()),
-            false => Err(ContractError::Unauthorized {}),
-        },
-        cron::MsgRemoveSchedule::TYPE_URL => match strategy.has_cron_remove_schedule_permission() {
-            true => Ok(()),
-            false => Err(ContractError::Unauthorized {}),
-        },
-        MsgSoftwareUpgrade::TYPE_URL => match strategy.has_software_upgrade_permission() {
-            true => Ok(()),
-            false => Err(ContractError::Unauthorized {}),
-        },
-        MsgCancelUpgrade::TYPE_URL => match strategy.has_cancel_software_upgrade_permission() {
-            true => Ok(()),
-            false => Err(ContractError::Unauthorized {}),
-        },
-        _ => Err(ContractError::Unauthorized {}),
+        cron::MsgAddSchedule::TYPE_URL => {
+            let msg_add_schedule =
+                serde_json_wasm::from_str::<cron::MsgAddSchedule>(proposal.message.as_str())?;
+            let contracts: Vec<String> = msg_add_schedule
+                .msgs
+                .into_iter()
+                .map(|msg| msg.contract)
+                .collect();
+            let execution_stage =
+                if msg_add_schedule.execution_stage == cron::ExecutionStage::BeginBlocker as i32 {
+                    CronExecutionStage::BeginBlocker
+                } else {
+                    CronExecutionStage::EndBlocker
+                };
+            check_cron_add_schedule(
+                strategy,
+                &msg_add_schedule.name,
+                msg_add_schedule.period,
+                &contracts,
+                execution_stage,
+            )
+        }
+        cron::MsgRemoveSchedule::TYPE_URL => {
+            let msg_remove_schedule =
+                serde_json_wasm::from_str::<cron::MsgRemoveSchedule>(proposal.message.as_str())?;
+            check_cron_remove_schedule(strategy, &msg_remove_schedule.name)
+        }
+        MsgSoftwareUpgrade::TYPE_URL => check_software_upgrade_msg(env, strategy, proposal),
+        MsgCancelUpgrade::TYPE_URL => check_cancel_upgrade_msg(deps, env, strategy),
+        type_url => check_generic_update_msg_params(deps, strategy, type_url, proposal),
+    }
+}
+
+/// Checks that the strategy owner is authorised to schedule the software upgrade,
+/// and that the upgrade plan satisfies the constraints of the permission.
+fn check_software_upgrade_msg(
+    env: &Env,
+    strategy: Strategy,
+    proposal: ProposalExecuteMessage,
+) -> Result<(), ContractError> {
+    if !strategy.has_software_upgrade_permission() {
+        return Err(ContractError::MissingPermission {
+            permission: "software_upgrade_permission.upgrade".to_string(),
+        });
+    }
+
+    let Some(constraints) = strategy.get_software_upgrade_constraints() else {
+        return Ok(());
+    };
+    let plan = serde_json_wasm::from_str::<MsgSoftwareUpgrade>(proposal.message.as_str())?
+        .plan
+        .ok_or(ContractError::Unauthorized {})?;
+
+    if let Some(min_upgrade_delay_blocks) = constraints.min_upgrade_delay_blocks {
+        // A delay past the maximum plan height can not be satisfied.
+        let min_upgrade_height = env
+            .block
+            .height
+            .checked_add(min_upgrade_delay_blocks)
+            .and_then(|height| i64::try_from(height).ok());
+        if min_upgrade_height.map_or(true, |min_upgrade_height| plan.height < min_upgrade_height) {
+            return Err(ContractError::SoftwareUpgradeConstraintViolation {
+                constraint: "min_upgrade_delay_blocks".to_string(),
+            });
+        }
+    }
+    if let Some(name_prefix) = constraints.name_prefix {
+        if !plan.name.starts_with(name_prefix.as_str()) {
+            return Err(ContractError::SoftwareUpgradeConstraintViolation {
+                constraint: "name_prefix".to_string(),
+            });
+        }
+    }
+
+    Ok(())
+}
+
+/// Checks that the strategy owner is authorised to cancel the software upgrade.
+/// If the permission has a freeze period, we query the current upgrade plan and
+/// forbid the cancellation when the upgrade height is too close.
+fn check_cancel_upgrade_msg(
+    deps: Deps,
+    env: &Env,
+    strategy: Strategy,
+) -> Result<(), ContractError> {
+    if !strategy.has_cancel_software_upgrade_permission() {
+        return Err(ContractError::MissingPermission {
+            permission: "software_upgrade_permission.cancel_upgrade".to_string(),
+        });
+    }
+
+    let Some(cancel_upgrade_freeze_blocks) = strategy
+        .get_software_upgrade_constraints()
+        .and_then(|constraints| constraints.cancel_upgrade_freeze_blocks)
+    else {
+        return Ok(());
+    };
+    if let Some(plan) = get_current_upgrade_plan(deps)?.plan {
+        // A freeze period past the maximum plan height covers every plan.
+        let max_frozen_plan_height = env
+            .block
+            .height
+            .checked_add(cancel_upgrade_freeze_blocks)
+            .and_then(|height| i64::try_from(height).ok());
+        if max_frozen_plan_height.map_or(true, |max_frozen_plan_height| {
+            plan.height <= max_frozen_plan_height
+        }) {
+            return Err(ContractError::SoftwareUpgradeConstraintViolation {
+                constraint: "cancel_upgrade_freeze_blocks".to_string(),
+            });
+        }
+    }
+
+    Ok(())
+}
+
+/// Queries the upgrade plan that is currently scheduled, if any.
+pub fn get_current_upgrade_plan(deps: Deps) -> StdResult<QueryCurrentPlanResponse> {
+    let upgrade_querier = UpgradeQuerier::new(&deps.querier);
+    upgrade_querier.current_plan()
+}
+
+/// Checks that the strategy owner is authorised to add the schedule, and that the
+/// schedule is within the scope of the cron permission.
+fn check_cron_add_schedule(
+    strategy: Strategy,
+    name: &str,
+    period: u64,
+    contracts: &[String],
+    execution_stage: CronExecutionStage,
+) -> Result<(), ContractError> {
+    if !strategy.has_cron_add_schedule_permission() {
+        return Err(ContractError::MissingPermission {
+            permission: "cron_permission.add_schedule".to_string(),
+        });
+    }
+
+    let scope = strategy.get_cron_permission_scope().unwrap_or_default();
+    check_cron_schedule_name(&scope, name)?;
+    if let Some(allowed_contracts) = scope.contracts {
+        if contracts
+            .iter()
+            .any(|contract| !allowed_contracts.contains(contract))
+        {
+            return Err(ContractError::MissingPermission {
+                permission: "cron_permission.scope.contracts".to_string(),
+            });
+        }
+    }
+    if let Some(execution_stages) = scope.execution_stages {
+        if !execution_stages.contains(&execution_stage) {
+            return Err(ContractError::MissingPermission {
+                permission: "cron_permission.scope.execution_stages".to_string(),
+            });
+        }
+    }
+    if scope
+        .min_period
+        .is_some_and(|min_period| period < min_period)
+        || scope
+            .max_period
+            .is_some_and(|max_period| period > max_period)
+    {
+        return Err(ContractError::MissingPermission {
+            permission: "cron_permission.scope.period".to_string(),
+        });
+    }
+
+    Ok(())
+}
+
+/// Checks that the strategy owner is authorised to remove the schedule. Only the
+/// name of the schedule is known, so only the name prefix of the scope applies.
+fn check_cron_remove_schedule(strategy: Strategy, name: &str) -> Result<(), ContractError> {
+    if !strategy.has_cron_remove_schedule_permission() {
+        return Err(ContractError::MissingPermission {
+            permission: "cron_permission.remove_schedule".to_string(),
+        });
     }
+
+    let scope = strategy.get_cron_permission_scope().unwrap_or_default();
+    check_cron_schedule_name(&scope, name)
+}
+
+fn check_cron_schedule_name(scope: &CronPermissionScope, name: &str) -> Result<(), ContractError> {
+    if let Some(name_prefix) = &scope.name_prefix {
+        if !name.starts_with(name_prefix.as_str()) {
+            return Err(ContractError::MissingPermission {
+                permission: "cron_permission.scope.name_prefix".to_string(),
+            });
+        }
+    }
+
+    Ok(())
+}
+
+/// Checks that the strategy owner is authorised to change the parameters of a
+/// module without a dedicated permission. We query the current parameters with a
+/// stargate query & compare every top-level field with the proposal; all modified
+/// fields must be listed in the permission. Messages without a permission for
+/// their type URL are not supported.
+fn check_generic_update_msg_params(
+    deps: Deps,
+    strategy: Strategy,
+    type_url: &str,
+    proposal: ProposalExecuteMessage,
+) -> Result<(), ContractError> {
+    let update_params_permission = strategy
+        .get_update_params_permission(type_url)
+        .ok_or(ContractError::Unauthorized {})?;
+    let msg_update_params =
+        serde_json_wasm::from_str::<ParamsJSON>(proposal.message.as_str())?.params;
+
+    let params = get_generic_params(deps, update_params_permission.params_query_path)?.params;
+    let fields: BTreeSet<&String> = params.keys().chain(msg_update_params.keys()).collect();
+    for field in fields {
+        if params.get(field) != msg_update_params.get(field)
+            && !update_params_permission.fields.contains(field)
+        {
+            return Err(ContractError::MissingPermission {
+                permission: format!("update_params_permission.{}.{}", type_url, field),
+            });
+        }
+    }
+
+    Ok(())
+}
+
+/// Queries the parameters of a module with a stargate query. Stargate queries
+/// return JSON, so the parameters can be compared without knowing their type.
+pub fn get_generic_params(deps: Deps, path: String) -> StdResult<ParamsJSON> {
+    #[allow(deprecated)]
+    deps.querier.query(&QueryRequest::Stargate {
+        path,
+        data: Binary::default(),
+    })
 }
 
 /// Checks that the strategy owner is authorised to change the parameters of the
@@ -283,19 +1415,31 @@ fn check_cron_update_msg_params(
         serde_json_wasm::from_str::<cron::MsgUpdateParams>(proposal.message.as_str())?
             .params
             .ok_or(ContractError::Unauthorized {})?;
-    let cron_update_param_permission = strategy
-        .get_cron_update_param_permission()
-        .ok_or(ContractError::Unauthorized {})?;
+    let cron_update_param_permission =
+        strategy.get_cron_update_param_permission().ok_or_else(|| {
+            ContractError::MissingPermission {
+                permission: "update_cron_params_permission".to_string(),
+            }
+        })?;
 
     let cron_params = get_cron_params(deps)?.params.unwrap_or_default();
     if cron_params.limit != msg_update_params.limit && !cron_update_param_permission.limit {
-        return Err(ContractError::Unauthorized {});
+        return Err(ContractError::MissingPermission {
+            permission: "update_cron_params_permission.limit".to_string(),
+        });
     }
 
     if cron_params.security_address != msg_update_params.security_address
         && !cron_update_param_permission.security_address
     {
-        return Err(ContractError::Unauthorized {});
+        return Err(ContractError::MissingPermission {
+            permission: "update_cron_params_permission.security_address".to_string(),
+        });
+    }
+
+    let constraints = cron_update_param_permission.constraints.unwrap_or_default();
+    if cron_params.limit != msg_update_params.limit {
+        check_numeric_constraint("limit", &constraints.limit, msg_update_params.limit)?;
     }
 
     Ok(())
@@ -322,32 +1466,43 @@ fn check_tokenfactory_update_msg_params(
     .ok_or(ContractError::Unauthorized {})?;
     let tokenfactory_update_param_permission = strategy
         .get_tokenfactory_update_param_permission()
-        .ok_or(ContractError::Unauthorized {})?;
+        .ok_or_else(|| ContractError::MissingPermission {
+            permission: "update_tokenfactory_params_permission".to_string(),
+        })?;
 
     let tokenfactory_params = get_tokenfactory_params(deps)?.params.unwrap_or_default();
     if tokenfactory_params.denom_creation_fee != msg_update_params.denom_creation_fee
         && !tokenfactory_update_param_permission.denom_creation_fee
     {
-        return Err(ContractError::Unauthorized {});
+        return Err(ContractError::MissingPermission {
+            permission: "update_tokenfactory_params_permission.denom_creation_fee".to_string(),
+        });
     }
 
     if tokenfactory_params.denom_creation_gas_consume
         != msg_update_params.denom_creation_gas_consume
         && !tokenfactory_update_param_permission.denom_creation_gas_consume
     {
-        return Err(ContractError::Unauthorized {});
+        return Err(ContractError::MissingPermission {
+            permission: "update_tokenfactory_params_permission.denom_creation_gas_consume"
+                .to_string(),
+        });
     }
 
     if tokenfactory_params.fee_collector_address != msg_update_params.fee_collector_address
         && !tokenfactory_update_param_permission.fee_collector_address
     {
-        return Err(ContractError::Unauthorized {});
+        return Err(ContractError::MissingPermission {
+            permission: "update_tokenfactory_params_permission.fee_collector_address".to_string(),
+        });
     }
 
     if tokenfactory_params.whitelisted_hooks != msg_update_params.whitelisted_hooks
         && !tokenfactory_update_param_permission.whitelisted_hooks
     {
-        return Err(ContractError::Unauthorized {});
+        return Err(ContractError::MissingPermission {
+            permission: "update_tokenfactory_params_permission.whitelisted_hooks".to_string(),
+        });
     }
 
     Ok(())
@@ -374,34 +1529,68 @@ fn check_dex_update_msg_params(
             .params
             .ok_or(ContractError::Unauthorized {})?;
 
-    let dex_update_param_permission = strategy
-        .get_dex_update_param_permission()
-        .ok_or(ContractError::Unauthorized {})?;
+    let dex_update_param_permission =
+        strategy.get_dex_update_param_permission().ok_or_else(|| {
+            ContractError::MissingPermission {
+                permission: "update_dex_params_permission".to_string(),
+            }
+        })?;
 
     let dex_params = get_dex_params(deps)?.params.unwrap_or_default();
 
     if dex_params.fee_tiers != msg_update_params.fee_tiers && !dex_update_param_permission.fee_tiers
     {
-        return Err(ContractError::Unauthorized {});
+        return Err(ContractError::MissingPermission {
+            permission: "update_dex_params_permission.fee_tiers".to_string(),
+        });
     }
     if dex_params.paused != msg_update_params.paused && !dex_update_param_permission.paused {
-        return Err(ContractError::Unauthorized {});
+        return Err(ContractError::MissingPermission {
+            permission: "update_dex_params_permission.paused".to_string(),
+        });
     }
     if dex_params.max_jits_per_block != msg_update_params.max_jits_per_block
         && !dex_update_param_permission.max_jits_per_block
     {
-        return Err(ContractError::Unauthorized {});
+        return Err(ContractError::MissingPermission {
+            permission: "update_dex_params_permission.max_jits_per_block".to_string(),
+        });
     }
     if dex_params.good_til_purge_allowance != msg_update_params.good_til_purge_allowance
         && !dex_update_param_permission.good_til_purge_allowance
     {
-        return Err(ContractError::Unauthorized {});
+        return Err(ContractError::MissingPermission {
+            permission: "update_dex_params_permission.good_til_purge_allowance".to_string(),
+        });
     }
 
     if dex_params.whitelisted_lps != msg_update_params.whitelisted_lps
         && !dex_update_param_permission.whitelisted_lps
     {
-        return Err(ContractError::Unauthorized {});
+        return Err(ContractError::MissingPermission {
+            permission: "update_dex_params_permission.whitelisted_lps".to_string(),
+        });
+    }
+
+    let constraints = dex_update_param_permission.constraints.unwrap_or_default();
+    if dex_params.fee_tiers != msg_update_params.fee_tiers {
+        for fee_tier in msg_update_params.fee_tiers.iter() {
+            check_numeric_constraint("fee_tiers", &constraints.fee_tiers, *fee_tier)?;
+        }
+    }
+    if dex_params.max_jits_per_block != msg_update_params.max_jits_per_block {
+        check_numeric_constraint(
+            "max_jits_per_block",
+            &constraints.max_jits_per_block,
+            msg_update_params.max_jits_per_block,
+        )?;
+    }
+    if dex_params.good_til_purge_allowance != msg_update_params.good_til_purge_allowance {
+        check_numeric_constraint(
+            "good_til_purge_allowance",
+            &constraints.good_til_purge_allowance,
+            msg_update_params.good_til_purge_allowance,
+        )?;
     }
 
     Ok(())
@@ -428,14 +1617,29 @@ fn check_dynamicfees_update_msg_params(
 
     let dynamicfees_update_param_permission = strategy
         .get_dynamicfees_update_param_permission()
-        .ok_or(ContractError::Unauthorized {})?;
+        .ok_or_else(|| ContractError::MissingPermission {
+            permission: "update_dynamicfees_params_permission".to_string(),
+        })?;
 
     let dynamicfees_params = get_dynamicfees_params(deps)?.params.unwrap_or_default();
 
     if dynamicfees_params.ntrn_prices != msg_update_params.ntrn_prices
         && !dynamicfees_update_param_permission.ntrn_prices
     {
-        return Err(ContractError::Unauthorized {});
+        return Err(ContractError::MissingPermission {
+            permission: "update_dynamicfees_params_permission.ntrn_prices".to_string(),
+        });
+    }
+
+    let constraints = dynamicfees_update_param_permission
+        .constraints
+        .unwrap_or_default();
+    if dynamicfees_params.ntrn_prices != msg_update_params.ntrn_prices {
+        check_price_constraints(
+            "ntrn_prices",
+            &constraints.ntrn_prices,
+            &msg_update_params.ntrn_prices,
+        )?;
     }
 
     Ok(())
@@ -463,27 +1667,69 @@ fn check_globalfee_update_msg_params(
 
     let globalfee_update_param_permission = strategy
         .get_globalfee_update_param_permission()
-        .ok_or(ContractError::Unauthorized {})?;
+        .ok_or_else(|| ContractError::MissingPermission {
+            permission: "update_globalfee_params_permission".to_string(),
+        })?;
 
     let globalfee_params = get_globalfee_params(deps)?.params.unwrap_or_default();
 
     if globalfee_params.bypass_min_fee_msg_types != msg_update_params.bypass_min_fee_msg_types
         && !globalfee_update_param_permission.bypass_min_fee_msg_types
     {
-        return Err(ContractError::Unauthorized {});
+        return Err(ContractError::MissingPermission {
+            permission: "update_globalfee_params_permission.bypass_min_fee_msg_types".to_string(),
+        });
     }
 
     if globalfee_params.max_total_bypass_min_fee_msg_gas_usage
         != msg_update_params.max_total_bypass_min_fee_msg_gas_usage
         && !globalfee_update_param_permission.max_total_bypass_min_fee_msg_gas_usage
     {
-        return Err(ContractError::Unauthorized {});
+        return Err(ContractError::MissingPermission {
+            permission: "update_globalfee_params_permission.max_total_bypass_min_fee_msg_gas_usage"
+                .to_string(),
+        });
     }
 
     if globalfee_params.minimum_gas_prices != msg_update_params.minimum_gas_prices
         && !globalfee_update_param_permission.minimum_gas_prices
     {
-        return Err(ContractError::Unauthorized {});
+        return Err(ContractError::MissingPermission {
+            permission: "update_globalfee_params_permission.minimum_gas_prices".to_string(),
+        });
+    }
+
+    let constraints = globalfee_update_param_permission
+        .constraints
+        .unwrap_or_default();
+    if globalfee_params.bypass_min_fee_msg_types != msg_update_params.bypass_min_fee_msg_types {
+        if let Some(allowed_msg_types) = constraints.bypass_min_fee_msg_types {
+            if !msg_update_params
+                .bypass_min_fee_msg_types
+                .iter()
+                .all(|msg_type| allowed_msg_types.contains(msg_type))
+            {
+                return Err(ContractError::ParamConstraintViolation {
+                    param: "bypass_min_fee_msg_types".to_string(),
+                });
+            }
+        }
+    }
+    if globalfee_params.max_total_bypass_min_fee_msg_gas_usage
+        != msg_update_params.max_total_bypass_min_fee_msg_gas_usage
+    {
+        check_numeric_constraint(
+            "max_total_bypass_min_fee_msg_gas_usage",
+            &constraints.max_total_bypass_min_fee_msg_gas_usage,
+            msg_update_params.max_total_bypass_min_fee_msg_gas_usage,
+        )?;
+    }
+    if globalfee_params.minimum_gas_prices != msg_update_params.minimum_gas_prices {
+        check_price_constraints(
+            "minimum_gas_prices",
+            &constraints.minimum_gas_prices,
+            &msg_update_params.minimum_gas_prices,
+        )?;
     }
 
     Ok(())
@@ -509,80 +1755,157 @@ fn check_ccv_update_msg_params(
             .params
             .ok_or(ContractError::Unauthorized {})?;
 
-    let ccv_update_param_permission = strategy
-        .get_ccv_update_param_permission()
-        .ok_or(ContractError::Unauthorized {})?;
+    let ccv_update_param_permission =
+        strategy.get_ccv_update_param_permission().ok_or_else(|| {
+            ContractError::MissingPermission {
+                permission: "update_ccv_params_permission".to_string(),
+            }
+        })?;
 
     let ccv_params = get_ccv_params(deps)?.params.unwrap_or_default();
 
     // never allow to change 'enabled'
     if ccv_params.enabled != msg_update_params.enabled {
-        return Err(ContractError::Unauthorized {});
+        return Err(ContractError::MissingPermission {
+            permission: "update_ccv_params_permission.enabled".to_string(),
+        });
     }
 
     if ccv_params.blocks_per_distribution_transmission
         != msg_update_params.blocks_per_distribution_transmission
         && !ccv_update_param_permission.blocks_per_distribution_transmission
     {
-        return Err(ContractError::Unauthorized {});
+        return Err(ContractError::MissingPermission {
+            permission: "update_ccv_params_permission.blocks_per_distribution_transmission"
+                .to_string(),
+        });
     }
 
     if ccv_params.ccv_timeout_period != msg_update_params.ccv_timeout_period
         && !ccv_update_param_permission.ccv_timeout_period
     {
-        return Err(ContractError::Unauthorized {});
+        return Err(ContractError::MissingPermission {
+            permission: "update_ccv_params_permission.ccv_timeout_period".to_string(),
+        });
     }
     if ccv_params.consumer_redistribution_fraction
         != msg_update_params.consumer_redistribution_fraction
         && !ccv_update_param_permission.consumer_redistribution_fraction
     {
-        return Err(ContractError::Unauthorized {});
+        return Err(ContractError::MissingPermission {
+            permission: "update_ccv_params_permission.consumer_redistribution_fraction".to_string(),
+        });
     }
     if ccv_params.distribution_transmission_channel
         != msg_update_params.distribution_transmission_channel
         && !ccv_update_param_permission.distribution_transmission_channel
     {
-        return Err(ContractError::Unauthorized {});
+        return Err(ContractError::MissingPermission {
+            permission: "update_ccv_params_permission.distribution_transmission_channel"
+                .to_string(),
+        });
     }
     if ccv_params.historical_entries != msg_update_params.historical_entries
         && !ccv_update_param_permission.historical_entries
     {
-        return Err(ContractError::Unauthorized {});
+        return Err(ContractError::MissingPermission {
+            permission: "update_ccv_params_permission.historical_entries".to_string(),
+        });
     }
     if ccv_params.provider_fee_pool_addr_str != msg_update_params.provider_fee_pool_addr_str
         && !ccv_update_param_permission.provider_fee_pool_addr_str
     {
-        return Err(ContractError::Unauthorized {});
+        return Err(ContractError::MissingPermission {
+            permission: "update_ccv_params_permission.provider_fee_pool_addr_str".to_string(),
+        });
     }
     if ccv_params.provider_reward_denoms != msg_update_params.provider_reward_denoms
         && !ccv_update_param_permission.provider_reward_denoms
     {
-        return Err(ContractError::Unauthorized {});
+        return Err(ContractError::MissingPermission {
+            permission: "update_ccv_params_permission.provider_reward_denoms".to_string(),
+        });
     }
     if ccv_params.retry_delay_period != msg_update_params.retry_delay_period
         && !ccv_update_param_permission.retry_delay_period
     {
-        return Err(ContractError::Unauthorized {});
+        return Err(ContractError::MissingPermission {
+            permission: "update_ccv_params_permission.retry_delay_period".to_string(),
+        });
     }
     if ccv_params.reward_denoms != msg_update_params.reward_denoms
         && !ccv_update_param_permission.reward_denoms
     {
-        return Err(ContractError::Unauthorized {});
+        return Err(ContractError::MissingPermission {
+            permission: "update_ccv_params_permission.reward_denoms".to_string(),
+        });
     }
     if ccv_params.soft_opt_out_threshold != msg_update_params.soft_opt_out_threshold
         && !ccv_update_param_permission.soft_opt_out_threshold
     {
-        return Err(ContractError::Unauthorized {});
+        return Err(ContractError::MissingPermission {
+            permission: "update_ccv_params_permission.soft_opt_out_threshold".to_string(),
+        });
     }
     if ccv_params.transfer_timeout_period != msg_update_params.transfer_timeout_period
         && !ccv_update_param_permission.transfer_timeout_period
     {
-        return Err(ContractError::Unauthorized {});
+        return Err(ContractError::MissingPermission {
+            permission: "update_ccv_params_permission.transfer_timeout_period".to_string(),
+        });
     }
     if ccv_params.unbonding_period != msg_update_params.unbonding_period
         && !ccv_update_param_permission.unbonding_period
     {
-        return Err(ContractError::Unauthorized {});
+        return Err(ContractError::MissingPermission {
+            permission: "update_ccv_params_permission.unbonding_period".to_string(),
+        });
+    }
+
+    let constraints = ccv_update_param_permission.constraints.unwrap_or_default();
+    if ccv_params.blocks_per_distribution_transmission
+        != msg_update_params.blocks_per_distribution_transmission
+    {
+        check_signed_numeric_constraint(
+            "blocks_per_distribution_transmission",
+            &constraints.blocks_per_distribution_transmission,
+            msg_update_params.blocks_per_distribution_transmission,
+        )?;
+    }
+    if ccv_params.ccv_timeout_period != msg_update_params.ccv_timeout_period {
+        check_duration_constraint(
+            "ccv_timeout_period",
+            &constraints.ccv_timeout_period,
+            &msg_update_params.ccv_timeout_period,
+        )?;
+    }
+    if ccv_params.transfer_timeout_period != msg_update_params.transfer_timeout_period {
+        check_duration_constraint(
+            "transfer_timeout_period",
+            &constraints.transfer_timeout_period,
+            &msg_update_params.transfer_timeout_period,
+        )?;
+    }
+    if ccv_params.historical_entries != msg_update_params.historical_entries {
+        check_signed_numeric_constraint(
+            "historical_entries",
+            &constraints.historical_entries,
+            msg_update_params.historical_entries,
+        )?;
+    }
+    if ccv_params.unbonding_period != msg_update_params.unbonding_period {
+        check_duration_constraint(
+            "unbonding_period",
+            &constraints.unbonding_period,
+            &msg_update_params.unbonding_period,
+        )?;
+    }
+    if ccv_params.retry_delay_period != msg_update_params.retry_delay_period {
+        check_duration_constraint(
+            "retry_delay_period",
+            &constraints.retry_delay_period,
+            &msg_update_params.retry_delay_period,
+        )?;
     }
 
     Ok(())
@@ -594,30 +1917,302 @@ pub fn get_ccv_params(deps: Deps) -> StdResult<consumer::v1::QueryParamsResponse
     ccv_querier.query_params()
 }
 
+/// Checks that the new value of a numeric parameter satisfies the constraint
+/// of the permission, if there is one.
+fn check_numeric_constraint(
+    param: &str,
+    constraint: &Option<NumericConstraint>,
+    value: u64,
+) -> Result<(), ContractError> {
+    match constraint {
+        Some(constraint) if !constraint.allows(value) => {
+            Err(ContractError::ParamConstraintViolation {
+                param: param.to_string(),
+            })
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Same as `check_numeric_constraint`, for parameters of a signed type. Negative
+/// values never satisfy a constraint.
+fn check_signed_numeric_constraint(
+    param: &str,
+    constraint: &Option<NumericConstraint>,
+    value: i64,
+) -> Result<(), ContractError> {
+    match u64::try_from(value) {
+        Ok(value) => check_numeric_constraint(param, constraint, value),
+        Err(_) if constraint.is_some() => Err(ContractError::ParamConstraintViolation {
+            param: param.to_string(),
+        }),
+        Err(_) => Ok(()),
+    }
+}
+
+/// Checks the number of whole seconds of a duration parameter against the
+/// constraint of the permission. An unset duration never satisfies a constraint.
+fn check_duration_constraint(
+    param: &str,
+    constraint: &Option<NumericConstraint>,
+    duration: &Option<Duration>,
+) -> Result<(), ContractError> {
+    match duration {
+        Some(duration) => check_signed_numeric_constraint(param, constraint, duration.seconds),
+        None if constraint.is_some() => Err(ContractError::ParamConstraintViolation {
+            param: param.to_string(),
+        }),
+        None => Ok(()),
+    }
+}
+
+/// Checks that every new price satisfies the constraint defined for its denom,
+/// if the permission has price constraints. Prices in denoms without a constraint
+/// are rejected.
+fn check_price_constraints(
+    param: &str,
+    constraints: &Option<Vec<PriceConstraint>>,
+    prices: &[DecCoin],
+) -> Result<(), ContractError> {
+    let Some(constraints) = constraints else {
+        return Ok(());
+    };
+
+    for price in prices {
+        let amount = Decimal::from_str(price.amount.as_str())?;
+        let allowed = constraints
+            .iter()
+            .find(|constraint| constraint.denom == price.denom)
+            .is_some_and(|constraint| constraint.allows(amount));
+        if !allowed {
+            return Err(ContractError::ParamConstraintViolation {
+                param: param.to_string(),
+            });
+        }
+    }
+
+    Ok(())
+}
+
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
     match msg {
         QueryMsg::Strategies {} => to_json_binary(&query_strategies(deps)?),
+        QueryMsg::CheckMessages { address, messages } => {
+            to_json_binary(&query_check_messages(deps, env, address, messages)?)
+        }
+        QueryMsg::BankSendAllowance { address } => {
+            to_json_binary(&query_bank_send_allowance(deps, env, address)?)
+        }
+        QueryMsg::AuditLogByAddress {
+            address,
+            start_after,
+            limit,
+        } => to_json_binary(&query_audit_log_by_address(
+            deps,
+            address,
+            start_after,
+            limit,
+        )?),
+        QueryMsg::AuditLogByTypeUrl {
+            type_url,
+            start_after,
+            limit,
+        } => to_json_binary(&query_audit_log_by_type_url(
+            deps,
+            type_url,
+            start_after,
+            limit,
+        )?),
+        QueryMsg::Batch { batch_id } => to_json_binary(&BATCHES.load(deps.storage, batch_id)?),
+        QueryMsg::Batches { start_after, limit } => {
+            to_json_binary(&query_batches(deps, start_after, limit)?)
+        }
     }
 }
 
 /// No pagination is added because it's unlikely that there is going
-/// to be more than 10 strategies.
-pub fn query_strategies(deps: Deps) -> StdResult<Vec<(Addr, StrategyMsg)>> {
-    let all_strategies: Vec<(Addr, StrategyMsg)> = STRATEGIES
-        .range(deps.storage, None, None, cosmwasm_std::Order::Ascending)
-        .map(|v| match v {
-            Ok((addr, Strategy::AllowAll)) => Ok((addr, StrategyMsg::AllowAll)),
-            Ok((addr, Strategy::AllowOnly(permissions))) => Ok((
-                addr,
-                StrategyMsg::AllowOnly(permissions.values().cloned().collect::<Vec<Permission>>()),
-            )),
-            Err(e) => Err(e),
+/// to be more than 10 strategies. Expired strategies that have not been
+/// pruned yet are returned as well, along with their expiration.
+pub fn query_strategies(deps: Deps) -> StdResult<Vec<StrategyResponse>> {
+    let all_strategies: Vec<StrategyResponse> = STRATEGIES
+        .range(deps.storage, None, None, Order::Ascending)
+        .map(|v| {
+            let (address, strategy) = v?;
+            let expiration = STRATEGY_EXPIRATIONS.may_load(deps.storage, address.clone())?;
+            let rate_limit = STRATEGY_RATE_LIMITS.may_load(deps.storage, address.clone())?;
+            let co_signing = STRATEGY_CO_SIGNING.may_load(deps.storage, address.clone())?;
+            let parent = STRATEGY_PARENTS.may_load(deps.storage, address.clone())?;
+            Ok(StrategyResponse {
+                address,
+                strategy: strategy.into(),
+                expiration,
+                rate_limit,
+                co_signing,
+                parent,
+            })
         })
-        .collect::<Result<Vec<(Addr, StrategyMsg)>, _>>()?;
+        .collect::<StdResult<Vec<StrategyResponse>>>()?;
     Ok(all_strategies)
 }
 
+/// Runs the same checks as `execute_execute_messages` for every message without
+/// executing anything, and returns a verdict for each message.
+pub fn query_check_messages(
+    deps: Deps,
+    env: Env,
+    address: Addr,
+    messages: Vec<CosmosMsg<NeutronMsg>>,
+) -> StdResult<Vec<MessageCheckResponse>> {
+    let strategy = match STRATEGIES.may_load(deps.storage, address.clone())? {
+        Some(strategy) => check_strategy_not_expired(deps, &env, address).map(|_| strategy),
+        None => Err(ContractError::Unauthorized {}),
+    };
+
+    let checks = match strategy {
+        Ok(Strategy::AllowAll) => vec![to_message_check(Ok(())); messages.len()],
+        Ok(strategy) => messages
+            .into_iter()
+            .map(|msg| {
+                to_message_check(check_allow_only_permission(
+                    deps,
+                    &env,
+                    strategy.clone(),
+                    msg,
+                ))
+            })
+            .collect(),
+        // Strategy-level errors apply to every message.
+        Err(err) => vec![to_message_check(Err(err)); messages.len()],
+    };
+    Ok(checks)
+}
+
+/// Returns the amounts the address can still send in the current period.
+pub fn query_bank_send_allowance(
+    deps: Deps,
+    env: Env,
+    address: Addr,
+) -> StdResult<BankSendAllowanceResponse> {
+    let permission = STRATEGIES
+        .may_load(deps.storage, address.clone())?
+        .and_then(|strategy| strategy.get_bank_send_permission());
+    let Some(permission) = permission else {
+        return Ok(BankSendAllowanceResponse {
+            remaining: vec![],
+            period_end_height: None,
+        });
+    };
+
+    let spending = load_bank_send_spending(deps.storage, &env, address, &permission)?;
+    let remaining = permission
+        .limits
+        .iter()
+        .map(|limit| {
+            let spent = spending
+                .spent
+                .iter()
+                .find(|spent| spent.denom == limit.denom)
+                .map(|spent| spent.amount)
+                .unwrap_or_default();
+            Coin {
+                denom: limit.denom.clone(),
+                amount: limit.amount.saturating_sub(spent),
+            }
+        })
+        .collect();
+
+    Ok(BankSendAllowanceResponse {
+        remaining,
+        period_end_height: Some(spending.period_start + permission.period_blocks),
+    })
+}
+
+pub fn query_audit_log_by_address(
+    deps: Deps,
+    address: Addr,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<Vec<AuditLogEntry>> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT);
+    AUDIT_LOG_BY_ADDRESS
+        .prefix(address)
+        .keys(
+            deps.storage,
+            start_after.map(Bound::exclusive),
+            None,
+            Order::Ascending,
+        )
+        .take(limit as usize)
+        .map(|id| AUDIT_LOG.load(deps.storage, id?))
+        .collect()
+}
+
+pub fn query_audit_log_by_type_url(
+    deps: Deps,
+    type_url: String,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<Vec<AuditLogEntry>> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT);
+    AUDIT_LOG_BY_TYPE_URL
+        .prefix(type_url)
+        .keys(
+            deps.storage,
+            start_after.map(Bound::exclusive),
+            None,
+            Order::Ascending,
+        )
+        .take(limit as usize)
+        .map(|id| AUDIT_LOG.load(deps.storage, id?))
+        .collect()
+}
+
+pub fn query_batches(
+    deps: Deps,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<Vec<Batch>> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT);
+    BATCHES
+        .range(
+            deps.storage,
+            start_after.map(Bound::exclusive),
+            None,
+            Order::Ascending,
+        )
+        .take(limit as usize)
+        .map(|item| item.map(|(_, batch)| batch))
+        .collect()
+}
+
+/// The missing permission is only reported by the `check_messages` query, the
+/// execution fails with `Unauthorized` as it always did.
+fn to_execution_error(err: ContractError) -> ContractError {
+    match err {
+        ContractError::MissingPermission { .. } => ContractError::Unauthorized {},
+        err => err,
+    }
+}
+
+fn to_message_check(result: Result<(), ContractError>) -> MessageCheckResponse {
+    match result {
+        Ok(()) => MessageCheckResponse {
+            allowed: true,
+            missing_permission: None,
+            error: None,
+        },
+        Err(err) => MessageCheckResponse {
+            allowed: false,
+            missing_permission: match &err {
+                ContractError::MissingPermission { permission } => Some(permission.clone()),
+                _ => None,
+            },
+            error: Some(to_execution_error(err).to_string()),
+        },
+    }
+}
+
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn migrate(deps: DepsMut, _env: Env, _msg: MigrateMsg) -> Result<Response, ContractError> {
     // Set contract to version to latest
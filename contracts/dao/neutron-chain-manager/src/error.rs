@@ -12,11 +12,80 @@ pub enum ContractError {
     #[error("Unauthorized")]
     Unauthorized {},
 
+    // This error is reported by the `check_messages` query when the strategy lacks
+    // the permission (or a specific field of the permission) required to execute a
+    // message. The execution itself fails with `Unauthorized`.
+    #[error("Unauthorized: missing permission {permission}")]
+    MissingPermission { permission: String },
+
     // This error is returned when you try to remove the only existing
     // ALLOW_ALL strategy.
     #[error("An invalid demotion was attempted")]
     InvalidDemotion {},
 
+    // This error is returned when an expiration is set for an ALLOW_ALL
+    // strategy, or when the expiration is already in the past.
+    #[error("Invalid strategy expiration")]
+    InvalidExpiration {},
+
+    #[error("Strategy has expired")]
+    StrategyExpired {},
+
+    // This error is returned when a rate limit is set for an ALLOW_ALL
+    // strategy, or when the rate limit window is empty.
+    #[error("Invalid strategy rate limit")]
+    InvalidRateLimit {},
+
+    #[error("Strategy rate limit exceeded")]
+    RateLimitExceeded {},
+
+    // This error is returned when co-signing is set for an ALLOW_ALL strategy,
+    // when the threshold is zero or higher than the number of co-signers, when
+    // a co-signer is listed twice, or when the deadline is empty.
+    #[error("Invalid strategy co-signing")]
+    InvalidCoSigning {},
+
+    // This error is returned when the delegated permissions are not a subset
+    // of the permissions of the delegator.
+    #[error("Delegated permissions must be a subset of the delegator permissions")]
+    InvalidDelegation {},
+
+    #[error("A strategy can't be delegated to more than {max} addresses")]
+    TooManyDelegatedStrategies { max: u32 },
+
+    #[error("A strategy can't be delegated more than {max} times in a row")]
+    DelegationTooDeep { max: u32 },
+
+    #[error("Batch is not pending")]
+    BatchNotPending {},
+
+    #[error("Batch has expired")]
+    BatchExpired {},
+
+    #[error("Batch has not expired yet")]
+    BatchNotExpired {},
+
+    #[error("Co-signer has already voted on the batch")]
+    AlreadyVoted {},
+
+    #[error("Bank send limit exceeded for denom {denom}")]
+    BankSendLimitExceeded { denom: String },
+
+    // This error is returned when the period of a bank send permission is
+    // empty, so the spent amounts would never be reset.
+    #[error("Invalid bank send permission")]
+    InvalidBankSendPermission {},
+
+    // This error is returned when a parameter is changed to a value that
+    // violates the constraints of the permission.
+    #[error("New value of parameter {param} violates the permission constraints")]
+    ParamConstraintViolation { param: String },
+
+    // This error is returned when a software upgrade is scheduled or cancelled
+    // against the constraints of the permission.
+    #[error("Software upgrade violates the permission constraint {constraint}")]
+    SoftwareUpgradeConstraintViolation { constraint: String },
+
     // A variant for serde_json_wasm deserialization errors.
     #[error("Deserialization error: {0}")]
     DeserializationError(String),
@@ -1,7 +1,8 @@
 use std::collections::HashMap;
 
 use cosmwasm_schema::{cw_serde, QueryResponses};
-use cosmwasm_std::{Addr, CosmosMsg};
+use cosmwasm_std::{Addr, Coin, CosmosMsg, Decimal, WasmMsg};
+use cw_utils::Expiration;
 use neutron_sdk::bindings::msg::{NeutronMsg, ParamChange};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
@@ -19,6 +20,16 @@ pub enum ExecuteMsg {
     AddStrategy {
         address: Addr,
         strategy: StrategyMsg,
+        /// Defines the moment after which the strategy can no longer be used.
+        /// Only ALLOW_ONLY strategies can expire.
+        expiration: Option<Expiration>,
+        /// Limits how often the strategy can be used. Only ALLOW_ONLY strategies
+        /// can be rate limited.
+        rate_limit: Option<RateLimit>,
+        /// Requires the messages executed with the strategy to be approved by
+        /// a quorum of co-signers before they are dispatched. Only ALLOW_ONLY
+        /// strategies can be co-signed.
+        co_signing: Option<CoSigning>,
     },
     RemoveStrategy {
         address: Addr,
@@ -26,13 +37,189 @@ pub enum ExecuteMsg {
     ExecuteMessages {
         messages: Vec<CosmosMsg<NeutronMsg>>,
     },
+    /// Removes the expired strategies among the `limit` strategies with an
+    /// expiration after `start_after`, ordered by address. The last address
+    /// checked is returned in the `last_address` attribute, so that the next
+    /// page can start after it. Can be called by anyone.
+    PruneExpiredStrategies {
+        start_after: Option<Addr>,
+        limit: Option<u32>,
+    },
+    /// Approves a pending batch. The messages of the batch are dispatched once
+    /// the approval threshold is reached.
+    ApproveBatch {
+        batch_id: u64,
+    },
+    /// Rejects a pending batch. The batch is rejected once the threshold can
+    /// no longer be reached.
+    RejectBatch {
+        batch_id: u64,
+    },
+    /// Marks a pending batch past its expiration as expired. Can be called
+    /// by anyone.
+    ExpireBatch {
+        batch_id: u64,
+    },
+    /// Grants a subset of the permissions of the sender's strategy to another
+    /// address. The delegated strategy inherits the expiration and co-signing
+    /// of the sender's strategy, and its rate limit unless `rate_limit` is set.
+    /// Its executions count against the rate limits of the strategies it was
+    /// delegated from as well. The number of strategies delegated
+    /// from a strategy and the depth of the delegations are capped.
+    DelegateStrategy {
+        address: Addr,
+        permissions: Vec<Permission>,
+        rate_limit: Option<RateLimit>,
+    },
+    /// Removes a strategy delegated by the sender, along with the strategies
+    /// delegated from it.
+    RevokeDelegatedStrategy {
+        address: Addr,
+    },
 }
 
 #[cw_serde]
 #[derive(QueryResponses)]
 pub enum QueryMsg {
-    #[returns(Vec < StrategyMsg >)]
+    #[returns(Vec < StrategyResponse >)]
     Strategies {},
+    /// Checks whether the given address is allowed to execute the messages,
+    /// without executing them. Returns a verdict for every message.
+    #[returns(Vec < MessageCheckResponse >)]
+    CheckMessages {
+        address: Addr,
+        messages: Vec<CosmosMsg<NeutronMsg>>,
+    },
+    /// Returns the amounts the given address can still send with its bank send
+    /// permission in the current period.
+    #[returns(BankSendAllowanceResponse)]
+    BankSendAllowance { address: Addr },
+    /// Returns the audit log entries of the executions made by the given address,
+    /// oldest first.
+    #[returns(Vec < AuditLogEntry >)]
+    AuditLogByAddress {
+        address: Addr,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+    /// Returns the audit log entries of the executions that contained a message
+    /// of the given type URL, oldest first.
+    #[returns(Vec < AuditLogEntry >)]
+    AuditLogByTypeUrl {
+        type_url: String,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+    #[returns(Batch)]
+    Batch { batch_id: u64 },
+    #[returns(Vec < Batch >)]
+    Batches {
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+}
+
+/// Defines the co-signers that have to approve the messages executed with a
+/// strategy before they are dispatched.
+#[cw_serde]
+#[serde(rename_all = "snake_case")]
+pub struct CoSigning {
+    pub co_signers: Vec<Addr>,
+    /// The number of approvals required to dispatch the messages.
+    pub threshold: u64,
+    /// The number of blocks after which a batch that has not been approved
+    /// expires.
+    pub deadline_blocks: u64,
+}
+
+/// Messages executed with a co-signed strategy, waiting for the approval of
+/// the co-signers. The co-signers and the threshold are those of the strategy
+/// at the moment the batch was created.
+#[cw_serde]
+pub struct Batch {
+    pub id: u64,
+    pub proposer: Addr,
+    pub messages: Vec<CosmosMsg<NeutronMsg>>,
+    pub co_signers: Vec<Addr>,
+    pub threshold: u64,
+    pub approvals: Vec<Addr>,
+    pub rejections: Vec<Addr>,
+    pub expiration: Expiration,
+    pub status: BatchStatus,
+}
+
+#[cw_serde]
+pub enum BatchStatus {
+    Pending,
+    Executed,
+    Rejected,
+    Expired,
+}
+
+/// A successful execution of messages made with an ALLOW_ONLY strategy.
+#[cw_serde]
+pub struct AuditLogEntry {
+    pub id: u64,
+    pub sender: Addr,
+    pub height: u64,
+    /// The type URLs of the executed messages, in execution order.
+    pub type_urls: Vec<String>,
+    pub param_updates: Vec<ParamUpdateRecord>,
+}
+
+/// The values of the parameters changed by a message, as JSON.
+#[cw_serde]
+pub struct ParamUpdateRecord {
+    /// The type URL of a `MsgUpdateParams` message, or `subspace.key` for legacy
+    /// param changes.
+    pub param: String,
+    /// The value before the update. Not known for legacy param changes.
+    pub before: Option<String>,
+    pub after: String,
+}
+
+#[cw_serde]
+pub struct BankSendAllowanceResponse {
+    pub remaining: Vec<Coin>,
+    /// The height at which the spent amounts are reset. Not set if the address
+    /// has no bank send permission.
+    pub period_end_height: Option<u64>,
+}
+
+#[cw_serde]
+pub struct StrategyResponse {
+    pub address: Addr,
+    pub strategy: StrategyMsg,
+    /// The moment after which the strategy can no longer be used, if any.
+    pub expiration: Option<Expiration>,
+    pub rate_limit: Option<RateLimit>,
+    pub co_signing: Option<CoSigning>,
+    /// The address that delegated the strategy, if any.
+    pub parent: Option<Addr>,
+}
+
+/// Defines how many times a strategy can be used within a rolling window
+/// of blocks.
+#[cw_serde]
+#[serde(rename_all = "snake_case")]
+pub struct RateLimit {
+    /// The length of the rolling window in blocks.
+    pub window_blocks: u64,
+    /// The maximum number of `ExecuteMessages` calls within the window.
+    pub max_executions: Option<u64>,
+    /// The maximum number of parameter updates within the window. Every
+    /// `MsgUpdateParams` message and every legacy param change counts as one.
+    pub max_param_updates: Option<u64>,
+}
+
+#[cw_serde]
+pub struct MessageCheckResponse {
+    pub allowed: bool,
+    /// The permission (or the field of the permission) that is missing
+    /// to execute the message, if any.
+    pub missing_permission: Option<String>,
+    /// The error that `execute_messages` would fail with.
+    pub error: Option<String>,
 }
 
 #[cw_serde]
@@ -71,7 +258,29 @@ impl From<StrategyMsg> for Strategy {
     }
 }
 
+impl From<Strategy> for StrategyMsg {
+    fn from(value: Strategy) -> Self {
+        match value {
+            Strategy::AllowAll => StrategyMsg::AllowAll,
+            Strategy::AllowOnly(permissions) => {
+                StrategyMsg::AllowOnly(permissions.values().cloned().collect::<Vec<Permission>>())
+            }
+        }
+    }
+}
+
 impl Strategy {
+    /// Returns true if the permission is a subset of the permissions of the
+    /// strategy, i.e. if the strategy can delegate it.
+    pub fn contains_permission(&self, permission: &Permission) -> bool {
+        match self {
+            Strategy::AllowAll => true,
+            Strategy::AllowOnly(permissions) => permissions
+                .get(&PermissionType::from(permission.clone()))
+                .is_some_and(|own_permission| permission.is_subset_of(own_permission)),
+        }
+    }
+
     pub fn has_cron_add_schedule_permission(&self) -> bool {
         match self {
             Strategy::AllowAll => true,
@@ -94,6 +303,19 @@ impl Strategy {
             }
         }
     }
+    /// ALLOW_ALL strategies can manage any schedule, so there is no scope to
+    /// return for them.
+    pub fn get_cron_permission_scope(&self) -> Option<CronPermissionScope> {
+        match self {
+            Strategy::AllowAll => None,
+            Strategy::AllowOnly(permissions) => {
+                match permissions.get(&PermissionType::CronPermission) {
+                    Some(Permission::CronPermission(permission)) => permission.scope.clone(),
+                    _ => None,
+                }
+            }
+        }
+    }
     pub fn has_param_change_permission(&self, param_change: ParamChange) -> bool {
         match self {
             Strategy::AllowAll => true,
@@ -115,11 +337,79 @@ impl Strategy {
         }
     }
 
+    pub fn has_wasm_admin_permission(&self, wasm_msg: &WasmMsg) -> bool {
+        match self {
+            Strategy::AllowAll => true,
+            Strategy::AllowOnly(permissions) => {
+                match permissions.get(&PermissionType::WasmAdminPermission) {
+                    Some(Permission::WasmAdminPermission(wasm_admin_permission)) => {
+                        match wasm_msg {
+                            WasmMsg::Migrate {
+                                contract_addr,
+                                new_code_id,
+                                ..
+                            } => {
+                                wasm_admin_permission.migrate
+                                    && wasm_admin_permission.contracts.contains(contract_addr)
+                                    && wasm_admin_permission.code_ids.contains(new_code_id)
+                            }
+                            WasmMsg::UpdateAdmin { contract_addr, .. } => {
+                                wasm_admin_permission.update_admin
+                                    && wasm_admin_permission.contracts.contains(contract_addr)
+                            }
+                            WasmMsg::ClearAdmin { contract_addr } => {
+                                wasm_admin_permission.clear_admin
+                                    && wasm_admin_permission.contracts.contains(contract_addr)
+                            }
+                            _ => false,
+                        }
+                    }
+                    _ => false,
+                }
+            }
+        }
+    }
+
+    /// ALLOW_ALL strategies can change any parameter without a query path to
+    /// fetch the current ones, so there is no permission to return for them.
+    pub fn get_update_params_permission(&self, type_url: &str) -> Option<UpdateParamsPermission> {
+        match self {
+            Strategy::AllowAll => None,
+            Strategy::AllowOnly(permissions) => {
+                match permissions.get(&PermissionType::UpdateParamsPermission(
+                    type_url.to_string(),
+                )) {
+                    Some(Permission::UpdateParamsPermission(update_params_permission)) => {
+                        Some(update_params_permission.clone())
+                    }
+                    _ => None,
+                }
+            }
+        }
+    }
+
+    /// ALLOW_ALL strategies are not limited in what they can send, so there is
+    /// no permission to return for them.
+    pub fn get_bank_send_permission(&self) -> Option<BankSendPermission> {
+        match self {
+            Strategy::AllowAll => None,
+            Strategy::AllowOnly(permissions) => {
+                match permissions.get(&PermissionType::BankSendPermission) {
+                    Some(Permission::BankSendPermission(bank_send_permission)) => {
+                        Some(bank_send_permission.clone())
+                    }
+                    _ => None,
+                }
+            }
+        }
+    }
+
     pub fn get_cron_update_param_permission(&self) -> Option<CronUpdateParamsPermission> {
         match self {
             Strategy::AllowAll => Some(CronUpdateParamsPermission {
                 security_address: true,
                 limit: true,
+                constraints: None,
             }),
             Strategy::AllowOnly(permissions) => {
                 match permissions.get(&PermissionType::UpdateCronParamsPermission) {
@@ -161,6 +451,7 @@ impl Strategy {
                 max_jits_per_block: true,
                 good_til_purge_allowance: true,
                 whitelisted_lps: true,
+                constraints: None,
             }),
             Strategy::AllowOnly(permissions) => {
                 match permissions.get(&PermissionType::UpdateDexParamsPermission) {
@@ -177,7 +468,10 @@ impl Strategy {
         &self,
     ) -> Option<DynamicFeesUpdateParamsPermission> {
         match self {
-            Strategy::AllowAll => Some(DynamicFeesUpdateParamsPermission { ntrn_prices: true }),
+            Strategy::AllowAll => Some(DynamicFeesUpdateParamsPermission {
+                ntrn_prices: true,
+                constraints: None,
+            }),
             Strategy::AllowOnly(permissions) => {
                 match permissions.get(&PermissionType::UpdateDynamicfeesParamsPermission) {
                     Some(Permission::UpdateDynamicfeesParamsPermission(
@@ -195,6 +489,7 @@ impl Strategy {
                 minimum_gas_prices: true,
                 bypass_min_fee_msg_types: true,
                 max_total_bypass_min_fee_msg_gas_usage: true,
+                constraints: None,
             }),
             Strategy::AllowOnly(permissions) => {
                 match permissions.get(&PermissionType::UpdateGlobalfeeParamsPermission) {
@@ -222,6 +517,7 @@ impl Strategy {
                 reward_denoms: true,
                 provider_reward_denoms: true,
                 retry_delay_period: true,
+                constraints: None,
             }),
             Strategy::AllowOnly(permissions) => {
                 match permissions.get(&PermissionType::UpdateCCVParamsPermission) {
@@ -248,6 +544,22 @@ impl Strategy {
         }
     }
 
+    /// ALLOW_ALL strategies are not constrained, so there are no constraints
+    /// to return for them.
+    pub fn get_software_upgrade_constraints(&self) -> Option<SoftwareUpgradeConstraints> {
+        match self {
+            Strategy::AllowAll => None,
+            Strategy::AllowOnly(permissions) => {
+                match permissions.get(&PermissionType::SoftwareUpgradePermission) {
+                    Some(Permission::SoftwareUpgradePermission(software_upgrade_params)) => {
+                        software_upgrade_params.constraints.clone()
+                    }
+                    _ => None,
+                }
+            }
+        }
+    }
+
     pub fn has_cancel_software_upgrade_permission(&self) -> bool {
         match self {
             Strategy::AllowAll => true,
@@ -278,6 +590,136 @@ pub enum Permission {
     UpdateCCVParamsPermission(CCVUpdateParamsPermission),
     CronPermission(CronPermission),
     SoftwareUpgradePermission(SoftwareUpgradePermission),
+    WasmAdminPermission(WasmAdminPermission),
+    BankSendPermission(BankSendPermission),
+    // For new-style parameter updates of modules without a dedicated permission.
+    UpdateParamsPermission(UpdateParamsPermission),
+}
+
+impl Permission {
+    /// Returns true if everything allowed by this permission is also allowed by
+    /// the other one. Constraints and scopes are only considered narrower if they
+    /// are the same as the other ones, or if the other permission has none. Bank
+    /// send permissions are never a subset, because the spent amounts are
+    /// tracked per address.
+    pub fn is_subset_of(&self, other: &Permission) -> bool {
+        match (self, other) {
+            (Permission::ParamChangePermission(p), Permission::ParamChangePermission(o)) => {
+                p.params.iter().all(|param| o.params.contains(param))
+            }
+            (
+                Permission::UpdateCronParamsPermission(p),
+                Permission::UpdateCronParamsPermission(o),
+            ) => {
+                implies(p.security_address, o.security_address)
+                    && implies(p.limit, o.limit)
+                    && is_narrower(&p.constraints, &o.constraints)
+            }
+            (
+                Permission::UpdateTokenfactoryParamsPermission(p),
+                Permission::UpdateTokenfactoryParamsPermission(o),
+            ) => {
+                implies(p.denom_creation_fee, o.denom_creation_fee)
+                    && implies(p.denom_creation_gas_consume, o.denom_creation_gas_consume)
+                    && implies(p.fee_collector_address, o.fee_collector_address)
+                    && implies(p.whitelisted_hooks, o.whitelisted_hooks)
+            }
+            (
+                Permission::UpdateDexParamsPermission(p),
+                Permission::UpdateDexParamsPermission(o),
+            ) => {
+                implies(p.fee_tiers, o.fee_tiers)
+                    && implies(p.paused, o.paused)
+                    && implies(p.max_jits_per_block, o.max_jits_per_block)
+                    && implies(p.good_til_purge_allowance, o.good_til_purge_allowance)
+                    && implies(p.whitelisted_lps, o.whitelisted_lps)
+                    && is_narrower(&p.constraints, &o.constraints)
+            }
+            (
+                Permission::UpdateDynamicfeesParamsPermission(p),
+                Permission::UpdateDynamicfeesParamsPermission(o),
+            ) => {
+                implies(p.ntrn_prices, o.ntrn_prices) && is_narrower(&p.constraints, &o.constraints)
+            }
+            (
+                Permission::UpdateGlobalfeeParamsPermission(p),
+                Permission::UpdateGlobalfeeParamsPermission(o),
+            ) => {
+                implies(p.minimum_gas_prices, o.minimum_gas_prices)
+                    && implies(p.bypass_min_fee_msg_types, o.bypass_min_fee_msg_types)
+                    && implies(
+                        p.max_total_bypass_min_fee_msg_gas_usage,
+                        o.max_total_bypass_min_fee_msg_gas_usage,
+                    )
+                    && is_narrower(&p.constraints, &o.constraints)
+            }
+            (
+                Permission::UpdateCCVParamsPermission(p),
+                Permission::UpdateCCVParamsPermission(o),
+            ) => {
+                implies(
+                    p.blocks_per_distribution_transmission,
+                    o.blocks_per_distribution_transmission,
+                ) && implies(
+                    p.distribution_transmission_channel,
+                    o.distribution_transmission_channel,
+                ) && implies(p.provider_fee_pool_addr_str, o.provider_fee_pool_addr_str)
+                    && implies(p.ccv_timeout_period, o.ccv_timeout_period)
+                    && implies(p.transfer_timeout_period, o.transfer_timeout_period)
+                    && implies(
+                        p.consumer_redistribution_fraction,
+                        o.consumer_redistribution_fraction,
+                    )
+                    && implies(p.historical_entries, o.historical_entries)
+                    && implies(p.unbonding_period, o.unbonding_period)
+                    && implies(p.soft_opt_out_threshold, o.soft_opt_out_threshold)
+                    && implies(p.reward_denoms, o.reward_denoms)
+                    && implies(p.provider_reward_denoms, o.provider_reward_denoms)
+                    && implies(p.retry_delay_period, o.retry_delay_period)
+                    && is_narrower(&p.constraints, &o.constraints)
+            }
+            (Permission::CronPermission(p), Permission::CronPermission(o)) => {
+                implies(p.add_schedule, o.add_schedule)
+                    && implies(p.remove_schedule, o.remove_schedule)
+                    && is_narrower(&p.scope, &o.scope)
+            }
+            (
+                Permission::SoftwareUpgradePermission(p),
+                Permission::SoftwareUpgradePermission(o),
+            ) => {
+                implies(p.upgrade, o.upgrade)
+                    && implies(p.cancel_upgrade, o.cancel_upgrade)
+                    && is_narrower(&p.constraints, &o.constraints)
+            }
+            (Permission::WasmAdminPermission(p), Permission::WasmAdminPermission(o)) => {
+                implies(p.migrate, o.migrate)
+                    && implies(p.update_admin, o.update_admin)
+                    && implies(p.clear_admin, o.clear_admin)
+                    && p.contracts
+                        .iter()
+                        .all(|contract| o.contracts.contains(contract))
+                    && p.code_ids
+                        .iter()
+                        .all(|code_id| o.code_ids.contains(code_id))
+            }
+            (Permission::UpdateParamsPermission(p), Permission::UpdateParamsPermission(o)) => {
+                p.type_url == o.type_url
+                    && p.params_query_path == o.params_query_path
+                    && p.fields.iter().all(|field| o.fields.contains(field))
+            }
+            _ => false,
+        }
+    }
+}
+
+/// Returns true if a flag of a permission allows at most what the flag of
+/// another permission allows.
+fn implies(flag: bool, other_flag: bool) -> bool {
+    !flag || other_flag
+}
+
+fn is_narrower<T: PartialEq>(constraints: &Option<T>, other_constraints: &Option<T>) -> bool {
+    other_constraints.is_none() || constraints == other_constraints
 }
 
 impl From<Permission> for PermissionType {
@@ -298,6 +740,11 @@ impl From<Permission> for PermissionType {
                 PermissionType::UpdateGlobalfeeParamsPermission
             }
             Permission::UpdateCCVParamsPermission(_) => PermissionType::UpdateCCVParamsPermission,
+            Permission::WasmAdminPermission(_) => PermissionType::WasmAdminPermission,
+            Permission::BankSendPermission(_) => PermissionType::BankSendPermission,
+            Permission::UpdateParamsPermission(permission) => {
+                PermissionType::UpdateParamsPermission(permission.type_url)
+            }
         }
     }
 }
@@ -314,6 +761,11 @@ pub enum PermissionType {
     UpdateCCVParamsPermission,
     CronPermission,
     SoftwareUpgradePermission,
+    WasmAdminPermission,
+    BankSendPermission,
+    // Generic update params permissions are keyed by the type URL of the
+    // `MsgUpdateParams` message, so a strategy can hold one per module.
+    UpdateParamsPermission(String),
 }
 
 #[cw_serde]
@@ -337,6 +789,14 @@ pub struct ParamPermission {
 pub struct CronUpdateParamsPermission {
     pub security_address: bool,
     pub limit: bool,
+    pub constraints: Option<CronUpdateParamsConstraints>,
+}
+
+#[cw_serde]
+#[derive(Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub struct CronUpdateParamsConstraints {
+    pub limit: Option<NumericConstraint>,
 }
 
 #[cw_serde]
@@ -345,6 +805,31 @@ pub struct CronUpdateParamsPermission {
 pub struct CronPermission {
     pub add_schedule: bool,
     pub remove_schedule: bool,
+    /// Restricts the schedules that can be added or removed. Without a scope,
+    /// any schedule can be added or removed.
+    pub scope: Option<CronPermissionScope>,
+}
+
+/// Restricts the schedules that can be managed with a cron permission. Only
+/// the name prefix applies to removed schedules.
+#[cw_serde]
+#[derive(Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub struct CronPermissionScope {
+    /// The names of the schedules must start with this prefix.
+    pub name_prefix: Option<String>,
+    /// The contracts that the schedules can call.
+    pub contracts: Option<Vec<String>>,
+    pub execution_stages: Option<Vec<CronExecutionStage>>,
+    pub min_period: Option<u64>,
+    pub max_period: Option<u64>,
+}
+
+#[cw_serde]
+#[derive(Eq)]
+pub enum CronExecutionStage {
+    BeginBlocker,
+    EndBlocker,
 }
 
 #[cw_serde]
@@ -366,6 +851,17 @@ pub struct DexUpdateParamsPermission {
     pub max_jits_per_block: bool,
     pub good_til_purge_allowance: bool,
     pub whitelisted_lps: bool,
+    pub constraints: Option<DexUpdateParamsConstraints>,
+}
+
+#[cw_serde]
+#[derive(Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub struct DexUpdateParamsConstraints {
+    /// Applied to every fee tier in the new list.
+    pub fee_tiers: Option<NumericConstraint>,
+    pub max_jits_per_block: Option<NumericConstraint>,
+    pub good_til_purge_allowance: Option<NumericConstraint>,
 }
 
 #[cw_serde]
@@ -373,6 +869,14 @@ pub struct DexUpdateParamsPermission {
 #[serde(rename_all = "snake_case")]
 pub struct DynamicFeesUpdateParamsPermission {
     pub ntrn_prices: bool,
+    pub constraints: Option<DynamicFeesUpdateParamsConstraints>,
+}
+
+#[cw_serde]
+#[derive(Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub struct DynamicFeesUpdateParamsConstraints {
+    pub ntrn_prices: Option<Vec<PriceConstraint>>,
 }
 
 #[cw_serde]
@@ -382,6 +886,17 @@ pub struct GlobalfeeUpdateParamsPermission {
     pub minimum_gas_prices: bool,
     pub bypass_min_fee_msg_types: bool,
     pub max_total_bypass_min_fee_msg_gas_usage: bool,
+    pub constraints: Option<GlobalfeeUpdateParamsConstraints>,
+}
+
+#[cw_serde]
+#[derive(Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub struct GlobalfeeUpdateParamsConstraints {
+    pub minimum_gas_prices: Option<Vec<PriceConstraint>>,
+    /// Defines the message types that are allowed to be in the new list.
+    pub bypass_min_fee_msg_types: Option<Vec<String>>,
+    pub max_total_bypass_min_fee_msg_gas_usage: Option<NumericConstraint>,
 }
 
 #[cw_serde]
@@ -402,6 +917,59 @@ pub struct CCVUpdateParamsPermission {
     pub reward_denoms: bool,
     pub provider_reward_denoms: bool,
     pub retry_delay_period: bool,
+    pub constraints: Option<CCVUpdateParamsConstraints>,
+}
+
+/// Constraints on the durations apply to their number of whole seconds.
+#[cw_serde]
+#[derive(Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub struct CCVUpdateParamsConstraints {
+    pub blocks_per_distribution_transmission: Option<NumericConstraint>,
+    pub ccv_timeout_period: Option<NumericConstraint>,
+    pub transfer_timeout_period: Option<NumericConstraint>,
+    pub historical_entries: Option<NumericConstraint>,
+    pub unbonding_period: Option<NumericConstraint>,
+    pub retry_delay_period: Option<NumericConstraint>,
+}
+
+/// Defines the values a numeric parameter can be set to. Every condition that
+/// is set must hold for a new value to be accepted.
+#[cw_serde]
+#[derive(Eq)]
+#[serde(rename_all = "snake_case")]
+pub struct NumericConstraint {
+    pub min: Option<u64>,
+    pub max: Option<u64>,
+    pub allowed_values: Option<Vec<u64>>,
+}
+
+impl NumericConstraint {
+    pub fn allows(&self, value: u64) -> bool {
+        self.min.map_or(true, |min| value >= min)
+            && self.max.map_or(true, |max| value <= max)
+            && self
+                .allowed_values
+                .as_ref()
+                .map_or(true, |allowed_values| allowed_values.contains(&value))
+    }
+}
+
+/// Defines the range a price in the given denom can be set to. Prices in denoms
+/// without a constraint can not be set at all.
+#[cw_serde]
+#[derive(Eq)]
+#[serde(rename_all = "snake_case")]
+pub struct PriceConstraint {
+    pub denom: String,
+    pub min: Option<Decimal>,
+    pub max: Option<Decimal>,
+}
+
+impl PriceConstraint {
+    pub fn allows(&self, price: Decimal) -> bool {
+        self.min.map_or(true, |min| price >= min) && self.max.map_or(true, |max| price <= max)
+    }
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
@@ -409,6 +977,62 @@ pub struct CCVUpdateParamsPermission {
 pub struct SoftwareUpgradePermission {
     pub upgrade: bool,
     pub cancel_upgrade: bool,
+    pub constraints: Option<SoftwareUpgradeConstraints>,
+}
+
+/// Guardrails for scheduling and cancelling software upgrades.
+#[cw_serde]
+#[derive(Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub struct SoftwareUpgradeConstraints {
+    /// The minimum number of blocks between the current height and the height
+    /// of a scheduled upgrade plan.
+    pub min_upgrade_delay_blocks: Option<u64>,
+    /// The name of a scheduled upgrade plan must start with this prefix.
+    pub name_prefix: Option<String>,
+    /// The current upgrade plan can not be cancelled within this number of
+    /// blocks before its height.
+    pub cancel_upgrade_freeze_blocks: Option<u64>,
+}
+
+/// Allows to migrate the listed contracts to the listed code IDs, and to
+/// update or clear their admin.
+#[cw_serde]
+#[derive(Eq)]
+#[serde(rename_all = "snake_case")]
+pub struct WasmAdminPermission {
+    pub contracts: Vec<String>,
+    pub code_ids: Vec<u64>,
+    pub migrate: bool,
+    pub update_admin: bool,
+    pub clear_admin: bool,
+}
+
+/// Allows to change the listed top-level parameters of a module that has no
+/// dedicated update params permission. The current parameters are fetched with
+/// a stargate query to `params_query_path` (e.g. `/neutron.feeburner.Query/Params`)
+/// and compared with the parameters in the `type_url` message.
+#[cw_serde]
+#[derive(Eq)]
+#[serde(rename_all = "snake_case")]
+pub struct UpdateParamsPermission {
+    pub type_url: String,
+    pub params_query_path: String,
+    pub fields: Vec<String>,
+}
+
+/// Allows to send tokens from the chain manager balance, up to the given
+/// amounts per period. Denoms without a limit can not be sent.
+#[cw_serde]
+#[derive(Eq)]
+#[serde(rename_all = "snake_case")]
+pub struct BankSendPermission {
+    pub limits: Vec<Coin>,
+    /// The length of the period in blocks after which the spent amounts are reset.
+    /// Must not be zero.
+    pub period_blocks: u64,
+    /// If set, tokens can only be sent to these addresses.
+    pub allowed_recipients: Option<Vec<String>>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
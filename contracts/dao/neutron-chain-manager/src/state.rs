@@ -1,6 +1,85 @@
-use crate::msg::Strategy;
-use cosmwasm_std::Addr;
-use cw_storage_plus::Map;
+use crate::msg::{AuditLogEntry, Batch, CoSigning, RateLimit, Strategy};
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{Addr, Coin, Empty};
+use cw_storage_plus::{Item, Map};
+use cw_utils::Expiration;
 
 /// Defines a mapping from an address to a strategy associated with the address.
 pub const STRATEGIES: Map<Addr, Strategy> = Map::new("chain-manager-strategies");
+
+/// Defines a mapping from an address to the expiration of the strategy associated
+/// with the address. Strategies without an entry never expire.
+pub const STRATEGY_EXPIRATIONS: Map<Addr, Expiration> =
+    Map::new("chain-manager-strategy-expirations");
+
+/// Defines a mapping from an address to the rate limit of the strategy associated
+/// with the address. Strategies without an entry are not rate limited.
+pub const STRATEGY_RATE_LIMITS: Map<Addr, RateLimit> =
+    Map::new("chain-manager-strategy-rate-limits");
+
+/// Defines a mapping from an address to the recent executions made with the
+/// rate limited strategy associated with the address. Only the executions within
+/// the current rate limit window are kept.
+pub const STRATEGY_EXECUTIONS: Map<Addr, Vec<ExecutionRecord>> =
+    Map::new("chain-manager-strategy-executions");
+
+#[cw_serde]
+pub struct ExecutionRecord {
+    pub height: u64,
+    pub param_updates: u64,
+}
+
+/// Defines a mapping from an address to the amounts sent with the bank send
+/// permission of the strategy associated with the address.
+pub const BANK_SEND_SPENDINGS: Map<Addr, BankSendSpending> =
+    Map::new("chain-manager-bank-send-spendings");
+
+#[cw_serde]
+pub struct BankSendSpending {
+    /// The height at which the current period started.
+    pub period_start: u64,
+    pub spent: Vec<Coin>,
+}
+
+/// Default limit for audit log, batch and expired strategies pagination.
+pub const DEFAULT_LIMIT: u32 = 10;
+/// Maximum limit for audit log, batch and expired strategies pagination.
+pub const MAX_LIMIT: u32 = 30;
+
+/// The ID that the next audit log entry is going to get.
+pub const AUDIT_LOG_NEXT_ID: Item<u64> = Item::new("chain-manager-audit-log-next-id");
+
+/// Defines a mapping from an ID to an audit log entry.
+pub const AUDIT_LOG: Map<u64, AuditLogEntry> = Map::new("chain-manager-audit-log");
+
+/// Indexes the audit log entries by the sender address.
+pub const AUDIT_LOG_BY_ADDRESS: Map<(Addr, u64), Empty> =
+    Map::new("chain-manager-audit-log-by-address");
+
+/// Indexes the audit log entries by the type URLs of the executed messages.
+pub const AUDIT_LOG_BY_TYPE_URL: Map<(String, u64), Empty> =
+    Map::new("chain-manager-audit-log-by-type-url");
+
+/// Defines a mapping from an address to the co-signers of the strategy
+/// associated with the address. Strategies without an entry are not co-signed.
+pub const STRATEGY_CO_SIGNING: Map<Addr, CoSigning> = Map::new("chain-manager-strategy-co-signing");
+
+/// Defines a mapping from an address to the address that delegated the strategy
+/// associated with it. Strategies added by ALLOW_ALL addresses have no entry.
+pub const STRATEGY_PARENTS: Map<Addr, Addr> = Map::new("chain-manager-strategy-parents");
+
+/// Indexes the delegated strategies by the address that delegated them, so
+/// that they can be removed along with it.
+pub const STRATEGY_CHILDREN: Map<(Addr, Addr), Empty> = Map::new("chain-manager-strategy-children");
+
+/// Maximum number of strategies delegated from a single strategy.
+pub const MAX_DELEGATED_STRATEGIES: u32 = 5;
+/// Maximum number of delegations between a strategy added by an ALLOW_ALL
+/// address and a strategy delegated from it.
+pub const MAX_DELEGATION_DEPTH: u32 = 2;
+
+/// The ID that the next batch is going to get.
+pub const BATCH_NEXT_ID: Item<u64> = Item::new("chain-manager-batch-next-id");
+
+/// Defines a mapping from an ID to a batch created by a co-signed strategy.
+pub const BATCHES: Map<u64, Batch> = Map::new("chain-manager-batches");
@@ -6,12 +6,16 @@ use cosmwasm_std::{
 use neutron_std::shim::Duration;
 use neutron_std::types::cosmos::base::v1beta1::Coin;
 use neutron_std::types::cosmos::base::v1beta1::DecCoin;
+use neutron_std::types::cosmos::upgrade;
 use neutron_std::types::gaia::globalfee;
 use neutron_std::types::interchain_security::ccv::{self, consumer};
 use neutron_std::types::neutron::{cron, dex, dynamicfees};
 use neutron_std::types::osmosis::tokenfactory;
 use std::marker::PhantomData;
 
+/// The height of the upgrade plan returned by the current plan query.
+pub const CURRENT_UPGRADE_PLAN_HEIGHT: i64 = 13_000;
+
 pub fn mock_dependencies() -> OwnedDeps<MockStorage, MockApi, WasmMockQuerier> {
     let custom_storage = MockStorage::default();
     let custom_querier = WasmMockQuerier::new(MockQuerier::new(&[]));
@@ -112,6 +116,26 @@ impl WasmMockQuerier {
                     .to_proto_bytes();
                     SystemResult::Ok(ContractResult::Ok(Binary::new(resp.to_vec())))
                 }
+                upgrade::v1beta1::QueryCurrentPlanRequest::PATH => {
+                    let resp = &upgrade::v1beta1::QueryCurrentPlanResponse {
+                        plan: Some(upgrade::v1beta1::Plan {
+                            name: "v5.0.0".to_string(),
+                            height: CURRENT_UPGRADE_PLAN_HEIGHT,
+                            ..Default::default()
+                        }),
+                    }
+                    .to_proto_bytes();
+                    SystemResult::Ok(ContractResult::Ok(Binary::new(resp.to_vec())))
+                }
+                _ => todo!(),
+            },
+            // Stargate queries return JSON.
+            #[allow(deprecated)]
+            QueryRequest::Stargate { data: _, path } => match path.as_str() {
+                "/neutron.interchaintxs.v1.Query/Params" => {
+                    let resp = br#"{"params":{"msg_submit_tx_max_messages":"16","register_fee":[{"denom":"untrn","amount":"1000000"}]}}"#;
+                    SystemResult::Ok(ContractResult::Ok(Binary::new(resp.to_vec())))
+                }
                 _ => todo!(),
             },
             _ => self.base.handle_query(request),
@@ -1,26 +1,51 @@
 use crate::contract::{
-    execute_add_strategy, execute_execute_messages, execute_remove_strategy, instantiate,
+    execute_add_strategy, execute_approve_batch, execute_delegate_strategy,
+    execute_execute_messages, execute_expire_batch, execute_prune_expired_strategies,
+    execute_reject_batch, execute_remove_strategy, execute_revoke_delegated_strategy, instantiate,
+    query_audit_log_by_address, query_audit_log_by_type_url, query_bank_send_allowance,
+    query_batches, query_check_messages, query_strategies,
+};
+use crate::error::ContractError::{
+    AlreadyVoted, BankSendLimitExceeded, BatchExpired, BatchNotExpired, BatchNotPending,
+    DelegationTooDeep, InvalidBankSendPermission, InvalidCoSigning, InvalidDelegation,
+    InvalidDemotion, InvalidExpiration, InvalidRateLimit, ParamConstraintViolation,
+    RateLimitExceeded, SoftwareUpgradeConstraintViolation, StrategyExpired,
+    TooManyDelegatedStrategies, Unauthorized,
 };
-use crate::error::ContractError::{InvalidDemotion, Unauthorized};
 use crate::msg::Permission::{
-    CronPermission, ParamChangePermission, UpdateCCVParamsPermission, UpdateCronParamsPermission,
-    UpdateDexParamsPermission, UpdateDynamicfeesParamsPermission, UpdateGlobalfeeParamsPermission,
-    UpdateTokenfactoryParamsPermission,
+    BankSendPermission, CronPermission, ParamChangePermission, SoftwareUpgradePermission,
+    UpdateCCVParamsPermission, UpdateCronParamsPermission, UpdateDexParamsPermission,
+    UpdateDynamicfeesParamsPermission, UpdateGlobalfeeParamsPermission, UpdateParamsPermission,
+    UpdateTokenfactoryParamsPermission, WasmAdminPermission,
 };
 use crate::msg::{
-    CCVUpdateParamsPermission, CronPermission as CronPermissionType, CronUpdateParamsPermission,
-    DexUpdateParamsPermission, DynamicFeesUpdateParamsPermission, GlobalfeeUpdateParamsPermission,
-    InstantiateMsg, ParamChangePermission as ParamChangePermissionType, ParamPermission,
-    StrategyMsg, TokenfactoryUpdateParamsPermission,
+    BankSendAllowanceResponse, BankSendPermission as BankSendPermissionType, BatchStatus,
+    CCVUpdateParamsConstraints, CCVUpdateParamsPermission, CoSigning, CronExecutionStage,
+    CronPermission as CronPermissionType, CronPermissionScope, CronUpdateParamsPermission,
+    DexUpdateParamsConstraints, DexUpdateParamsPermission, DynamicFeesUpdateParamsPermission,
+    GlobalfeeUpdateParamsConstraints, GlobalfeeUpdateParamsPermission, InstantiateMsg,
+    MessageCheckResponse, NumericConstraint, ParamChangePermission as ParamChangePermissionType,
+    ParamPermission, PriceConstraint, RateLimit, SoftwareUpgradeConstraints,
+    SoftwareUpgradePermission as SoftwareUpgradePermissionType, StrategyMsg,
+    TokenfactoryUpdateParamsPermission, UpdateParamsPermission as UpdateParamsPermissionType,
+    WasmAdminPermission as WasmAdminPermissionType,
 };
+use crate::state::{MAX_DELEGATED_STRATEGIES, MAX_DELEGATION_DEPTH};
 use crate::testing::mock_querier::{
     consumer_params_to_update, default_consumer_params, mock_dependencies,
+    CURRENT_UPGRADE_PLAN_HEIGHT,
 };
 use cosmwasm_std::testing::{message_info, mock_env};
-use cosmwasm_std::{Addr, BankMsg, Coin, CosmosMsg, Uint128};
+use cosmwasm_std::{
+    to_json_binary, Addr, BankMsg, Coin, CosmosMsg, Decimal, Empty, Uint128, WasmMsg,
+};
+use cw_utils::Expiration;
 use neutron_sdk::bindings::msg::{
-    AdminProposal, NeutronMsg, ParamChange, ParamChangeProposal, ProposalExecuteMessage,
+    AdminProposal, MsgExecuteContract, NeutronMsg, ParamChange, ParamChangeProposal,
+    ProposalExecuteMessage,
 };
+use neutron_std::shim::Duration;
+use neutron_std::types::interchain_security::ccv::v1::ConsumerParams;
 
 #[test]
 fn test_instantiate() {
@@ -59,9 +84,13 @@ fn test_add_strategy() {
     let info = message_info(&Addr::unchecked("neutron_dao_address"), &[]);
     execute_add_strategy(
         deps.as_mut(),
+        env.clone(),
         info.clone(),
         Addr::unchecked("addr1".to_string()),
         StrategyMsg::AllowAll,
+        None,
+        None,
+        None,
     )
     .unwrap();
 
@@ -69,12 +98,17 @@ fn test_add_strategy() {
     let info = message_info(&Addr::unchecked("neutron_dao_address"), &[]);
     execute_add_strategy(
         deps.as_mut(),
+        env.clone(),
         info.clone(),
         Addr::unchecked("addr2".to_string()),
         StrategyMsg::AllowOnly(vec![CronPermission(CronPermissionType {
             add_schedule: true,
             remove_schedule: true,
+            scope: None,
         })]),
+        None,
+        None,
+        None,
     )
     .unwrap();
 }
@@ -100,28 +134,41 @@ fn test_add_strategy_promotion() {
     let info = message_info(&Addr::unchecked("neutron_dao_address"), &[]);
     execute_add_strategy(
         deps.as_mut(),
+        env.clone(),
         info.clone(),
         Addr::unchecked("addr2".to_string()),
         StrategyMsg::AllowOnly(vec![CronPermission(CronPermissionType {
             add_schedule: true,
             remove_schedule: true,
+            scope: None,
         })]),
+        None,
+        None,
+        None,
     )
     .unwrap();
 
     execute_add_strategy(
         deps.as_mut(),
+        env.clone(),
         info.clone(),
         Addr::unchecked("addr2".to_string()),
         StrategyMsg::AllowAll,
+        None,
+        None,
+        None,
     )
     .unwrap();
     let info = message_info(&Addr::unchecked("addr2"), &[]);
     execute_add_strategy(
         deps.as_mut(),
+        env.clone(),
         info.clone(),
         Addr::unchecked("addr3".to_string()),
         StrategyMsg::AllowAll,
+        None,
+        None,
+        None,
     )
     .unwrap();
 }
@@ -147,27 +194,40 @@ fn test_add_strategy_demotion() {
     let info = message_info(&Addr::unchecked("neutron_dao_address"), &[]);
     execute_add_strategy(
         deps.as_mut(),
+        env.clone(),
         info.clone(),
         Addr::unchecked("addr1".to_string()),
         StrategyMsg::AllowAll,
+        None,
+        None,
+        None,
     )
     .unwrap();
     execute_add_strategy(
         deps.as_mut(),
+        env.clone(),
         info.clone(),
         Addr::unchecked("addr1".to_string()),
         StrategyMsg::AllowOnly(vec![CronPermission(CronPermissionType {
             add_schedule: true,
             remove_schedule: true,
+            scope: None,
         })]),
+        None,
+        None,
+        None,
     )
     .unwrap();
     let info = message_info(&Addr::unchecked("addr1"), &[]);
     let err = execute_add_strategy(
         deps.as_mut(),
+        env.clone(),
         info,
         Addr::unchecked("addr2".to_string()),
         StrategyMsg::AllowAll,
+        None,
+        None,
+        None,
     )
     .unwrap_err();
     assert_eq!(err, Unauthorized {})
@@ -194,12 +254,17 @@ fn test_add_strategy_invalid_demotion() {
     let info = message_info(&Addr::unchecked("neutron_dao_address"), &[]);
     let err = execute_add_strategy(
         deps.as_mut(),
+        env.clone(),
         info.clone(),
         Addr::unchecked("neutron_dao_address".to_string()),
         StrategyMsg::AllowOnly(vec![CronPermission(CronPermissionType {
             add_schedule: true,
             remove_schedule: true,
+            scope: None,
         })]),
+        None,
+        None,
+        None,
     )
     .unwrap_err();
     assert_eq!(err, InvalidDemotion {});
@@ -224,9 +289,13 @@ fn test_remove_strategy() {
     let info = message_info(&Addr::unchecked("neutron_dao_address"), &[]);
     execute_add_strategy(
         deps.as_mut(),
+        env.clone(),
         info.clone(),
         Addr::unchecked("addr1".to_string()),
         StrategyMsg::AllowAll,
+        None,
+        None,
+        None,
     )
     .unwrap();
     execute_remove_strategy(
@@ -239,9 +308,13 @@ fn test_remove_strategy() {
     let info = message_info(&Addr::unchecked("addr1"), &[]);
     let err = execute_add_strategy(
         deps.as_mut(),
+        env.clone(),
         info,
         Addr::unchecked("addr1".to_string()),
         StrategyMsg::AllowAll,
+        None,
+        None,
+        None,
     )
     .unwrap_err();
     assert_eq!(err, Unauthorized {})
@@ -303,19 +376,24 @@ pub fn test_execute_execute_message_update_params_cron_authorized() {
     let info = message_info(&Addr::unchecked("neutron_dao_address"), &[]);
     execute_add_strategy(
         deps.as_mut(),
+        env.clone(),
         info.clone(),
         Addr::unchecked("addr1".to_string()),
         StrategyMsg::AllowOnly(vec![UpdateCronParamsPermission(
             CronUpdateParamsPermission {
                 security_address: true,
                 limit: true,
+                constraints: None,
             },
         )]),
+        None,
+        None,
+        None,
     )
     .unwrap();
 
     let info = message_info(&Addr::unchecked("addr1"), &[]);
-    execute_execute_messages(deps.as_mut(), info.clone(), vec![msg]).unwrap();
+    execute_execute_messages(deps.as_mut(), env.clone(), info.clone(), vec![msg]).unwrap();
 }
 
 /// Checks that unsupported message types inside a ProposalExecuteMessage are not
@@ -348,19 +426,25 @@ pub fn test_execute_execute_message_unsupported_message_type_unauthorized() {
     let info = message_info(&Addr::unchecked("neutron_dao_address"), &[]);
     execute_add_strategy(
         deps.as_mut(),
+        env.clone(),
         info.clone(),
         Addr::unchecked("addr1".to_string()),
         StrategyMsg::AllowOnly(vec![UpdateCronParamsPermission(
             CronUpdateParamsPermission {
                 security_address: true,
                 limit: true,
+                constraints: None,
             },
         )]),
+        None,
+        None,
+        None,
     )
     .unwrap();
 
     let info = message_info(&Addr::unchecked("addr1"), &[]);
-    let err = execute_execute_messages(deps.as_mut(), info.clone(), vec![msg]).unwrap_err();
+    let err =
+        execute_execute_messages(deps.as_mut(), env.clone(), info.clone(), vec![msg]).unwrap_err();
     assert_eq!(err, Unauthorized {})
 }
 
@@ -394,19 +478,25 @@ pub fn test_execute_execute_message_update_params_cron_unauthorized_limit() {
     let info = message_info(&Addr::unchecked("neutron_dao_address"), &[]);
     execute_add_strategy(
         deps.as_mut(),
+        env.clone(),
         info.clone(),
         Addr::unchecked("addr1".to_string()),
         StrategyMsg::AllowOnly(vec![UpdateCronParamsPermission(
             CronUpdateParamsPermission {
                 security_address: true,
                 limit: false,
+                constraints: None,
             },
         )]),
+        None,
+        None,
+        None,
     )
     .unwrap();
 
     let info = message_info(&Addr::unchecked("addr1"), &[]);
-    let err = execute_execute_messages(deps.as_mut(), info.clone(), vec![msg]).unwrap_err();
+    let err =
+        execute_execute_messages(deps.as_mut(), env.clone(), info.clone(), vec![msg]).unwrap_err();
     assert_eq!(err, Unauthorized {})
 }
 
@@ -440,19 +530,25 @@ pub fn test_execute_execute_message_update_params_cron_unauthorized_security_add
     let info = message_info(&Addr::unchecked("neutron_dao_address"), &[]);
     execute_add_strategy(
         deps.as_mut(),
+        env.clone(),
         info.clone(),
         Addr::unchecked("addr1".to_string()),
         StrategyMsg::AllowOnly(vec![UpdateCronParamsPermission(
             CronUpdateParamsPermission {
                 security_address: false,
                 limit: true,
+                constraints: None,
             },
         )]),
+        None,
+        None,
+        None,
     )
     .unwrap();
 
     let info = message_info(&Addr::unchecked("addr1"), &[]);
-    let err = execute_execute_messages(deps.as_mut(), info.clone(), vec![msg]).unwrap_err();
+    let err =
+        execute_execute_messages(deps.as_mut(), env.clone(), info.clone(), vec![msg]).unwrap_err();
     assert_eq!(err, Unauthorized {});
 }
 
@@ -487,6 +583,7 @@ pub fn test_execute_execute_message_update_params_tokenfactory_authorized() {
     let info = message_info(&Addr::unchecked("neutron_dao_address"), &[]);
     execute_add_strategy(
         deps.as_mut(),
+        env.clone(),
         info.clone(),
         Addr::unchecked("addr1".to_string()),
         StrategyMsg::AllowOnly(vec![UpdateTokenfactoryParamsPermission(
@@ -497,11 +594,14 @@ pub fn test_execute_execute_message_update_params_tokenfactory_authorized() {
                 whitelisted_hooks: true,
             },
         )]),
+        None,
+        None,
+        None,
     )
     .unwrap();
 
     let info = message_info(&Addr::unchecked("addr1"), &[]);
-    execute_execute_messages(deps.as_mut(), info.clone(), vec![msg]).unwrap();
+    execute_execute_messages(deps.as_mut(), env.clone(), info.clone(), vec![msg]).unwrap();
 }
 /// Checks that you can't change the denom_creation_fee if you don't have the permission to do so
 /// (new style parameter changes).
@@ -533,6 +633,7 @@ pub fn test_execute_execute_message_update_params_tokenfactory_unauthorized_deno
     let info = message_info(&Addr::unchecked("neutron_dao_address"), &[]);
     execute_add_strategy(
         deps.as_mut(),
+        env.clone(),
         info.clone(),
         Addr::unchecked("addr1".to_string()),
         StrategyMsg::AllowOnly(vec![UpdateTokenfactoryParamsPermission(
@@ -543,11 +644,15 @@ pub fn test_execute_execute_message_update_params_tokenfactory_unauthorized_deno
                 whitelisted_hooks: true,
             },
         )]),
+        None,
+        None,
+        None,
     )
     .unwrap();
 
     let info = message_info(&Addr::unchecked("addr1"), &[]);
-    let err = execute_execute_messages(deps.as_mut(), info.clone(), vec![msg]).unwrap_err();
+    let err =
+        execute_execute_messages(deps.as_mut(), env.clone(), info.clone(), vec![msg]).unwrap_err();
     assert_eq!(err, Unauthorized {})
 }
 
@@ -582,6 +687,7 @@ pub fn test_execute_execute_message_update_params_tokenfactory_unauthorized_deno
     let info = message_info(&Addr::unchecked("neutron_dao_address"), &[]);
     execute_add_strategy(
         deps.as_mut(),
+        env.clone(),
         info.clone(),
         Addr::unchecked("addr1".to_string()),
         StrategyMsg::AllowOnly(vec![UpdateTokenfactoryParamsPermission(
@@ -592,11 +698,15 @@ pub fn test_execute_execute_message_update_params_tokenfactory_unauthorized_deno
                 whitelisted_hooks: true,
             },
         )]),
+        None,
+        None,
+        None,
     )
     .unwrap();
 
     let info = message_info(&Addr::unchecked("addr1"), &[]);
-    let err = execute_execute_messages(deps.as_mut(), info.clone(), vec![msg]).unwrap_err();
+    let err =
+        execute_execute_messages(deps.as_mut(), env.clone(), info.clone(), vec![msg]).unwrap_err();
     assert_eq!(err, Unauthorized {});
 }
 
@@ -631,6 +741,7 @@ pub fn test_execute_execute_message_update_params_tokenfactory_unauthorized_fee_
     let info = message_info(&Addr::unchecked("neutron_dao_address"), &[]);
     execute_add_strategy(
         deps.as_mut(),
+        env.clone(),
         info.clone(),
         Addr::unchecked("addr1".to_string()),
         StrategyMsg::AllowOnly(vec![UpdateTokenfactoryParamsPermission(
@@ -641,11 +752,15 @@ pub fn test_execute_execute_message_update_params_tokenfactory_unauthorized_fee_
                 whitelisted_hooks: true,
             },
         )]),
+        None,
+        None,
+        None,
     )
     .unwrap();
 
     let info = message_info(&Addr::unchecked("addr1"), &[]);
-    let err = execute_execute_messages(deps.as_mut(), info.clone(), vec![msg]).unwrap_err();
+    let err =
+        execute_execute_messages(deps.as_mut(), env.clone(), info.clone(), vec![msg]).unwrap_err();
     assert_eq!(err, Unauthorized {});
 }
 
@@ -679,6 +794,7 @@ pub fn test_execute_execute_message_update_params_tokenfactory_unauthorized_whit
     let info = message_info(&Addr::unchecked("neutron_dao_address"), &[]);
     execute_add_strategy(
         deps.as_mut(),
+        env.clone(),
         info.clone(),
         Addr::unchecked("addr1".to_string()),
         StrategyMsg::AllowOnly(vec![UpdateTokenfactoryParamsPermission(
@@ -689,11 +805,15 @@ pub fn test_execute_execute_message_update_params_tokenfactory_unauthorized_whit
                 whitelisted_hooks: false,
             },
         )]),
+        None,
+        None,
+        None,
     )
     .unwrap();
 
     let info = message_info(&Addr::unchecked("addr1"), &[]);
-    let err = execute_execute_messages(deps.as_mut(), info.clone(), vec![msg]).unwrap_err();
+    let err =
+        execute_execute_messages(deps.as_mut(), env.clone(), info.clone(), vec![msg]).unwrap_err();
     assert_eq!(err, Unauthorized {});
 }
 
@@ -728,6 +848,61 @@ pub fn test_execute_execute_message_update_params_dex_authorized() {
     let info = message_info(&Addr::unchecked("neutron_dao_address"), &[]);
     execute_add_strategy(
         deps.as_mut(),
+        env.clone(),
+        info.clone(),
+        Addr::unchecked("addr1".to_string()),
+        StrategyMsg::AllowOnly(vec![UpdateDexParamsPermission(DexUpdateParamsPermission {
+            fee_tiers: true,
+            paused: true,
+            max_jits_per_block: true,
+            good_til_purge_allowance: true,
+            whitelisted_lps: true,
+            constraints: None,
+        })]),
+        None,
+        None,
+        None,
+    )
+    .unwrap();
+
+    let info = message_info(&Addr::unchecked("addr1"), &[]);
+    execute_execute_messages(deps.as_mut(), env.clone(), info.clone(), vec![msg]).unwrap();
+}
+
+/// Checks that dex parameters can only be changed to values allowed by the
+/// constraints of the permission.
+#[test]
+pub fn test_execute_execute_message_update_params_dex_constraints() {
+    let dex_update_msg = |params: &str| {
+        CosmosMsg::Custom(NeutronMsg::SubmitAdminProposal {
+            admin_proposal: AdminProposal::ProposalExecuteMessage(ProposalExecuteMessage {
+                message: format!(
+                    r#"{{"@type":"/neutron.dex.MsgUpdateParams",
+                    "authority":"neutron1hxskfdxpp5hqgtjj6am6nkjefhfzj359x0ar3z",
+                    "params": {}}}"#,
+                    params
+                ),
+            }),
+        })
+    };
+
+    let mut deps = mock_dependencies();
+    let env = mock_env();
+    let info = message_info(&Addr::unchecked("neutron_dao_address"), &[]);
+
+    instantiate(
+        deps.as_mut(),
+        env.clone(),
+        info.clone(),
+        InstantiateMsg {
+            initial_strategy_address: Addr::unchecked("neutron_dao_address".to_string()),
+        },
+    )
+    .unwrap();
+
+    execute_add_strategy(
+        deps.as_mut(),
+        env.clone(),
         info.clone(),
         Addr::unchecked("addr1".to_string()),
         StrategyMsg::AllowOnly(vec![UpdateDexParamsPermission(DexUpdateParamsPermission {
@@ -736,12 +911,57 @@ pub fn test_execute_execute_message_update_params_dex_authorized() {
             max_jits_per_block: true,
             good_til_purge_allowance: true,
             whitelisted_lps: true,
+            constraints: Some(DexUpdateParamsConstraints {
+                fee_tiers: Some(NumericConstraint {
+                    min: Some(1),
+                    max: Some(200),
+                    allowed_values: None,
+                }),
+                max_jits_per_block: Some(NumericConstraint {
+                    min: None,
+                    max: Some(20),
+                    allowed_values: None,
+                }),
+                good_til_purge_allowance: None,
+            }),
         })]),
+        None,
+        None,
+        None,
     )
     .unwrap();
 
     let info = message_info(&Addr::unchecked("addr1"), &[]);
-    execute_execute_messages(deps.as_mut(), info.clone(), vec![msg]).unwrap();
+
+    // All fee tiers are within the range, and max_jits_per_block is not changed.
+    let msg = dex_update_msg(
+        r#"{"fee_tiers":["1","200"],"paused":false,"max_jits_per_block":"20","good_til_purge_allowance":"540000","whitelisted_lps":[]}"#,
+    );
+    execute_execute_messages(deps.as_mut(), env.clone(), info.clone(), vec![msg]).unwrap();
+
+    let msg = dex_update_msg(
+        r#"{"fee_tiers":["1","300"],"paused":false,"max_jits_per_block":"20","good_til_purge_allowance":"25000","whitelisted_lps":[]}"#,
+    );
+    let err =
+        execute_execute_messages(deps.as_mut(), env.clone(), info.clone(), vec![msg]).unwrap_err();
+    assert_eq!(
+        err,
+        ParamConstraintViolation {
+            param: "fee_tiers".to_string()
+        }
+    );
+
+    let msg = dex_update_msg(
+        r#"{"fee_tiers":["1","2","99"],"paused":false,"max_jits_per_block":"25","good_til_purge_allowance":"25000","whitelisted_lps":[]}"#,
+    );
+    let err =
+        execute_execute_messages(deps.as_mut(), env.clone(), info.clone(), vec![msg]).unwrap_err();
+    assert_eq!(
+        err,
+        ParamConstraintViolation {
+            param: "max_jits_per_block".to_string()
+        }
+    );
 }
 
 /// Checks that you can't change the `fee_tiers` if you don't have the permission to do so
@@ -774,6 +994,7 @@ pub fn test_execute_execute_message_update_params_dex_unauthorized_fee_tiers() {
     let info = message_info(&Addr::unchecked("neutron_dao_address"), &[]);
     execute_add_strategy(
         deps.as_mut(),
+        env.clone(),
         info.clone(),
         Addr::unchecked("addr1".to_string()),
         StrategyMsg::AllowOnly(vec![UpdateDexParamsPermission(DexUpdateParamsPermission {
@@ -782,12 +1003,17 @@ pub fn test_execute_execute_message_update_params_dex_unauthorized_fee_tiers() {
             max_jits_per_block: true,
             good_til_purge_allowance: true,
             whitelisted_lps: true,
+            constraints: None,
         })]),
+        None,
+        None,
+        None,
     )
     .unwrap();
 
     let info = message_info(&Addr::unchecked("addr1"), &[]);
-    let err = execute_execute_messages(deps.as_mut(), info.clone(), vec![msg]).unwrap_err();
+    let err =
+        execute_execute_messages(deps.as_mut(), env.clone(), info.clone(), vec![msg]).unwrap_err();
     assert_eq!(err, Unauthorized {})
 }
 
@@ -822,6 +1048,7 @@ pub fn test_execute_execute_message_update_params_dex_unauthorized_paused() {
     let info = message_info(&Addr::unchecked("neutron_dao_address"), &[]);
     execute_add_strategy(
         deps.as_mut(),
+        env.clone(),
         info.clone(),
         Addr::unchecked("addr1".to_string()),
         StrategyMsg::AllowOnly(vec![UpdateDexParamsPermission(DexUpdateParamsPermission {
@@ -830,12 +1057,17 @@ pub fn test_execute_execute_message_update_params_dex_unauthorized_paused() {
             max_jits_per_block: true,
             good_til_purge_allowance: true,
             whitelisted_lps: true,
+            constraints: None,
         })]),
+        None,
+        None,
+        None,
     )
     .unwrap();
 
     let info = message_info(&Addr::unchecked("addr1"), &[]);
-    let err = execute_execute_messages(deps.as_mut(), info.clone(), vec![msg]).unwrap_err();
+    let err =
+        execute_execute_messages(deps.as_mut(), env.clone(), info.clone(), vec![msg]).unwrap_err();
     assert_eq!(err, Unauthorized {});
 }
 
@@ -869,6 +1101,7 @@ pub fn test_execute_execute_message_update_params_dex_unauthorized_max_jits_per_
     let info = message_info(&Addr::unchecked("neutron_dao_address"), &[]);
     execute_add_strategy(
         deps.as_mut(),
+        env.clone(),
         info.clone(),
         Addr::unchecked("addr1".to_string()),
         StrategyMsg::AllowOnly(vec![UpdateDexParamsPermission(DexUpdateParamsPermission {
@@ -877,12 +1110,17 @@ pub fn test_execute_execute_message_update_params_dex_unauthorized_max_jits_per_
             max_jits_per_block: false,
             good_til_purge_allowance: true,
             whitelisted_lps: true,
+            constraints: None,
         })]),
+        None,
+        None,
+        None,
     )
     .unwrap();
 
     let info = message_info(&Addr::unchecked("addr1"), &[]);
-    let err = execute_execute_messages(deps.as_mut(), info.clone(), vec![msg]).unwrap_err();
+    let err =
+        execute_execute_messages(deps.as_mut(), env.clone(), info.clone(), vec![msg]).unwrap_err();
     assert_eq!(err, Unauthorized {});
 }
 /// Checks that you can't change `good_til_purge_allowance` if you don't have the permission to do so
@@ -915,6 +1153,7 @@ pub fn test_execute_execute_message_update_params_dex_unauthorized_good_til_purg
     let info = message_info(&Addr::unchecked("neutron_dao_address"), &[]);
     execute_add_strategy(
         deps.as_mut(),
+        env.clone(),
         info.clone(),
         Addr::unchecked("addr1".to_string()),
         StrategyMsg::AllowOnly(vec![UpdateDexParamsPermission(DexUpdateParamsPermission {
@@ -923,12 +1162,17 @@ pub fn test_execute_execute_message_update_params_dex_unauthorized_good_til_purg
             max_jits_per_block: true,
             good_til_purge_allowance: false,
             whitelisted_lps: true,
+            constraints: None,
         })]),
+        None,
+        None,
+        None,
     )
     .unwrap();
 
     let info = message_info(&Addr::unchecked("addr1"), &[]);
-    let err = execute_execute_messages(deps.as_mut(), info.clone(), vec![msg]).unwrap_err();
+    let err =
+        execute_execute_messages(deps.as_mut(), env.clone(), info.clone(), vec![msg]).unwrap_err();
     assert_eq!(err, Unauthorized {});
 }
 
@@ -962,6 +1206,7 @@ pub fn test_execute_execute_message_update_params_dex_unauthorized_whitelisted_l
     let info = message_info(&Addr::unchecked("neutron_dao_address"), &[]);
     execute_add_strategy(
         deps.as_mut(),
+        env.clone(),
         info.clone(),
         Addr::unchecked("addr1".to_string()),
         StrategyMsg::AllowOnly(vec![UpdateDexParamsPermission(DexUpdateParamsPermission {
@@ -970,12 +1215,17 @@ pub fn test_execute_execute_message_update_params_dex_unauthorized_whitelisted_l
             max_jits_per_block: true,
             good_til_purge_allowance: true,
             whitelisted_lps: false,
+            constraints: None,
         })]),
+        None,
+        None,
+        None,
     )
     .unwrap();
 
     let info = message_info(&Addr::unchecked("addr1"), &[]);
-    let err = execute_execute_messages(deps.as_mut(), info.clone(), vec![msg]).unwrap_err();
+    let err =
+        execute_execute_messages(deps.as_mut(), env.clone(), info.clone(), vec![msg]).unwrap_err();
     assert_eq!(err, Unauthorized {});
 }
 
@@ -1008,22 +1258,34 @@ pub fn test_execute_execute_message_update_params_dynamicfees_authorized() {
     )
     .unwrap();
 
-    let err = execute_execute_messages(deps.as_mut(), non_priv_info.clone(), vec![msg.clone()])
-        .unwrap_err();
+    let err = execute_execute_messages(
+        deps.as_mut(),
+        env.clone(),
+        non_priv_info.clone(),
+        vec![msg.clone()],
+    )
+    .unwrap_err();
     assert_eq!(err, Unauthorized {});
 
     let info = message_info(&Addr::unchecked("neutron_dao_address"), &[]);
     execute_add_strategy(
         deps.as_mut(),
+        env.clone(),
         info.clone(),
         Addr::unchecked("addr1".to_string()),
         StrategyMsg::AllowOnly(vec![UpdateDynamicfeesParamsPermission(
-            DynamicFeesUpdateParamsPermission { ntrn_prices: true },
+            DynamicFeesUpdateParamsPermission {
+                ntrn_prices: true,
+                constraints: None,
+            },
         )]),
+        None,
+        None,
+        None,
     )
     .unwrap();
 
-    execute_execute_messages(deps.as_mut(), non_priv_info.clone(), vec![msg]).unwrap();
+    execute_execute_messages(deps.as_mut(), env.clone(), non_priv_info.clone(), vec![msg]).unwrap();
 }
 
 /// Checks that you can't change `ntrn_prices` if you don't have the permission to do so
@@ -1054,23 +1316,36 @@ pub fn test_execute_execute_message_update_params_dynamicfees_unauthorized() {
     )
     .unwrap();
 
-    let err = execute_execute_messages(deps.as_mut(), non_priv_info.clone(), vec![msg.clone()])
-        .unwrap_err();
+    let err = execute_execute_messages(
+        deps.as_mut(),
+        env.clone(),
+        non_priv_info.clone(),
+        vec![msg.clone()],
+    )
+    .unwrap_err();
     assert_eq!(err, Unauthorized {});
 
     let info = message_info(&Addr::unchecked("neutron_dao_address"), &[]);
     execute_add_strategy(
         deps.as_mut(),
+        env.clone(),
         info.clone(),
         Addr::unchecked("addr1".to_string()),
         StrategyMsg::AllowOnly(vec![UpdateDynamicfeesParamsPermission(
-            DynamicFeesUpdateParamsPermission { ntrn_prices: false },
+            DynamicFeesUpdateParamsPermission {
+                ntrn_prices: false,
+                constraints: None,
+            },
         )]),
+        None,
+        None,
+        None,
     )
     .unwrap();
 
     let err =
-        execute_execute_messages(deps.as_mut(), non_priv_info.clone(), vec![msg]).unwrap_err();
+        execute_execute_messages(deps.as_mut(), env.clone(), non_priv_info.clone(), vec![msg])
+            .unwrap_err();
     assert_eq!(err, Unauthorized {});
 }
 
@@ -1103,13 +1378,72 @@ pub fn test_execute_execute_message_update_params_globalfee_authorized() {
     )
     .unwrap();
 
-    let err = execute_execute_messages(deps.as_mut(), non_priv_info.clone(), vec![msg.clone()])
-        .unwrap_err();
+    let err = execute_execute_messages(
+        deps.as_mut(),
+        env.clone(),
+        non_priv_info.clone(),
+        vec![msg.clone()],
+    )
+    .unwrap_err();
     assert_eq!(err, Unauthorized {});
 
     let info = message_info(&Addr::unchecked("neutron_dao_address"), &[]);
     execute_add_strategy(
         deps.as_mut(),
+        env.clone(),
+        info.clone(),
+        Addr::unchecked("addr1".to_string()),
+        StrategyMsg::AllowOnly(vec![UpdateGlobalfeeParamsPermission(
+            GlobalfeeUpdateParamsPermission {
+                minimum_gas_prices: true,
+                bypass_min_fee_msg_types: true,
+                max_total_bypass_min_fee_msg_gas_usage: true,
+                constraints: None,
+            },
+        )]),
+        None,
+        None,
+        None,
+    )
+    .unwrap();
+
+    execute_execute_messages(deps.as_mut(), env.clone(), non_priv_info.clone(), vec![msg]).unwrap();
+}
+
+/// Checks that gas prices can only be set within the bands defined by the
+/// constraints of the permission.
+#[test]
+pub fn test_execute_execute_message_update_params_globalfee_constraints() {
+    let globalfee_update_msg = |minimum_gas_prices: &str| {
+        CosmosMsg::Custom(NeutronMsg::SubmitAdminProposal {
+            admin_proposal: AdminProposal::ProposalExecuteMessage(ProposalExecuteMessage {
+                message: format!(
+                    r#"{{"@type":"/gaia.globalfee.v1beta1.MsgUpdateParams",
+                    "authority":"neutron1hxskfdxpp5hqgtjj6am6nkjefhfzj359x0ar3z",
+                    "params": {{"minimum_gas_prices":{},"bypass_min_fee_msg_types":["allowedMsgType"],"max_total_bypass_min_fee_msg_gas_usage":10000}}}}"#,
+                    minimum_gas_prices
+                ),
+            }),
+        })
+    };
+
+    let mut deps = mock_dependencies();
+    let env = mock_env();
+    let info = message_info(&Addr::unchecked("neutron_dao_address"), &[]);
+
+    instantiate(
+        deps.as_mut(),
+        env.clone(),
+        info.clone(),
+        InstantiateMsg {
+            initial_strategy_address: Addr::unchecked("neutron_dao_address".to_string()),
+        },
+    )
+    .unwrap();
+
+    execute_add_strategy(
+        deps.as_mut(),
+        env.clone(),
         info.clone(),
         Addr::unchecked("addr1".to_string()),
         StrategyMsg::AllowOnly(vec![UpdateGlobalfeeParamsPermission(
@@ -1117,12 +1451,49 @@ pub fn test_execute_execute_message_update_params_globalfee_authorized() {
                 minimum_gas_prices: true,
                 bypass_min_fee_msg_types: true,
                 max_total_bypass_min_fee_msg_gas_usage: true,
+                constraints: Some(GlobalfeeUpdateParamsConstraints {
+                    minimum_gas_prices: Some(vec![PriceConstraint {
+                        denom: "untrn".to_string(),
+                        min: Some(Decimal::percent(1)),
+                        max: Some(Decimal::percent(10)),
+                    }]),
+                    bypass_min_fee_msg_types: None,
+                    max_total_bypass_min_fee_msg_gas_usage: None,
+                }),
             },
         )]),
+        None,
+        None,
+        None,
     )
     .unwrap();
 
-    execute_execute_messages(deps.as_mut(), non_priv_info.clone(), vec![msg]).unwrap();
+    let info = message_info(&Addr::unchecked("addr1"), &[]);
+
+    let msg = globalfee_update_msg(r#"[{"denom":"untrn","amount":"0.05"}]"#);
+    execute_execute_messages(deps.as_mut(), env.clone(), info.clone(), vec![msg]).unwrap();
+
+    // The price is out of the band.
+    let msg = globalfee_update_msg(r#"[{"denom":"untrn","amount":"0.5"}]"#);
+    let err =
+        execute_execute_messages(deps.as_mut(), env.clone(), info.clone(), vec![msg]).unwrap_err();
+    assert_eq!(
+        err,
+        ParamConstraintViolation {
+            param: "minimum_gas_prices".to_string()
+        }
+    );
+
+    // There is no band defined for the denom.
+    let msg = globalfee_update_msg(r#"[{"denom":"utia","amount":"0.05"}]"#);
+    let err =
+        execute_execute_messages(deps.as_mut(), env.clone(), info.clone(), vec![msg]).unwrap_err();
+    assert_eq!(
+        err,
+        ParamConstraintViolation {
+            param: "minimum_gas_prices".to_string()
+        }
+    );
 }
 
 /// Checks that you can't change `minimum_gas_prices` if you don't have the permission to do so
@@ -1153,13 +1524,19 @@ pub fn test_execute_execute_message_update_params_globalfee_minimum_gas_prices_u
     )
     .unwrap();
 
-    let err = execute_execute_messages(deps.as_mut(), non_priv_info.clone(), vec![msg.clone()])
-        .unwrap_err();
+    let err = execute_execute_messages(
+        deps.as_mut(),
+        env.clone(),
+        non_priv_info.clone(),
+        vec![msg.clone()],
+    )
+    .unwrap_err();
     assert_eq!(err, Unauthorized {});
 
     let info = message_info(&Addr::unchecked("neutron_dao_address"), &[]);
     execute_add_strategy(
         deps.as_mut(),
+        env.clone(),
         info.clone(),
         Addr::unchecked("addr1".to_string()),
         StrategyMsg::AllowOnly(vec![UpdateGlobalfeeParamsPermission(
@@ -1167,13 +1544,18 @@ pub fn test_execute_execute_message_update_params_globalfee_minimum_gas_prices_u
                 minimum_gas_prices: false,
                 bypass_min_fee_msg_types: true,
                 max_total_bypass_min_fee_msg_gas_usage: true,
+                constraints: None,
             },
         )]),
+        None,
+        None,
+        None,
     )
     .unwrap();
 
     let err =
-        execute_execute_messages(deps.as_mut(), non_priv_info.clone(), vec![msg]).unwrap_err();
+        execute_execute_messages(deps.as_mut(), env.clone(), non_priv_info.clone(), vec![msg])
+            .unwrap_err();
     assert_eq!(err, Unauthorized {});
 }
 
@@ -1206,13 +1588,19 @@ pub fn test_execute_execute_message_update_params_globalfee_bypass_min_fee_msg_t
     )
     .unwrap();
 
-    let err = execute_execute_messages(deps.as_mut(), non_priv_info.clone(), vec![msg.clone()])
-        .unwrap_err();
+    let err = execute_execute_messages(
+        deps.as_mut(),
+        env.clone(),
+        non_priv_info.clone(),
+        vec![msg.clone()],
+    )
+    .unwrap_err();
     assert_eq!(err, Unauthorized {});
 
     let info = message_info(&Addr::unchecked("neutron_dao_address"), &[]);
     execute_add_strategy(
         deps.as_mut(),
+        env.clone(),
         info.clone(),
         Addr::unchecked("addr1".to_string()),
         StrategyMsg::AllowOnly(vec![UpdateGlobalfeeParamsPermission(
@@ -1220,13 +1608,18 @@ pub fn test_execute_execute_message_update_params_globalfee_bypass_min_fee_msg_t
                 minimum_gas_prices: true,
                 bypass_min_fee_msg_types: false,
                 max_total_bypass_min_fee_msg_gas_usage: true,
+                constraints: None,
             },
         )]),
+        None,
+        None,
+        None,
     )
     .unwrap();
 
     let err =
-        execute_execute_messages(deps.as_mut(), non_priv_info.clone(), vec![msg]).unwrap_err();
+        execute_execute_messages(deps.as_mut(), env.clone(), non_priv_info.clone(), vec![msg])
+            .unwrap_err();
     assert_eq!(err, Unauthorized {});
 }
 
@@ -1259,13 +1652,19 @@ pub fn test_execute_execute_message_update_params_globalfee_max_total_bypass_min
     )
     .unwrap();
 
-    let err = execute_execute_messages(deps.as_mut(), non_priv_info.clone(), vec![msg.clone()])
-        .unwrap_err();
+    let err = execute_execute_messages(
+        deps.as_mut(),
+        env.clone(),
+        non_priv_info.clone(),
+        vec![msg.clone()],
+    )
+    .unwrap_err();
     assert_eq!(err, Unauthorized {});
 
     let info = message_info(&Addr::unchecked("neutron_dao_address"), &[]);
     execute_add_strategy(
         deps.as_mut(),
+        env.clone(),
         info.clone(),
         Addr::unchecked("addr1".to_string()),
         StrategyMsg::AllowOnly(vec![UpdateGlobalfeeParamsPermission(
@@ -1273,13 +1672,18 @@ pub fn test_execute_execute_message_update_params_globalfee_max_total_bypass_min
                 minimum_gas_prices: true,
                 bypass_min_fee_msg_types: true,
                 max_total_bypass_min_fee_msg_gas_usage: false,
+                constraints: None,
             },
         )]),
+        None,
+        None,
+        None,
     )
     .unwrap();
 
     let err =
-        execute_execute_messages(deps.as_mut(), non_priv_info.clone(), vec![msg]).unwrap_err();
+        execute_execute_messages(deps.as_mut(), env.clone(), non_priv_info.clone(), vec![msg])
+            .unwrap_err();
     assert_eq!(err, Unauthorized {});
 }
 
@@ -1316,13 +1720,79 @@ pub fn test_execute_execute_message_update_params_consumer_authorized() {
     )
     .unwrap();
 
-    let err = execute_execute_messages(deps.as_mut(), non_priv_info.clone(), vec![msg.clone()])
-        .unwrap_err();
+    let err = execute_execute_messages(
+        deps.as_mut(),
+        env.clone(),
+        non_priv_info.clone(),
+        vec![msg.clone()],
+    )
+    .unwrap_err();
     assert_eq!(err, Unauthorized {});
 
     let info = message_info(&Addr::unchecked("neutron_dao_address"), &[]);
     execute_add_strategy(
         deps.as_mut(),
+        env.clone(),
+        info.clone(),
+        Addr::unchecked("addr1".to_string()),
+        StrategyMsg::AllowOnly(vec![UpdateCCVParamsPermission(CCVUpdateParamsPermission {
+            blocks_per_distribution_transmission: true,
+            distribution_transmission_channel: true,
+            provider_fee_pool_addr_str: true,
+            ccv_timeout_period: true,
+            transfer_timeout_period: true,
+            consumer_redistribution_fraction: true,
+            historical_entries: true,
+            unbonding_period: true,
+            soft_opt_out_threshold: true,
+            reward_denoms: true,
+            provider_reward_denoms: true,
+            retry_delay_period: true,
+            constraints: None,
+        })]),
+        None,
+        None,
+        None,
+    )
+    .unwrap();
+
+    execute_execute_messages(deps.as_mut(), env.clone(), non_priv_info.clone(), vec![msg]).unwrap();
+}
+
+/// Checks that ccv parameters can only be changed to values allowed by the
+/// constraints of the permission.
+#[test]
+pub fn test_execute_execute_message_update_params_consumer_constraints() {
+    let consumer_update_msg = |params: &ConsumerParams| {
+        CosmosMsg::Custom(NeutronMsg::SubmitAdminProposal {
+            admin_proposal: AdminProposal::ProposalExecuteMessage(ProposalExecuteMessage {
+                message: format!(
+                    r#"{{"@type":"/interchain_security.ccv.consumer.v1.MsgUpdateParams",
+                    "authority":"neutron1hxskfdxpp5hqgtjj6am6nkjefhfzj359x0ar3z",
+                    "params": {}}}"#,
+                    serde_json_wasm::to_string(params).unwrap()
+                ),
+            }),
+        })
+    };
+
+    let mut deps = mock_dependencies();
+    let env = mock_env();
+    let info = message_info(&Addr::unchecked("neutron_dao_address"), &[]);
+
+    instantiate(
+        deps.as_mut(),
+        env.clone(),
+        info.clone(),
+        InstantiateMsg {
+            initial_strategy_address: Addr::unchecked("neutron_dao_address".to_string()),
+        },
+    )
+    .unwrap();
+
+    execute_add_strategy(
+        deps.as_mut(),
+        env.clone(),
         info.clone(),
         Addr::unchecked("addr1".to_string()),
         StrategyMsg::AllowOnly(vec![UpdateCCVParamsPermission(CCVUpdateParamsPermission {
@@ -1338,11 +1808,71 @@ pub fn test_execute_execute_message_update_params_consumer_authorized() {
             reward_denoms: true,
             provider_reward_denoms: true,
             retry_delay_period: true,
+            constraints: Some(CCVUpdateParamsConstraints {
+                historical_entries: Some(NumericConstraint {
+                    min: Some(10),
+                    max: Some(1000),
+                    allowed_values: None,
+                }),
+                unbonding_period: Some(NumericConstraint {
+                    min: Some(1),
+                    max: Some(10),
+                    allowed_values: None,
+                }),
+                ..Default::default()
+            }),
         })]),
+        None,
+        None,
+        None,
+    )
+    .unwrap();
+
+    let info = message_info(&Addr::unchecked("addr1"), &[]);
+
+    // Both values are within the range, and the other parameters are not constrained.
+    execute_execute_messages(
+        deps.as_mut(),
+        env.clone(),
+        info.clone(),
+        vec![consumer_update_msg(&consumer_params_to_update())],
     )
     .unwrap();
 
-    execute_execute_messages(deps.as_mut(), non_priv_info.clone(), vec![msg]).unwrap();
+    let mut params = default_consumer_params();
+    params.historical_entries = 1001;
+    let err = execute_execute_messages(
+        deps.as_mut(),
+        env.clone(),
+        info.clone(),
+        vec![consumer_update_msg(&params)],
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ParamConstraintViolation {
+            param: "historical_entries".to_string(),
+        }
+    );
+
+    let mut params = default_consumer_params();
+    params.unbonding_period = Some(Duration {
+        seconds: 11,
+        nanos: 0,
+    });
+    let err = execute_execute_messages(
+        deps.as_mut(),
+        env.clone(),
+        info.clone(),
+        vec![consumer_update_msg(&params)],
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ParamConstraintViolation {
+            param: "unbonding_period".to_string(),
+        }
+    );
 }
 
 /// Checks that you can't change `enabled`. It is not allowed to change `enabled` param.
@@ -1381,6 +1911,7 @@ pub fn test_execute_execute_message_update_params_consumer_disable_unauthorized(
     let info = message_info(&Addr::unchecked("neutron_dao_address"), &[]);
     execute_add_strategy(
         deps.as_mut(),
+        env.clone(),
         info.clone(),
         Addr::unchecked("addr1".to_string()),
         StrategyMsg::AllowOnly(vec![UpdateCCVParamsPermission(CCVUpdateParamsPermission {
@@ -1396,12 +1927,17 @@ pub fn test_execute_execute_message_update_params_consumer_disable_unauthorized(
             reward_denoms: true,
             provider_reward_denoms: true,
             retry_delay_period: true,
+            constraints: None,
         })]),
+        None,
+        None,
+        None,
     )
     .unwrap();
 
     let err =
-        execute_execute_messages(deps.as_mut(), non_priv_info.clone(), vec![msg]).unwrap_err();
+        execute_execute_messages(deps.as_mut(), env.clone(), non_priv_info.clone(), vec![msg])
+            .unwrap_err();
     assert_eq!(err, Unauthorized {});
 }
 
@@ -1441,6 +1977,7 @@ pub fn test_execute_execute_message_update_params_consumer_blocks_per_distributi
     let info = message_info(&Addr::unchecked("neutron_dao_address"), &[]);
     execute_add_strategy(
         deps.as_mut(),
+        env.clone(),
         info.clone(),
         Addr::unchecked("addr1".to_string()),
         StrategyMsg::AllowOnly(vec![UpdateCCVParamsPermission(CCVUpdateParamsPermission {
@@ -1456,12 +1993,17 @@ pub fn test_execute_execute_message_update_params_consumer_blocks_per_distributi
             reward_denoms: true,
             provider_reward_denoms: true,
             retry_delay_period: true,
+            constraints: None,
         })]),
+        None,
+        None,
+        None,
     )
     .unwrap();
 
     let err =
-        execute_execute_messages(deps.as_mut(), non_priv_info.clone(), vec![msg]).unwrap_err();
+        execute_execute_messages(deps.as_mut(), env.clone(), non_priv_info.clone(), vec![msg])
+            .unwrap_err();
     assert_eq!(err, Unauthorized {});
 }
 
@@ -1501,6 +2043,7 @@ pub fn test_execute_execute_message_update_params_consumer_distribution_transmis
     let info = message_info(&Addr::unchecked("neutron_dao_address"), &[]);
     execute_add_strategy(
         deps.as_mut(),
+        env.clone(),
         info.clone(),
         Addr::unchecked("addr1".to_string()),
         StrategyMsg::AllowOnly(vec![UpdateCCVParamsPermission(CCVUpdateParamsPermission {
@@ -1516,12 +2059,17 @@ pub fn test_execute_execute_message_update_params_consumer_distribution_transmis
             reward_denoms: true,
             provider_reward_denoms: true,
             retry_delay_period: true,
+            constraints: None,
         })]),
+        None,
+        None,
+        None,
     )
     .unwrap();
 
     let err =
-        execute_execute_messages(deps.as_mut(), non_priv_info.clone(), vec![msg]).unwrap_err();
+        execute_execute_messages(deps.as_mut(), env.clone(), non_priv_info.clone(), vec![msg])
+            .unwrap_err();
     assert_eq!(err, Unauthorized {});
 }
 
@@ -1561,6 +2109,7 @@ pub fn test_execute_execute_message_update_params_consumer_provider_fee_pool_add
     let info = message_info(&Addr::unchecked("neutron_dao_address"), &[]);
     execute_add_strategy(
         deps.as_mut(),
+        env.clone(),
         info.clone(),
         Addr::unchecked("addr1".to_string()),
         StrategyMsg::AllowOnly(vec![UpdateCCVParamsPermission(CCVUpdateParamsPermission {
@@ -1576,12 +2125,17 @@ pub fn test_execute_execute_message_update_params_consumer_provider_fee_pool_add
             reward_denoms: true,
             provider_reward_denoms: true,
             retry_delay_period: true,
+            constraints: None,
         })]),
+        None,
+        None,
+        None,
     )
     .unwrap();
 
     let err =
-        execute_execute_messages(deps.as_mut(), non_priv_info.clone(), vec![msg]).unwrap_err();
+        execute_execute_messages(deps.as_mut(), env.clone(), non_priv_info.clone(), vec![msg])
+            .unwrap_err();
     assert_eq!(err, Unauthorized {});
 }
 
@@ -1620,6 +2174,7 @@ pub fn test_execute_execute_message_update_params_consumer_ccv_timeout_period_un
     let info = message_info(&Addr::unchecked("neutron_dao_address"), &[]);
     execute_add_strategy(
         deps.as_mut(),
+        env.clone(),
         info.clone(),
         Addr::unchecked("addr1".to_string()),
         StrategyMsg::AllowOnly(vec![UpdateCCVParamsPermission(CCVUpdateParamsPermission {
@@ -1635,12 +2190,17 @@ pub fn test_execute_execute_message_update_params_consumer_ccv_timeout_period_un
             reward_denoms: true,
             provider_reward_denoms: true,
             retry_delay_period: true,
+            constraints: None,
         })]),
+        None,
+        None,
+        None,
     )
     .unwrap();
 
     let err =
-        execute_execute_messages(deps.as_mut(), non_priv_info.clone(), vec![msg]).unwrap_err();
+        execute_execute_messages(deps.as_mut(), env.clone(), non_priv_info.clone(), vec![msg])
+            .unwrap_err();
     assert_eq!(err, Unauthorized {});
 }
 
@@ -1679,6 +2239,7 @@ pub fn test_execute_execute_message_update_params_consumer_transfer_timeout_peri
     let info = message_info(&Addr::unchecked("neutron_dao_address"), &[]);
     execute_add_strategy(
         deps.as_mut(),
+        env.clone(),
         info.clone(),
         Addr::unchecked("addr1".to_string()),
         StrategyMsg::AllowOnly(vec![UpdateCCVParamsPermission(CCVUpdateParamsPermission {
@@ -1694,12 +2255,17 @@ pub fn test_execute_execute_message_update_params_consumer_transfer_timeout_peri
             reward_denoms: true,
             provider_reward_denoms: true,
             retry_delay_period: true,
+            constraints: None,
         })]),
+        None,
+        None,
+        None,
     )
     .unwrap();
 
     let err =
-        execute_execute_messages(deps.as_mut(), non_priv_info.clone(), vec![msg]).unwrap_err();
+        execute_execute_messages(deps.as_mut(), env.clone(), non_priv_info.clone(), vec![msg])
+            .unwrap_err();
     assert_eq!(err, Unauthorized {});
 }
 
@@ -1739,6 +2305,7 @@ pub fn test_execute_execute_message_update_params_consumer_consumer_redistributi
     let info = message_info(&Addr::unchecked("neutron_dao_address"), &[]);
     execute_add_strategy(
         deps.as_mut(),
+        env.clone(),
         info.clone(),
         Addr::unchecked("addr1".to_string()),
         StrategyMsg::AllowOnly(vec![UpdateCCVParamsPermission(CCVUpdateParamsPermission {
@@ -1754,12 +2321,17 @@ pub fn test_execute_execute_message_update_params_consumer_consumer_redistributi
             reward_denoms: true,
             provider_reward_denoms: true,
             retry_delay_period: true,
+            constraints: None,
         })]),
+        None,
+        None,
+        None,
     )
     .unwrap();
 
     let err =
-        execute_execute_messages(deps.as_mut(), non_priv_info.clone(), vec![msg]).unwrap_err();
+        execute_execute_messages(deps.as_mut(), env.clone(), non_priv_info.clone(), vec![msg])
+            .unwrap_err();
     assert_eq!(err, Unauthorized {});
 }
 
@@ -1798,6 +2370,7 @@ pub fn test_execute_execute_message_update_params_consumer_historical_entries_un
     let info = message_info(&Addr::unchecked("neutron_dao_address"), &[]);
     execute_add_strategy(
         deps.as_mut(),
+        env.clone(),
         info.clone(),
         Addr::unchecked("addr1".to_string()),
         StrategyMsg::AllowOnly(vec![UpdateCCVParamsPermission(CCVUpdateParamsPermission {
@@ -1813,12 +2386,17 @@ pub fn test_execute_execute_message_update_params_consumer_historical_entries_un
             reward_denoms: true,
             provider_reward_denoms: true,
             retry_delay_period: true,
+            constraints: None,
         })]),
+        None,
+        None,
+        None,
     )
     .unwrap();
 
     let err =
-        execute_execute_messages(deps.as_mut(), non_priv_info.clone(), vec![msg]).unwrap_err();
+        execute_execute_messages(deps.as_mut(), env.clone(), non_priv_info.clone(), vec![msg])
+            .unwrap_err();
     assert_eq!(err, Unauthorized {});
 }
 
@@ -1857,6 +2435,7 @@ pub fn test_execute_execute_message_update_params_consumer_unbonding_period_unau
     let info = message_info(&Addr::unchecked("neutron_dao_address"), &[]);
     execute_add_strategy(
         deps.as_mut(),
+        env.clone(),
         info.clone(),
         Addr::unchecked("addr1".to_string()),
         StrategyMsg::AllowOnly(vec![UpdateCCVParamsPermission(CCVUpdateParamsPermission {
@@ -1872,12 +2451,17 @@ pub fn test_execute_execute_message_update_params_consumer_unbonding_period_unau
             reward_denoms: true,
             provider_reward_denoms: true,
             retry_delay_period: true,
+            constraints: None,
         })]),
+        None,
+        None,
+        None,
     )
     .unwrap();
 
     let err =
-        execute_execute_messages(deps.as_mut(), non_priv_info.clone(), vec![msg]).unwrap_err();
+        execute_execute_messages(deps.as_mut(), env.clone(), non_priv_info.clone(), vec![msg])
+            .unwrap_err();
     assert_eq!(err, Unauthorized {});
 }
 
@@ -1916,6 +2500,7 @@ pub fn test_execute_execute_message_update_params_consumer_soft_opt_out_threshol
     let info = message_info(&Addr::unchecked("neutron_dao_address"), &[]);
     execute_add_strategy(
         deps.as_mut(),
+        env.clone(),
         info.clone(),
         Addr::unchecked("addr1".to_string()),
         StrategyMsg::AllowOnly(vec![UpdateCCVParamsPermission(CCVUpdateParamsPermission {
@@ -1931,12 +2516,17 @@ pub fn test_execute_execute_message_update_params_consumer_soft_opt_out_threshol
             reward_denoms: true,
             provider_reward_denoms: true,
             retry_delay_period: true,
+            constraints: None,
         })]),
+        None,
+        None,
+        None,
     )
     .unwrap();
 
     let err =
-        execute_execute_messages(deps.as_mut(), non_priv_info.clone(), vec![msg]).unwrap_err();
+        execute_execute_messages(deps.as_mut(), env.clone(), non_priv_info.clone(), vec![msg])
+            .unwrap_err();
     assert_eq!(err, Unauthorized {});
 }
 
@@ -1975,6 +2565,7 @@ pub fn test_execute_execute_message_update_params_consumer_reward_denoms_unautho
     let info = message_info(&Addr::unchecked("neutron_dao_address"), &[]);
     execute_add_strategy(
         deps.as_mut(),
+        env.clone(),
         info.clone(),
         Addr::unchecked("addr1".to_string()),
         StrategyMsg::AllowOnly(vec![UpdateCCVParamsPermission(CCVUpdateParamsPermission {
@@ -1990,12 +2581,17 @@ pub fn test_execute_execute_message_update_params_consumer_reward_denoms_unautho
             reward_denoms: false,
             provider_reward_denoms: true,
             retry_delay_period: true,
+            constraints: None,
         })]),
+        None,
+        None,
+        None,
     )
     .unwrap();
 
     let err =
-        execute_execute_messages(deps.as_mut(), non_priv_info.clone(), vec![msg]).unwrap_err();
+        execute_execute_messages(deps.as_mut(), env.clone(), non_priv_info.clone(), vec![msg])
+            .unwrap_err();
     assert_eq!(err, Unauthorized {});
 }
 
@@ -2034,6 +2630,7 @@ pub fn test_execute_execute_message_update_params_consumer_provider_reward_denom
     let info = message_info(&Addr::unchecked("neutron_dao_address"), &[]);
     execute_add_strategy(
         deps.as_mut(),
+        env.clone(),
         info.clone(),
         Addr::unchecked("addr1".to_string()),
         StrategyMsg::AllowOnly(vec![UpdateCCVParamsPermission(CCVUpdateParamsPermission {
@@ -2049,12 +2646,17 @@ pub fn test_execute_execute_message_update_params_consumer_provider_reward_denom
             reward_denoms: true,
             provider_reward_denoms: false,
             retry_delay_period: true,
+            constraints: None,
         })]),
+        None,
+        None,
+        None,
     )
     .unwrap();
 
     let err =
-        execute_execute_messages(deps.as_mut(), non_priv_info.clone(), vec![msg]).unwrap_err();
+        execute_execute_messages(deps.as_mut(), env.clone(), non_priv_info.clone(), vec![msg])
+            .unwrap_err();
     assert_eq!(err, Unauthorized {});
 }
 
@@ -2093,6 +2695,7 @@ pub fn test_execute_execute_message_update_params_consumer_retry_delay_period_un
     let info = message_info(&Addr::unchecked("neutron_dao_address"), &[]);
     execute_add_strategy(
         deps.as_mut(),
+        env.clone(),
         info.clone(),
         Addr::unchecked("addr1".to_string()),
         StrategyMsg::AllowOnly(vec![UpdateCCVParamsPermission(CCVUpdateParamsPermission {
@@ -2108,12 +2711,17 @@ pub fn test_execute_execute_message_update_params_consumer_retry_delay_period_un
             reward_denoms: true,
             provider_reward_denoms: true,
             retry_delay_period: false,
+            constraints: None,
         })]),
+        None,
+        None,
+        None,
     )
     .unwrap();
 
     let err =
-        execute_execute_messages(deps.as_mut(), non_priv_info.clone(), vec![msg]).unwrap_err();
+        execute_execute_messages(deps.as_mut(), env.clone(), non_priv_info.clone(), vec![msg])
+            .unwrap_err();
     assert_eq!(err, Unauthorized {});
 }
 
@@ -2149,6 +2757,7 @@ pub fn test_execute_execute_message_param_change_success() {
     let info = message_info(&Addr::unchecked("neutron_dao_address"), &[]);
     execute_add_strategy(
         deps.as_mut(),
+        env.clone(),
         info.clone(),
         Addr::unchecked("addr1".to_string()),
         StrategyMsg::AllowOnly(vec![ParamChangePermission(ParamChangePermissionType {
@@ -2157,11 +2766,14 @@ pub fn test_execute_execute_message_param_change_success() {
                 key: "MinimumGasPricesParam".to_string(),
             }],
         })]),
+        None,
+        None,
+        None,
     )
     .unwrap();
 
     let info = message_info(&Addr::unchecked("addr1"), &[]);
-    execute_execute_messages(deps.as_mut(), info.clone(), vec![msg]).unwrap();
+    execute_execute_messages(deps.as_mut(), env.clone(), info.clone(), vec![msg]).unwrap();
 }
 
 /// Checks that you can not update a legacy param without the necessary ALLOW_ONLY permission.
@@ -2197,6 +2809,7 @@ pub fn test_execute_execute_message_param_change_unauthorized_key() {
     let info = message_info(&Addr::unchecked("neutron_dao_address"), &[]);
     execute_add_strategy(
         deps.as_mut(),
+        env.clone(),
         info.clone(),
         Addr::unchecked("addr1".to_string()),
         StrategyMsg::AllowOnly(vec![ParamChangePermission(ParamChangePermissionType {
@@ -2205,11 +2818,15 @@ pub fn test_execute_execute_message_param_change_unauthorized_key() {
                 key: "0xdeadbeef".to_string(),
             }],
         })]),
+        None,
+        None,
+        None,
     )
     .unwrap();
 
     let info = message_info(&Addr::unchecked("addr1"), &[]);
-    let err = execute_execute_messages(deps.as_mut(), info.clone(), vec![msg]).unwrap_err();
+    let err =
+        execute_execute_messages(deps.as_mut(), env.clone(), info.clone(), vec![msg]).unwrap_err();
     assert_eq!(err, Unauthorized {});
 }
 
@@ -2246,6 +2863,7 @@ pub fn test_execute_execute_message_param_change_unauthorized_subspace() {
     let info = message_info(&Addr::unchecked("neutron_dao_address"), &[]);
     execute_add_strategy(
         deps.as_mut(),
+        env.clone(),
         info.clone(),
         Addr::unchecked("addr1".to_string()),
         StrategyMsg::AllowOnly(vec![ParamChangePermission(ParamChangePermissionType {
@@ -2254,11 +2872,15 @@ pub fn test_execute_execute_message_param_change_unauthorized_subspace() {
                 key: "MinimumGasPricesParam".to_string(),
             }],
         })]),
+        None,
+        None,
+        None,
     )
     .unwrap();
 
     let info = message_info(&Addr::unchecked("addr1"), &[]);
-    let err = execute_execute_messages(deps.as_mut(), info.clone(), vec![msg]).unwrap_err();
+    let err =
+        execute_execute_messages(deps.as_mut(), env.clone(), info.clone(), vec![msg]).unwrap_err();
     assert_eq!(err, Unauthorized {});
 }
 
@@ -2283,6 +2905,7 @@ pub fn test_execute_execute_unknown_message() {
     let info = message_info(&Addr::unchecked("neutron_dao_address"), &[]);
     execute_add_strategy(
         deps.as_mut(),
+        env.clone(),
         info.clone(),
         Addr::unchecked("addr1".to_string()),
         StrategyMsg::AllowOnly(vec![ParamChangePermission(ParamChangePermissionType {
@@ -2291,6 +2914,9 @@ pub fn test_execute_execute_unknown_message() {
                 key: "0xdeadbeef".to_string(),
             }],
         })]),
+        None,
+        None,
+        None,
     )
     .unwrap();
 
@@ -2299,7 +2925,8 @@ pub fn test_execute_execute_unknown_message() {
     });
 
     let info = message_info(&Addr::unchecked("addr1"), &[]);
-    let err = execute_execute_messages(deps.as_mut(), info.clone(), vec![msg]).unwrap_err();
+    let err =
+        execute_execute_messages(deps.as_mut(), env.clone(), info.clone(), vec![msg]).unwrap_err();
     assert_eq!(err, Unauthorized {});
 
     let msg = CosmosMsg::Custom(NeutronMsg::BurnTokens {
@@ -2309,6 +2936,1681 @@ pub fn test_execute_execute_unknown_message() {
     });
 
     let info = message_info(&Addr::unchecked("addr1"), &[]);
-    let err = execute_execute_messages(deps.as_mut(), info.clone(), vec![msg]).unwrap_err();
+    let err =
+        execute_execute_messages(deps.as_mut(), env.clone(), info.clone(), vec![msg]).unwrap_err();
     assert_eq!(err, Unauthorized {});
 }
+
+/// Checks that an expiration can only be set for ALLOW_ONLY strategies, and
+/// that it can not be in the past.
+#[test]
+fn test_add_strategy_invalid_expiration() {
+    let mut deps = mock_dependencies();
+    let env = mock_env();
+    let info = message_info(&Addr::unchecked("neutron_dao_address"), &[]);
+
+    instantiate(
+        deps.as_mut(),
+        env.clone(),
+        info.clone(),
+        InstantiateMsg {
+            initial_strategy_address: Addr::unchecked("neutron_dao_address".to_string()),
+        },
+    )
+    .unwrap();
+
+    let err = execute_add_strategy(
+        deps.as_mut(),
+        env.clone(),
+        info.clone(),
+        Addr::unchecked("addr1".to_string()),
+        StrategyMsg::AllowAll,
+        Some(Expiration::AtHeight(env.block.height + 10)),
+        None,
+        None,
+    )
+    .unwrap_err();
+    assert_eq!(err, InvalidExpiration {});
+
+    let err = execute_add_strategy(
+        deps.as_mut(),
+        env.clone(),
+        info.clone(),
+        Addr::unchecked("addr1".to_string()),
+        StrategyMsg::AllowOnly(vec![CronPermission(CronPermissionType {
+            add_schedule: true,
+            remove_schedule: true,
+            scope: None,
+        })]),
+        Some(Expiration::AtHeight(env.block.height)),
+        None,
+        None,
+    )
+    .unwrap_err();
+    assert_eq!(err, InvalidExpiration {});
+}
+
+/// Checks that an expired strategy can not execute messages, and that it is
+/// removed by anyone once pruned.
+#[test]
+fn test_execute_execute_messages_expired_strategy() {
+    let msg = CosmosMsg::Custom(NeutronMsg::SubmitAdminProposal {
+        admin_proposal: AdminProposal::ProposalExecuteMessage(ProposalExecuteMessage {
+            message: r#"{"@type":"/neutron.cron.MsgUpdateParams",
+            "authority":"neutron1hxskfdxpp5hqgtjj6am6nkjefhfzj359x0ar3z",
+            "params": {"security_address": "addr1", "limit": 16}}"#
+                .to_string(),
+        }),
+    });
+
+    let mut deps = mock_dependencies();
+    let mut env = mock_env();
+    let info = message_info(&Addr::unchecked("neutron_dao_address"), &[]);
+
+    instantiate(
+        deps.as_mut(),
+        env.clone(),
+        info.clone(),
+        InstantiateMsg {
+            initial_strategy_address: Addr::unchecked("neutron_dao_address".to_string()),
+        },
+    )
+    .unwrap();
+
+    let expiration = Expiration::AtHeight(env.block.height + 10);
+    execute_add_strategy(
+        deps.as_mut(),
+        env.clone(),
+        info.clone(),
+        Addr::unchecked("addr1".to_string()),
+        StrategyMsg::AllowOnly(vec![UpdateCronParamsPermission(
+            CronUpdateParamsPermission {
+                security_address: true,
+                limit: true,
+                constraints: None,
+            },
+        )]),
+        Some(expiration),
+        None,
+        None,
+    )
+    .unwrap();
+
+    let strategies = query_strategies(deps.as_ref()).unwrap();
+    assert_eq!(strategies.len(), 2);
+    assert_eq!(strategies[0].address, Addr::unchecked("addr1"));
+    assert_eq!(strategies[0].expiration, Some(expiration));
+    assert_eq!(strategies[1].expiration, None);
+
+    let non_priv_info = message_info(&Addr::unchecked("addr1"), &[]);
+    execute_execute_messages(
+        deps.as_mut(),
+        env.clone(),
+        non_priv_info.clone(),
+        vec![msg.clone()],
+    )
+    .unwrap();
+
+    // Nothing is pruned before the expiration.
+    execute_prune_expired_strategies(deps.as_mut(), env.clone(), None, None).unwrap();
+    assert_eq!(query_strategies(deps.as_ref()).unwrap().len(), 2);
+
+    env.block.height += 10;
+    let err = execute_execute_messages(
+        deps.as_mut(),
+        env.clone(),
+        non_priv_info.clone(),
+        vec![msg.clone()],
+    )
+    .unwrap_err();
+    assert_eq!(err, StrategyExpired {});
+
+    // Only the strategies after `start_after` are checked.
+    let res = execute_prune_expired_strategies(
+        deps.as_mut(),
+        env.clone(),
+        Some(Addr::unchecked("addr1")),
+        None,
+    )
+    .unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![
+            ("action", "execute_prune_expired_strategies"),
+            ("pruned_count", "0")
+        ]
+    );
+    let res = execute_prune_expired_strategies(deps.as_mut(), env.clone(), None, Some(1)).unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![
+            ("action", "execute_prune_expired_strategies"),
+            ("pruned_count", "1"),
+            ("last_address", "addr1")
+        ]
+    );
+    let strategies = query_strategies(deps.as_ref()).unwrap();
+    assert_eq!(strategies.len(), 1);
+    assert_eq!(
+        strategies[0].address,
+        Addr::unchecked("neutron_dao_address")
+    );
+
+    let err =
+        execute_execute_messages(deps.as_mut(), env.clone(), non_priv_info, vec![msg]).unwrap_err();
+    assert_eq!(err, Unauthorized {});
+}
+
+/// Checks that messages can be checked against a strategy without being
+/// executed, and that the missing permission is reported for every message.
+#[test]
+fn test_query_check_messages() {
+    let cron_update_msg = CosmosMsg::Custom(NeutronMsg::SubmitAdminProposal {
+        admin_proposal: AdminProposal::ProposalExecuteMessage(ProposalExecuteMessage {
+            message: r#"{"@type":"/neutron.cron.MsgUpdateParams",
+            "authority":"neutron1hxskfdxpp5hqgtjj6am6nkjefhfzj359x0ar3z",
+            "params": {"security_address": "addr1", "limit": 10}}"#
+                .to_string(),
+        }),
+    });
+    let cron_limit_update_msg = CosmosMsg::Custom(NeutronMsg::SubmitAdminProposal {
+        admin_proposal: AdminProposal::ProposalExecuteMessage(ProposalExecuteMessage {
+            message: r#"{"@type":"/neutron.cron.MsgUpdateParams",
+            "authority":"neutron1hxskfdxpp5hqgtjj6am6nkjefhfzj359x0ar3z",
+            "params": {"security_address": "neutron_dao_address", "limit": 16}}"#
+                .to_string(),
+        }),
+    });
+    let bank_msg = CosmosMsg::Bank(BankMsg::Burn {
+        amount: vec![Coin::new(Uint128::new(42u128), "untrn".to_string())],
+    });
+    let messages = vec![cron_update_msg, cron_limit_update_msg, bank_msg];
+
+    let mut deps = mock_dependencies();
+    let env = mock_env();
+    let info = message_info(&Addr::unchecked("neutron_dao_address"), &[]);
+
+    instantiate(
+        deps.as_mut(),
+        env.clone(),
+        info.clone(),
+        InstantiateMsg {
+            initial_strategy_address: Addr::unchecked("neutron_dao_address".to_string()),
+        },
+    )
+    .unwrap();
+
+    // An address without a strategy can not execute anything.
+    let checks = query_check_messages(
+        deps.as_ref(),
+        env.clone(),
+        Addr::unchecked("addr1"),
+        messages.clone(),
+    )
+    .unwrap();
+    assert_eq!(
+        checks,
+        vec![
+            MessageCheckResponse {
+                allowed: false,
+                missing_permission: None,
+                error: Some("Unauthorized".to_string()),
+            };
+            3
+        ]
+    );
+
+    execute_add_strategy(
+        deps.as_mut(),
+        env.clone(),
+        info.clone(),
+        Addr::unchecked("addr1".to_string()),
+        StrategyMsg::AllowOnly(vec![UpdateCronParamsPermission(
+            CronUpdateParamsPermission {
+                security_address: true,
+                limit: false,
+                constraints: None,
+            },
+        )]),
+        None,
+        None,
+        None,
+    )
+    .unwrap();
+
+    let checks = query_check_messages(
+        deps.as_ref(),
+        env.clone(),
+        Addr::unchecked("addr1"),
+        messages.clone(),
+    )
+    .unwrap();
+    assert_eq!(
+        checks,
+        vec![
+            MessageCheckResponse {
+                allowed: true,
+                missing_permission: None,
+                error: None,
+            },
+            MessageCheckResponse {
+                allowed: false,
+                missing_permission: Some("update_cron_params_permission.limit".to_string()),
+                error: Some("Unauthorized".to_string()),
+            },
+            MessageCheckResponse {
+                allowed: false,
+                missing_permission: None,
+                error: Some("Unauthorized".to_string()),
+            },
+        ]
+    );
+
+    // An ALLOW_ALL strategy can execute everything.
+    let checks = query_check_messages(
+        deps.as_ref(),
+        env.clone(),
+        Addr::unchecked("neutron_dao_address"),
+        messages,
+    )
+    .unwrap();
+    assert!(checks.iter().all(|check| check.allowed));
+}
+
+/// Checks that a rate limited strategy can only be used a limited number of
+/// times within the rate limit window.
+#[test]
+fn test_execute_execute_messages_rate_limit() {
+    let msg = CosmosMsg::Custom(NeutronMsg::SubmitAdminProposal {
+        admin_proposal: AdminProposal::ProposalExecuteMessage(ProposalExecuteMessage {
+            message: r#"{"@type":"/neutron.cron.MsgUpdateParams",
+            "authority":"neutron1hxskfdxpp5hqgtjj6am6nkjefhfzj359x0ar3z",
+            "params": {"security_address": "addr1", "limit": 16}}"#
+                .to_string(),
+        }),
+    });
+    let permissions = vec![UpdateCronParamsPermission(CronUpdateParamsPermission {
+        security_address: true,
+        limit: true,
+        constraints: None,
+    })];
+
+    let mut deps = mock_dependencies();
+    let mut env = mock_env();
+    let info = message_info(&Addr::unchecked("neutron_dao_address"), &[]);
+
+    instantiate(
+        deps.as_mut(),
+        env.clone(),
+        info.clone(),
+        InstantiateMsg {
+            initial_strategy_address: Addr::unchecked("neutron_dao_address".to_string()),
+        },
+    )
+    .unwrap();
+
+    // ALLOW_ALL strategies can not be rate limited.
+    let err = execute_add_strategy(
+        deps.as_mut(),
+        env.clone(),
+        info.clone(),
+        Addr::unchecked("addr1".to_string()),
+        StrategyMsg::AllowAll,
+        None,
+        Some(RateLimit {
+            window_blocks: 10,
+            max_executions: Some(2),
+            max_param_updates: None,
+        }),
+        None,
+    )
+    .unwrap_err();
+    assert_eq!(err, InvalidRateLimit {});
+
+    execute_add_strategy(
+        deps.as_mut(),
+        env.clone(),
+        info.clone(),
+        Addr::unchecked("addr1".to_string()),
+        StrategyMsg::AllowOnly(permissions.clone()),
+        None,
+        Some(RateLimit {
+            window_blocks: 10,
+            max_executions: Some(2),
+            max_param_updates: None,
+        }),
+        None,
+    )
+    .unwrap();
+    execute_add_strategy(
+        deps.as_mut(),
+        env.clone(),
+        info.clone(),
+        Addr::unchecked("addr2".to_string()),
+        StrategyMsg::AllowOnly(permissions.clone()),
+        None,
+        Some(RateLimit {
+            window_blocks: 10,
+            max_executions: None,
+            max_param_updates: Some(1),
+        }),
+        None,
+    )
+    .unwrap();
+
+    let addr1_info = message_info(&Addr::unchecked("addr1"), &[]);
+    execute_execute_messages(
+        deps.as_mut(),
+        env.clone(),
+        addr1_info.clone(),
+        vec![msg.clone()],
+    )
+    .unwrap();
+    env.block.height += 5;
+    execute_execute_messages(
+        deps.as_mut(),
+        env.clone(),
+        addr1_info.clone(),
+        vec![msg.clone()],
+    )
+    .unwrap();
+    let err = execute_execute_messages(
+        deps.as_mut(),
+        env.clone(),
+        addr1_info.clone(),
+        vec![msg.clone()],
+    )
+    .unwrap_err();
+    assert_eq!(err, RateLimitExceeded {});
+
+    // The first execution is out of the window now.
+    env.block.height += 5;
+    execute_execute_messages(
+        deps.as_mut(),
+        env.clone(),
+        addr1_info.clone(),
+        vec![msg.clone()],
+    )
+    .unwrap();
+
+    let addr2_info = message_info(&Addr::unchecked("addr2"), &[]);
+    let err = execute_execute_messages(
+        deps.as_mut(),
+        env.clone(),
+        addr2_info.clone(),
+        vec![msg.clone(), msg.clone()],
+    )
+    .unwrap_err();
+    assert_eq!(err, RateLimitExceeded {});
+    execute_execute_messages(
+        deps.as_mut(),
+        env.clone(),
+        addr2_info.clone(),
+        vec![msg.clone()],
+    )
+    .unwrap();
+
+    // Delegated strategies share the rate limit of the strategy they were
+    // delegated from.
+    execute_delegate_strategy(
+        deps.as_mut(),
+        env.clone(),
+        addr2_info.clone(),
+        Addr::unchecked("addr3".to_string()),
+        permissions.clone(),
+        None,
+    )
+    .unwrap();
+    let addr3_info = message_info(&Addr::unchecked("addr3"), &[]);
+    let err = execute_execute_messages(
+        deps.as_mut(),
+        env.clone(),
+        addr3_info.clone(),
+        vec![msg.clone()],
+    )
+    .unwrap_err();
+    assert_eq!(err, RateLimitExceeded {});
+
+    env.block.height += 10;
+    execute_execute_messages(deps.as_mut(), env.clone(), addr3_info, vec![msg.clone()]).unwrap();
+    let err = execute_execute_messages(
+        deps.as_mut(),
+        env.clone(),
+        addr2_info.clone(),
+        vec![msg.clone()],
+    )
+    .unwrap_err();
+    assert_eq!(err, RateLimitExceeded {});
+
+    // A delegated strategy can have a narrower rate limit than the strategy it
+    // was delegated from, and both are checked.
+    let err = execute_delegate_strategy(
+        deps.as_mut(),
+        env.clone(),
+        addr1_info.clone(),
+        Addr::unchecked("addr4".to_string()),
+        permissions.clone(),
+        Some(RateLimit {
+            window_blocks: 0,
+            max_executions: Some(1),
+            max_param_updates: None,
+        }),
+    )
+    .unwrap_err();
+    assert_eq!(err, InvalidRateLimit {});
+    execute_delegate_strategy(
+        deps.as_mut(),
+        env.clone(),
+        addr1_info.clone(),
+        Addr::unchecked("addr4".to_string()),
+        permissions,
+        Some(RateLimit {
+            window_blocks: 10,
+            max_executions: Some(1),
+            max_param_updates: None,
+        }),
+    )
+    .unwrap();
+    let addr4_info = message_info(&Addr::unchecked("addr4"), &[]);
+    execute_execute_messages(
+        deps.as_mut(),
+        env.clone(),
+        addr4_info.clone(),
+        vec![msg.clone()],
+    )
+    .unwrap();
+    let err = execute_execute_messages(
+        deps.as_mut(),
+        env.clone(),
+        addr4_info.clone(),
+        vec![msg.clone()],
+    )
+    .unwrap_err();
+    assert_eq!(err, RateLimitExceeded {});
+    execute_execute_messages(
+        deps.as_mut(),
+        env.clone(),
+        addr1_info.clone(),
+        vec![msg.clone()],
+    )
+    .unwrap();
+    let err =
+        execute_execute_messages(deps.as_mut(), env.clone(), addr1_info, vec![msg]).unwrap_err();
+    assert_eq!(err, RateLimitExceeded {});
+}
+
+/// Checks that contract admin messages can only be executed for the contracts
+/// and code IDs listed in the wasm admin permission.
+#[test]
+fn test_execute_execute_messages_wasm_admin() {
+    let mut deps = mock_dependencies();
+    let env = mock_env();
+    let info = message_info(&Addr::unchecked("neutron_dao_address"), &[]);
+
+    instantiate(
+        deps.as_mut(),
+        env.clone(),
+        info.clone(),
+        InstantiateMsg {
+            initial_strategy_address: Addr::unchecked("neutron_dao_address".to_string()),
+        },
+    )
+    .unwrap();
+
+    execute_add_strategy(
+        deps.as_mut(),
+        env.clone(),
+        info.clone(),
+        Addr::unchecked("addr1".to_string()),
+        StrategyMsg::AllowOnly(vec![WasmAdminPermission(WasmAdminPermissionType {
+            contracts: vec!["contract1".to_string()],
+            code_ids: vec![2],
+            migrate: true,
+            update_admin: true,
+            clear_admin: false,
+        })]),
+        None,
+        None,
+        None,
+    )
+    .unwrap();
+
+    let migrate_msg = |contract_addr: &str, new_code_id: u64| {
+        CosmosMsg::Wasm(WasmMsg::Migrate {
+            contract_addr: contract_addr.to_string(),
+            new_code_id,
+            msg: to_json_binary(&Empty {}).unwrap(),
+        })
+    };
+
+    let info = message_info(&Addr::unchecked("addr1"), &[]);
+    execute_execute_messages(
+        deps.as_mut(),
+        env.clone(),
+        info.clone(),
+        vec![
+            migrate_msg("contract1", 2),
+            CosmosMsg::Wasm(WasmMsg::UpdateAdmin {
+                contract_addr: "contract1".to_string(),
+                admin: "new_admin".to_string(),
+            }),
+        ],
+    )
+    .unwrap();
+
+    // The code ID is not allowed.
+    let err = execute_execute_messages(
+        deps.as_mut(),
+        env.clone(),
+        info.clone(),
+        vec![migrate_msg("contract1", 3)],
+    )
+    .unwrap_err();
+    assert_eq!(err, Unauthorized {});
+
+    // The contract is not allowed.
+    let err = execute_execute_messages(
+        deps.as_mut(),
+        env.clone(),
+        info.clone(),
+        vec![migrate_msg("contract2", 2)],
+    )
+    .unwrap_err();
+    assert_eq!(err, Unauthorized {});
+
+    let err = execute_execute_messages(
+        deps.as_mut(),
+        env.clone(),
+        info.clone(),
+        vec![CosmosMsg::Wasm(WasmMsg::ClearAdmin {
+            contract_addr: "contract1".to_string(),
+        })],
+    )
+    .unwrap_err();
+    assert_eq!(err, Unauthorized {});
+
+    // Other wasm messages are never allowed.
+    let err = execute_execute_messages(
+        deps.as_mut(),
+        env.clone(),
+        info,
+        vec![CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: "contract1".to_string(),
+            msg: to_json_binary(&Empty {}).unwrap(),
+            funds: vec![],
+        })],
+    )
+    .unwrap_err();
+    assert_eq!(err, Unauthorized {});
+}
+
+/// Checks that bank sends are limited by the recipients and the per-period
+/// amounts of the bank send permission.
+#[test]
+fn test_execute_execute_messages_bank_send() {
+    let mut deps = mock_dependencies();
+    let mut env = mock_env();
+    let info = message_info(&Addr::unchecked("neutron_dao_address"), &[]);
+
+    instantiate(
+        deps.as_mut(),
+        env.clone(),
+        info.clone(),
+        InstantiateMsg {
+            initial_strategy_address: Addr::unchecked("neutron_dao_address".to_string()),
+        },
+    )
+    .unwrap();
+
+    // The spent amounts of an empty period would never be reset.
+    let err = execute_add_strategy(
+        deps.as_mut(),
+        env.clone(),
+        info.clone(),
+        Addr::unchecked("addr1".to_string()),
+        StrategyMsg::AllowOnly(vec![BankSendPermission(BankSendPermissionType {
+            limits: vec![Coin::new(100u128, "untrn")],
+            period_blocks: 0,
+            allowed_recipients: Some(vec!["recipient".to_string()]),
+        })]),
+        None,
+        None,
+        None,
+    )
+    .unwrap_err();
+    assert_eq!(err, InvalidBankSendPermission {});
+
+    execute_add_strategy(
+        deps.as_mut(),
+        env.clone(),
+        info.clone(),
+        Addr::unchecked("addr1".to_string()),
+        StrategyMsg::AllowOnly(vec![BankSendPermission(BankSendPermissionType {
+            limits: vec![Coin::new(100u128, "untrn")],
+            period_blocks: 10,
+            allowed_recipients: Some(vec!["recipient".to_string()]),
+        })]),
+        None,
+        None,
+        None,
+    )
+    .unwrap();
+
+    let send_msg = |to_address: &str, amount: u128, denom: &str| {
+        CosmosMsg::Bank(BankMsg::Send {
+            to_address: to_address.to_string(),
+            amount: vec![Coin::new(amount, denom)],
+        })
+    };
+
+    let info = message_info(&Addr::unchecked("addr1"), &[]);
+    execute_execute_messages(
+        deps.as_mut(),
+        env.clone(),
+        info.clone(),
+        vec![send_msg("recipient", 60, "untrn")],
+    )
+    .unwrap();
+
+    let allowance =
+        query_bank_send_allowance(deps.as_ref(), env.clone(), Addr::unchecked("addr1")).unwrap();
+    assert_eq!(
+        allowance,
+        BankSendAllowanceResponse {
+            remaining: vec![Coin::new(40u128, "untrn")],
+            period_end_height: Some(env.block.height + 10),
+        }
+    );
+
+    // The limit of the current period is exceeded.
+    let err = execute_execute_messages(
+        deps.as_mut(),
+        env.clone(),
+        info.clone(),
+        vec![send_msg("recipient", 50, "untrn")],
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        BankSendLimitExceeded {
+            denom: "untrn".to_string()
+        }
+    );
+
+    // The recipient is not allowed.
+    let err = execute_execute_messages(
+        deps.as_mut(),
+        env.clone(),
+        info.clone(),
+        vec![send_msg("other", 10, "untrn")],
+    )
+    .unwrap_err();
+    assert_eq!(err, Unauthorized {});
+
+    // The denom has no limit.
+    let err = execute_execute_messages(
+        deps.as_mut(),
+        env.clone(),
+        info.clone(),
+        vec![send_msg("recipient", 10, "uatom")],
+    )
+    .unwrap_err();
+    assert_eq!(err, Unauthorized {});
+
+    // The allowance is replenished once the period is over.
+    env.block.height += 10;
+    execute_execute_messages(
+        deps.as_mut(),
+        env.clone(),
+        info.clone(),
+        vec![send_msg("recipient", 100, "untrn")],
+    )
+    .unwrap();
+
+    let allowance =
+        query_bank_send_allowance(deps.as_ref(), env.clone(), Addr::unchecked("addr1")).unwrap();
+    assert_eq!(allowance.remaining, vec![Coin::new(0u128, "untrn")]);
+}
+
+/// Checks that successful executions of ALLOW_ONLY strategies are recorded in
+/// the audit log, and that the log can be paginated by address and type URL.
+#[test]
+fn test_execute_execute_messages_audit_log() {
+    let update_params_msg = CosmosMsg::Custom(NeutronMsg::SubmitAdminProposal {
+        admin_proposal: AdminProposal::ProposalExecuteMessage(ProposalExecuteMessage {
+            message: r#"{"@type":"/neutron.cron.MsgUpdateParams",
+            "authority":"neutron1hxskfdxpp5hqgtjj6am6nkjefhfzj359x0ar3z",
+            "params": {"security_address": "new_security_address", "limit": 16}}"#
+                .to_string(),
+        }),
+    });
+    let remove_schedule_msg = CosmosMsg::Custom(NeutronMsg::RemoveSchedule {
+        name: "schedule".to_string(),
+    });
+
+    let mut deps = mock_dependencies();
+    let mut env = mock_env();
+    let info = message_info(&Addr::unchecked("neutron_dao_address"), &[]);
+
+    instantiate(
+        deps.as_mut(),
+        env.clone(),
+        info.clone(),
+        InstantiateMsg {
+            initial_strategy_address: Addr::unchecked("neutron_dao_address".to_string()),
+        },
+    )
+    .unwrap();
+
+    execute_add_strategy(
+        deps.as_mut(),
+        env.clone(),
+        info.clone(),
+        Addr::unchecked("addr1".to_string()),
+        StrategyMsg::AllowOnly(vec![
+            UpdateCronParamsPermission(CronUpdateParamsPermission {
+                security_address: true,
+                limit: true,
+                constraints: None,
+            }),
+            CronPermission(CronPermissionType {
+                add_schedule: false,
+                remove_schedule: true,
+                scope: None,
+            }),
+        ]),
+        None,
+        None,
+        None,
+    )
+    .unwrap();
+
+    // Executions with an ALLOW_ALL strategy are not recorded.
+    execute_execute_messages(
+        deps.as_mut(),
+        env.clone(),
+        info.clone(),
+        vec![remove_schedule_msg.clone()],
+    )
+    .unwrap();
+
+    let info = message_info(&Addr::unchecked("addr1"), &[]);
+    execute_execute_messages(
+        deps.as_mut(),
+        env.clone(),
+        info.clone(),
+        vec![update_params_msg, remove_schedule_msg.clone()],
+    )
+    .unwrap();
+
+    // Failed executions are not recorded.
+    env.block.height += 1;
+    execute_execute_messages(
+        deps.as_mut(),
+        env.clone(),
+        info.clone(),
+        vec![CosmosMsg::Bank(BankMsg::Burn {
+            amount: vec![Coin::new(42u128, "untrn")],
+        })],
+    )
+    .unwrap_err();
+
+    execute_execute_messages(
+        deps.as_mut(),
+        env.clone(),
+        info.clone(),
+        vec![remove_schedule_msg],
+    )
+    .unwrap();
+
+    let entries =
+        query_audit_log_by_address(deps.as_ref(), Addr::unchecked("addr1"), None, None).unwrap();
+    assert_eq!(entries.len(), 2);
+    assert_eq!(entries[0].id, 0);
+    assert_eq!(entries[0].sender, Addr::unchecked("addr1"));
+    assert_eq!(entries[0].height, env.block.height - 1);
+    assert_eq!(
+        entries[0].type_urls,
+        vec![
+            "/neutron.cron.MsgUpdateParams".to_string(),
+            "/neutron.cron.MsgRemoveSchedule".to_string(),
+        ]
+    );
+    assert_eq!(entries[0].param_updates.len(), 1);
+    let param_update = &entries[0].param_updates[0];
+    assert_eq!(param_update.param, "/neutron.cron.MsgUpdateParams");
+    assert!(param_update.before.is_some());
+    assert!(param_update.after.contains("new_security_address"));
+    assert_eq!(entries[1].id, 1);
+    assert!(entries[1].param_updates.is_empty());
+
+    let entries = query_audit_log_by_address(
+        deps.as_ref(),
+        Addr::unchecked("neutron_dao_address"),
+        None,
+        None,
+    )
+    .unwrap();
+    assert!(entries.is_empty());
+
+    let entries = query_audit_log_by_type_url(
+        deps.as_ref(),
+        "/neutron.cron.MsgRemoveSchedule".to_string(),
+        Some(0),
+        Some(10),
+    )
+    .unwrap();
+    assert_eq!(entries.len(), 1);
+    assert_eq!(entries[0].id, 1);
+
+    let entries = query_audit_log_by_type_url(
+        deps.as_ref(),
+        "/neutron.cron.MsgUpdateParams".to_string(),
+        None,
+        None,
+    )
+    .unwrap();
+    assert_eq!(entries.len(), 1);
+    assert_eq!(entries[0].id, 0);
+}
+
+/// Checks that the messages of a co-signed strategy are only dispatched once
+/// enough co-signers approve them, and that batches can be rejected and expire.
+#[test]
+fn test_execute_execute_messages_co_signed() {
+    let mut deps = mock_dependencies();
+    let mut env = mock_env();
+    let info = message_info(&Addr::unchecked("neutron_dao_address"), &[]);
+
+    instantiate(
+        deps.as_mut(),
+        env.clone(),
+        info.clone(),
+        InstantiateMsg {
+            initial_strategy_address: Addr::unchecked("neutron_dao_address".to_string()),
+        },
+    )
+    .unwrap();
+
+    let strategy = StrategyMsg::AllowOnly(vec![CronPermission(CronPermissionType {
+        add_schedule: false,
+        remove_schedule: true,
+        scope: None,
+    })]);
+    let co_signing = |threshold: u64| CoSigning {
+        co_signers: vec![
+            Addr::unchecked("co_signer1"),
+            Addr::unchecked("co_signer2"),
+            Addr::unchecked("co_signer3"),
+        ],
+        threshold,
+        deadline_blocks: 10,
+    };
+
+    // The threshold can not be reached.
+    let err = execute_add_strategy(
+        deps.as_mut(),
+        env.clone(),
+        info.clone(),
+        Addr::unchecked("addr1".to_string()),
+        strategy.clone(),
+        None,
+        None,
+        Some(co_signing(4)),
+    )
+    .unwrap_err();
+    assert_eq!(err, InvalidCoSigning {});
+
+    execute_add_strategy(
+        deps.as_mut(),
+        env.clone(),
+        info.clone(),
+        Addr::unchecked("addr1".to_string()),
+        strategy,
+        None,
+        None,
+        Some(co_signing(2)),
+    )
+    .unwrap();
+
+    let msg = CosmosMsg::Custom(NeutronMsg::RemoveSchedule {
+        name: "schedule".to_string(),
+    });
+    let info = message_info(&Addr::unchecked("addr1"), &[]);
+    let res = execute_execute_messages(deps.as_mut(), env.clone(), info.clone(), vec![msg.clone()])
+        .unwrap();
+    assert!(res.messages.is_empty());
+
+    // Only the co-signers can approve the batch, and only once.
+    let co_signer1 = message_info(&Addr::unchecked("co_signer1"), &[]);
+    let co_signer2 = message_info(&Addr::unchecked("co_signer2"), &[]);
+    let co_signer3 = message_info(&Addr::unchecked("co_signer3"), &[]);
+    let err = execute_approve_batch(deps.as_mut(), env.clone(), info.clone(), 0).unwrap_err();
+    assert_eq!(err, Unauthorized {});
+
+    let res = execute_approve_batch(deps.as_mut(), env.clone(), co_signer1.clone(), 0).unwrap();
+    assert!(res.messages.is_empty());
+    let err = execute_approve_batch(deps.as_mut(), env.clone(), co_signer1.clone(), 0).unwrap_err();
+    assert_eq!(err, AlreadyVoted {});
+
+    // The threshold is reached, so the messages are dispatched.
+    let res = execute_approve_batch(deps.as_mut(), env.clone(), co_signer2.clone(), 0).unwrap();
+    assert_eq!(res.messages.len(), 1);
+    assert_eq!(res.messages[0].msg, msg);
+    let batches = query_batches(deps.as_ref(), None, None).unwrap();
+    assert_eq!(batches[0].status, BatchStatus::Executed);
+    assert_eq!(
+        batches[0].approvals,
+        vec![Addr::unchecked("co_signer1"), Addr::unchecked("co_signer2")]
+    );
+
+    let err = execute_approve_batch(deps.as_mut(), env.clone(), co_signer3.clone(), 0).unwrap_err();
+    assert_eq!(err, BatchNotPending {});
+
+    // The batch is rejected once the threshold can no longer be reached.
+    execute_execute_messages(deps.as_mut(), env.clone(), info.clone(), vec![msg.clone()]).unwrap();
+    execute_reject_batch(deps.as_mut(), env.clone(), co_signer1.clone(), 1).unwrap();
+    let batches = query_batches(deps.as_ref(), Some(0), None).unwrap();
+    assert_eq!(batches[0].status, BatchStatus::Pending);
+    execute_reject_batch(deps.as_mut(), env.clone(), co_signer2.clone(), 1).unwrap();
+    let batches = query_batches(deps.as_ref(), Some(0), None).unwrap();
+    assert_eq!(batches[0].status, BatchStatus::Rejected);
+
+    // The batch can not be approved after the deadline.
+    execute_execute_messages(deps.as_mut(), env.clone(), info.clone(), vec![msg]).unwrap();
+    let err = execute_expire_batch(deps.as_mut(), env.clone(), 2).unwrap_err();
+    assert_eq!(err, BatchNotExpired {});
+
+    env.block.height += 10;
+    let err = execute_approve_batch(deps.as_mut(), env.clone(), co_signer1.clone(), 2).unwrap_err();
+    assert_eq!(err, BatchExpired {});
+    execute_expire_batch(deps.as_mut(), env.clone(), 2).unwrap();
+    let batches = query_batches(deps.as_ref(), Some(1), None).unwrap();
+    assert_eq!(batches.len(), 1);
+    assert_eq!(batches[0].status, BatchStatus::Expired);
+}
+
+/// Checks that the generic update params permission allows to change only the
+/// listed fields of a module without a dedicated permission.
+#[test]
+fn test_execute_execute_messages_generic_update_params() {
+    let update_params_msg = |max_messages: &str, register_fee: &str| {
+        CosmosMsg::Custom(NeutronMsg::SubmitAdminProposal {
+            admin_proposal: AdminProposal::ProposalExecuteMessage(ProposalExecuteMessage {
+                message: format!(
+                    r#"{{"@type":"/neutron.interchaintxs.v1.MsgUpdateParams",
+                    "authority":"neutron1hxskfdxpp5hqgtjj6am6nkjefhfzj359x0ar3z",
+                    "params": {{"msg_submit_tx_max_messages": {},
+                    "register_fee": [{{"denom": "untrn", "amount": "{}"}}]}}}}"#,
+                    max_messages, register_fee
+                ),
+            }),
+        })
+    };
+
+    let mut deps = mock_dependencies();
+    let env = mock_env();
+    let info = message_info(&Addr::unchecked("neutron_dao_address"), &[]);
+
+    instantiate(
+        deps.as_mut(),
+        env.clone(),
+        info.clone(),
+        InstantiateMsg {
+            initial_strategy_address: Addr::unchecked("neutron_dao_address".to_string()),
+        },
+    )
+    .unwrap();
+
+    execute_add_strategy(
+        deps.as_mut(),
+        env.clone(),
+        info.clone(),
+        Addr::unchecked("addr1".to_string()),
+        StrategyMsg::AllowOnly(vec![UpdateParamsPermission(UpdateParamsPermissionType {
+            type_url: "/neutron.interchaintxs.v1.MsgUpdateParams".to_string(),
+            params_query_path: "/neutron.interchaintxs.v1.Query/Params".to_string(),
+            fields: vec!["msg_submit_tx_max_messages".to_string()],
+        })]),
+        None,
+        None,
+        None,
+    )
+    .unwrap();
+
+    let info = message_info(&Addr::unchecked("addr1"), &[]);
+
+    // Numbers and strings with the same value are considered equal.
+    execute_execute_messages(
+        deps.as_mut(),
+        env.clone(),
+        info.clone(),
+        vec![update_params_msg("16", "1000000")],
+    )
+    .unwrap();
+
+    execute_execute_messages(
+        deps.as_mut(),
+        env.clone(),
+        info.clone(),
+        vec![update_params_msg("\"20\"", "1000000")],
+    )
+    .unwrap();
+
+    // The audit log has the current and the new parameters.
+    let entries =
+        query_audit_log_by_address(deps.as_ref(), Addr::unchecked("addr1"), None, None).unwrap();
+    let param_update = &entries[1].param_updates[0];
+    assert_eq!(
+        param_update.param,
+        "/neutron.interchaintxs.v1.MsgUpdateParams"
+    );
+    assert_eq!(
+        param_update.before,
+        Some(
+            r#"{"msg_submit_tx_max_messages":"16","register_fee":[{"amount":"1000000","denom":"untrn"}]}"#
+                .to_string()
+        )
+    );
+    assert_eq!(
+        param_update.after,
+        r#"{"msg_submit_tx_max_messages":"20","register_fee":[{"amount":"1000000","denom":"untrn"}]}"#
+    );
+
+    let err = execute_execute_messages(
+        deps.as_mut(),
+        env.clone(),
+        info.clone(),
+        vec![update_params_msg("16", "2000000")],
+    )
+    .unwrap_err();
+    assert_eq!(err, Unauthorized {});
+
+    // There is no permission for the type URL.
+    let err = execute_execute_messages(
+        deps.as_mut(),
+        env.clone(),
+        info.clone(),
+        vec![CosmosMsg::Custom(NeutronMsg::SubmitAdminProposal {
+            admin_proposal: AdminProposal::ProposalExecuteMessage(ProposalExecuteMessage {
+                message: r#"{"@type":"/neutron.feeburner.MsgUpdateParams",
+                "authority":"neutron1hxskfdxpp5hqgtjj6am6nkjefhfzj359x0ar3z",
+                "params": {"treasury_address": "addr1"}}"#
+                    .to_string(),
+            }),
+        })],
+    )
+    .unwrap_err();
+    assert_eq!(err, Unauthorized {});
+}
+
+/// Checks that schedules can only be added and removed within the scope of the
+/// cron permission.
+#[test]
+fn test_execute_execute_messages_cron_scope() {
+    let add_schedule_msg = |name: &str, period: u64, contract: &str, stage: &str| {
+        CosmosMsg::Custom(NeutronMsg::SubmitAdminProposal {
+            admin_proposal: AdminProposal::ProposalExecuteMessage(ProposalExecuteMessage {
+                message: format!(
+                    r#"{{"@type":"/neutron.cron.MsgAddSchedule",
+                    "authority":"neutron1hxskfdxpp5hqgtjj6am6nkjefhfzj359x0ar3z",
+                    "name":"{}","period":"{}",
+                    "msgs":[{{"contract":"{}","msg":"{{}}"}}],
+                    "execution_stage":"{}"}}"#,
+                    name, period, contract, stage
+                ),
+            }),
+        })
+    };
+    let bindings_add_schedule_msg = |name: &str, period: u64, contract: &str, stage: &str| {
+        CosmosMsg::Custom(NeutronMsg::AddSchedule {
+            name: name.to_string(),
+            period,
+            msgs: vec![MsgExecuteContract {
+                contract: contract.to_string(),
+                msg: "{}".to_string(),
+            }],
+            execution_stage: stage.to_string(),
+        })
+    };
+    let remove_schedule_msg = |name: &str| {
+        CosmosMsg::Custom(NeutronMsg::RemoveSchedule {
+            name: name.to_string(),
+        })
+    };
+
+    let mut deps = mock_dependencies();
+    let env = mock_env();
+    let info = message_info(&Addr::unchecked("neutron_dao_address"), &[]);
+
+    instantiate(
+        deps.as_mut(),
+        env.clone(),
+        info.clone(),
+        InstantiateMsg {
+            initial_strategy_address: Addr::unchecked("neutron_dao_address".to_string()),
+        },
+    )
+    .unwrap();
+
+    execute_add_strategy(
+        deps.as_mut(),
+        env.clone(),
+        info.clone(),
+        Addr::unchecked("addr1".to_string()),
+        StrategyMsg::AllowOnly(vec![CronPermission(CronPermissionType {
+            add_schedule: true,
+            remove_schedule: true,
+            scope: Some(CronPermissionScope {
+                name_prefix: Some("automation_".to_string()),
+                contracts: Some(vec!["contract1".to_string()]),
+                execution_stages: Some(vec![CronExecutionStage::EndBlocker]),
+                min_period: Some(10),
+                max_period: Some(100),
+            }),
+        })]),
+        None,
+        None,
+        None,
+    )
+    .unwrap();
+
+    let info = message_info(&Addr::unchecked("addr1"), &[]);
+    execute_execute_messages(
+        deps.as_mut(),
+        env.clone(),
+        info.clone(),
+        vec![
+            add_schedule_msg(
+                "automation_rebalance",
+                50,
+                "contract1",
+                "EXECUTION_STAGE_END_BLOCKER",
+            ),
+            bindings_add_schedule_msg(
+                "automation_compound",
+                50,
+                "contract1",
+                "EXECUTION_STAGE_END_BLOCKER",
+            ),
+            remove_schedule_msg("automation_rebalance"),
+        ],
+    )
+    .unwrap();
+
+    let cases = vec![
+        (
+            add_schedule_msg("protocol", 50, "contract1", "EXECUTION_STAGE_END_BLOCKER"),
+            "cron_permission.scope.name_prefix",
+        ),
+        (
+            add_schedule_msg(
+                "automation_rebalance",
+                50,
+                "contract2",
+                "EXECUTION_STAGE_END_BLOCKER",
+            ),
+            "cron_permission.scope.contracts",
+        ),
+        (
+            add_schedule_msg(
+                "automation_rebalance",
+                50,
+                "contract1",
+                "EXECUTION_STAGE_BEGIN_BLOCKER",
+            ),
+            "cron_permission.scope.execution_stages",
+        ),
+        (
+            bindings_add_schedule_msg(
+                "automation_rebalance",
+                50,
+                "contract1",
+                "EXECUTION_STAGE_BEGIN_BLOCKER",
+            ),
+            "cron_permission.scope.execution_stages",
+        ),
+        (
+            add_schedule_msg(
+                "automation_rebalance",
+                5,
+                "contract1",
+                "EXECUTION_STAGE_END_BLOCKER",
+            ),
+            "cron_permission.scope.period",
+        ),
+        (
+            remove_schedule_msg("protocol"),
+            "cron_permission.scope.name_prefix",
+        ),
+    ];
+    for (msg, permission) in cases {
+        let err =
+            execute_execute_messages(deps.as_mut(), env.clone(), info.clone(), vec![msg.clone()])
+                .unwrap_err();
+        assert_eq!(err, Unauthorized {});
+
+        let checks =
+            query_check_messages(deps.as_ref(), env.clone(), info.sender.clone(), vec![msg])
+                .unwrap();
+        assert_eq!(checks[0].missing_permission, Some(permission.to_string()));
+    }
+}
+
+/// Checks that software upgrades can only be scheduled and cancelled within the
+/// constraints of the software upgrade permission.
+#[test]
+fn test_execute_execute_messages_software_upgrade_constraints() {
+    let software_upgrade_msg = |name: &str, height: u64| {
+        CosmosMsg::Custom(NeutronMsg::SubmitAdminProposal {
+            admin_proposal: AdminProposal::ProposalExecuteMessage(ProposalExecuteMessage {
+                message: format!(
+                    r#"{{"@type":"/cosmos.upgrade.v1beta1.MsgSoftwareUpgrade",
+                    "authority":"neutron1hxskfdxpp5hqgtjj6am6nkjefhfzj359x0ar3z",
+                    "plan":{{"name":"{}","height":"{}","info":""}}}}"#,
+                    name, height
+                ),
+            }),
+        })
+    };
+    let cancel_upgrade_msg = CosmosMsg::Custom(NeutronMsg::SubmitAdminProposal {
+        admin_proposal: AdminProposal::ProposalExecuteMessage(ProposalExecuteMessage {
+            message: r#"{"@type":"/cosmos.upgrade.v1beta1.MsgCancelUpgrade",
+            "authority":"neutron1hxskfdxpp5hqgtjj6am6nkjefhfzj359x0ar3z"}"#
+                .to_string(),
+        }),
+    });
+
+    let mut deps = mock_dependencies();
+    let env = mock_env();
+    let info = message_info(&Addr::unchecked("neutron_dao_address"), &[]);
+
+    instantiate(
+        deps.as_mut(),
+        env.clone(),
+        info.clone(),
+        InstantiateMsg {
+            initial_strategy_address: Addr::unchecked("neutron_dao_address".to_string()),
+        },
+    )
+    .unwrap();
+
+    let strategy = |cancel_upgrade_freeze_blocks: u64| {
+        StrategyMsg::AllowOnly(vec![SoftwareUpgradePermission(
+            SoftwareUpgradePermissionType {
+                upgrade: true,
+                cancel_upgrade: true,
+                constraints: Some(SoftwareUpgradeConstraints {
+                    min_upgrade_delay_blocks: Some(1000),
+                    name_prefix: Some("v".to_string()),
+                    cancel_upgrade_freeze_blocks: Some(cancel_upgrade_freeze_blocks),
+                }),
+            },
+        )])
+    };
+    for (address, cancel_upgrade_freeze_blocks) in [("addr1", 1000), ("addr2", 500)] {
+        execute_add_strategy(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            Addr::unchecked(address),
+            strategy(cancel_upgrade_freeze_blocks),
+            None,
+            None,
+            None,
+        )
+        .unwrap();
+    }
+
+    let info = message_info(&Addr::unchecked("addr1"), &[]);
+    execute_execute_messages(
+        deps.as_mut(),
+        env.clone(),
+        info.clone(),
+        vec![software_upgrade_msg("v6.0.0", env.block.height + 1000)],
+    )
+    .unwrap();
+
+    let err = execute_execute_messages(
+        deps.as_mut(),
+        env.clone(),
+        info.clone(),
+        vec![software_upgrade_msg("v6.0.0", env.block.height + 999)],
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        SoftwareUpgradeConstraintViolation {
+            constraint: "min_upgrade_delay_blocks".to_string()
+        }
+    );
+
+    let err = execute_execute_messages(
+        deps.as_mut(),
+        env.clone(),
+        info.clone(),
+        vec![software_upgrade_msg("backdoor", env.block.height + 1000)],
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        SoftwareUpgradeConstraintViolation {
+            constraint: "name_prefix".to_string()
+        }
+    );
+
+    // The current upgrade plan is within the freeze period of addr1, but not
+    // within the freeze period of addr2.
+    assert!(CURRENT_UPGRADE_PLAN_HEIGHT <= (env.block.height + 1000) as i64);
+    assert!(CURRENT_UPGRADE_PLAN_HEIGHT > (env.block.height + 500) as i64);
+    let err = execute_execute_messages(
+        deps.as_mut(),
+        env.clone(),
+        info.clone(),
+        vec![cancel_upgrade_msg.clone()],
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        SoftwareUpgradeConstraintViolation {
+            constraint: "cancel_upgrade_freeze_blocks".to_string()
+        }
+    );
+
+    let info = message_info(&Addr::unchecked("addr2"), &[]);
+    execute_execute_messages(
+        deps.as_mut(),
+        env.clone(),
+        info,
+        vec![cancel_upgrade_msg.clone()],
+    )
+    .unwrap();
+
+    // Constraints past the maximum plan height can not be satisfied.
+    let info = message_info(&Addr::unchecked("neutron_dao_address"), &[]);
+    execute_add_strategy(
+        deps.as_mut(),
+        env.clone(),
+        info,
+        Addr::unchecked("addr3"),
+        StrategyMsg::AllowOnly(vec![SoftwareUpgradePermission(
+            SoftwareUpgradePermissionType {
+                upgrade: true,
+                cancel_upgrade: true,
+                constraints: Some(SoftwareUpgradeConstraints {
+                    min_upgrade_delay_blocks: Some(u64::MAX),
+                    name_prefix: None,
+                    cancel_upgrade_freeze_blocks: Some(u64::MAX),
+                }),
+            },
+        )]),
+        None,
+        None,
+        None,
+    )
+    .unwrap();
+
+    let info = message_info(&Addr::unchecked("addr3"), &[]);
+    let err = execute_execute_messages(
+        deps.as_mut(),
+        env.clone(),
+        info.clone(),
+        vec![software_upgrade_msg("v6.0.0", i64::MAX as u64)],
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        SoftwareUpgradeConstraintViolation {
+            constraint: "min_upgrade_delay_blocks".to_string()
+        }
+    );
+    let err =
+        execute_execute_messages(deps.as_mut(), env, info, vec![cancel_upgrade_msg]).unwrap_err();
+    assert_eq!(
+        err,
+        SoftwareUpgradeConstraintViolation {
+            constraint: "cancel_upgrade_freeze_blocks".to_string()
+        }
+    );
+}
+
+#[test]
+fn test_execute_delegate_strategy() {
+    let cron_permission = |add_schedule: bool, remove_schedule: bool| {
+        CronPermission(CronPermissionType {
+            add_schedule,
+            remove_schedule,
+            scope: None,
+        })
+    };
+    let wasm_admin_permission = |contracts: Vec<&str>| {
+        WasmAdminPermission(WasmAdminPermissionType {
+            contracts: contracts.iter().map(|c| c.to_string()).collect(),
+            code_ids: vec![],
+            migrate: true,
+            update_admin: false,
+            clear_admin: false,
+        })
+    };
+
+    let mut deps = mock_dependencies();
+    let env = mock_env();
+    let info = message_info(&Addr::unchecked("neutron_dao_address"), &[]);
+
+    instantiate(
+        deps.as_mut(),
+        env.clone(),
+        info.clone(),
+        InstantiateMsg {
+            initial_strategy_address: Addr::unchecked("neutron_dao_address".to_string()),
+        },
+    )
+    .unwrap();
+
+    execute_add_strategy(
+        deps.as_mut(),
+        env.clone(),
+        info.clone(),
+        Addr::unchecked("addr1".to_string()),
+        StrategyMsg::AllowOnly(vec![
+            cron_permission(true, true),
+            wasm_admin_permission(vec!["contract1", "contract2"]),
+        ]),
+        Some(Expiration::AtHeight(env.block.height + 100)),
+        None,
+        None,
+    )
+    .unwrap();
+    execute_add_strategy(
+        deps.as_mut(),
+        env.clone(),
+        info.clone(),
+        Addr::unchecked("addr4".to_string()),
+        StrategyMsg::AllowOnly(vec![cron_permission(true, false)]),
+        None,
+        None,
+        None,
+    )
+    .unwrap();
+
+    let addr1_info = message_info(&Addr::unchecked("addr1"), &[]);
+    execute_delegate_strategy(
+        deps.as_mut(),
+        env.clone(),
+        addr1_info.clone(),
+        Addr::unchecked("addr2"),
+        vec![
+            cron_permission(false, true),
+            wasm_admin_permission(vec!["contract1"]),
+        ],
+        None,
+    )
+    .unwrap();
+
+    // Permissions which are not a subset of the delegator permissions can't
+    // be delegated.
+    let err = execute_delegate_strategy(
+        deps.as_mut(),
+        env.clone(),
+        addr1_info.clone(),
+        Addr::unchecked("addr3"),
+        vec![wasm_admin_permission(vec!["contract3"])],
+        None,
+    )
+    .unwrap_err();
+    assert_eq!(err, InvalidDelegation {});
+    let err = execute_delegate_strategy(
+        deps.as_mut(),
+        env.clone(),
+        addr1_info.clone(),
+        Addr::unchecked("addr3"),
+        vec![ParamChangePermission(ParamChangePermissionType {
+            params: vec![ParamPermission {
+                subspace: "globalfee".to_string(),
+                key: "MaxTotalBypassMinFeeMsgGasUsage".to_string(),
+            }],
+        })],
+        None,
+    )
+    .unwrap_err();
+    assert_eq!(err, InvalidDelegation {});
+
+    // A strategy added by the DAO can't be overwritten by a delegation.
+    let err = execute_delegate_strategy(
+        deps.as_mut(),
+        env.clone(),
+        addr1_info.clone(),
+        Addr::unchecked("addr4"),
+        vec![cron_permission(true, false)],
+        None,
+    )
+    .unwrap_err();
+    assert_eq!(err, Unauthorized {});
+
+    let addr2_info = message_info(&Addr::unchecked("addr2"), &[]);
+    let err = execute_delegate_strategy(
+        deps.as_mut(),
+        env.clone(),
+        addr2_info.clone(),
+        Addr::unchecked("addr3"),
+        vec![cron_permission(true, false)],
+        None,
+    )
+    .unwrap_err();
+    assert_eq!(err, InvalidDelegation {});
+    execute_delegate_strategy(
+        deps.as_mut(),
+        env.clone(),
+        addr2_info.clone(),
+        Addr::unchecked("addr3"),
+        vec![cron_permission(false, true)],
+        None,
+    )
+    .unwrap();
+
+    let strategies = query_strategies(deps.as_ref()).unwrap();
+    let addr2 = strategies
+        .iter()
+        .find(|s| s.address == Addr::unchecked("addr2"))
+        .unwrap();
+    assert_eq!(addr2.parent, Some(Addr::unchecked("addr1")));
+    assert_eq!(
+        addr2.expiration,
+        Some(Expiration::AtHeight(env.block.height + 100))
+    );
+    let addr3 = strategies
+        .iter()
+        .find(|s| s.address == Addr::unchecked("addr3"))
+        .unwrap();
+    assert_eq!(addr3.parent, Some(Addr::unchecked("addr2")));
+
+    // The depth of the delegations and the number of strategies delegated from
+    // a single strategy are capped.
+    let err = execute_delegate_strategy(
+        deps.as_mut(),
+        env.clone(),
+        message_info(&Addr::unchecked("addr3"), &[]),
+        Addr::unchecked("addr5"),
+        vec![cron_permission(false, true)],
+        None,
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        DelegationTooDeep {
+            max: MAX_DELEGATION_DEPTH
+        }
+    );
+    let addr4_info = message_info(&Addr::unchecked("addr4"), &[]);
+    for i in 0..MAX_DELEGATED_STRATEGIES {
+        execute_delegate_strategy(
+            deps.as_mut(),
+            env.clone(),
+            addr4_info.clone(),
+            Addr::unchecked(format!("delegate{}", i)),
+            vec![cron_permission(true, false)],
+            None,
+        )
+        .unwrap();
+    }
+    let err = execute_delegate_strategy(
+        deps.as_mut(),
+        env.clone(),
+        addr4_info.clone(),
+        Addr::unchecked("addr5"),
+        vec![cron_permission(true, false)],
+        None,
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        TooManyDelegatedStrategies {
+            max: MAX_DELEGATED_STRATEGIES
+        }
+    );
+    // Replacing a delegated strategy is still possible.
+    execute_delegate_strategy(
+        deps.as_mut(),
+        env.clone(),
+        addr4_info.clone(),
+        Addr::unchecked("delegate0"),
+        vec![cron_permission(true, false)],
+        None,
+    )
+    .unwrap();
+    for i in 0..MAX_DELEGATED_STRATEGIES {
+        execute_revoke_delegated_strategy(
+            deps.as_mut(),
+            addr4_info.clone(),
+            Addr::unchecked(format!("delegate{}", i)),
+        )
+        .unwrap();
+    }
+
+    // Only the direct delegator can revoke a delegated strategy.
+    let err = execute_revoke_delegated_strategy(
+        deps.as_mut(),
+        addr1_info.clone(),
+        Addr::unchecked("addr3"),
+    )
+    .unwrap_err();
+    assert_eq!(err, Unauthorized {});
+    execute_revoke_delegated_strategy(deps.as_mut(), addr2_info, Addr::unchecked("addr3")).unwrap();
+    assert_eq!(query_strategies(deps.as_ref()).unwrap().len(), 4);
+
+    // Removing a strategy removes the strategies delegated from it.
+    execute_delegate_strategy(
+        deps.as_mut(),
+        env.clone(),
+        message_info(&Addr::unchecked("addr2"), &[]),
+        Addr::unchecked("addr3"),
+        vec![cron_permission(false, true)],
+        None,
+    )
+    .unwrap();
+    assert_eq!(query_strategies(deps.as_ref()).unwrap().len(), 5);
+    execute_remove_strategy(deps.as_mut(), info, Addr::unchecked("addr1")).unwrap();
+    let strategies = query_strategies(deps.as_ref()).unwrap();
+    assert_eq!(strategies.len(), 2);
+    assert!(strategies.iter().all(|s| s.parent.is_none()));
+}
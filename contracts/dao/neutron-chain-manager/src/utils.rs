@@ -1,5 +1,6 @@
-use serde::de::{self, SeqAccess, Visitor};
-use serde::{Deserialize, Deserializer};
+use serde::de::{self, MapAccess, SeqAccess, Visitor};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::collections::BTreeMap;
 use std::fmt;
 
 /// Unfortunately, stargate returns a string instead of a number for
@@ -78,3 +79,121 @@ enum U64OrString {
     U64(u64),
     String(String),
 }
+
+/// The parameters of a module, either from a `MsgUpdateParams` message or from
+/// a stargate params query, without knowing their type.
+#[derive(Deserialize)]
+pub struct ParamsJSON {
+    #[serde(default)]
+    pub params: BTreeMap<String, ParamValue>,
+}
+
+/// A JSON value of a module parameter. Integers are kept as strings, because
+/// stargate queries return 64-bit integers as strings while proposals might
+/// contain them as numbers.
+#[derive(Debug, PartialEq)]
+pub enum ParamValue {
+    Null,
+    Bool(bool),
+    String(String),
+    Array(Vec<ParamValue>),
+    Object(BTreeMap<String, ParamValue>),
+}
+
+impl<'de> Deserialize<'de> for ParamValue {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct ParamValueVisitor;
+
+        impl<'de> Visitor<'de> for ParamValueVisitor {
+            type Value = ParamValue;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("a JSON value without floats")
+            }
+
+            fn visit_unit<E>(self) -> Result<Self::Value, E>
+            where
+                E: de::Error,
+            {
+                Ok(ParamValue::Null)
+            }
+
+            fn visit_none<E>(self) -> Result<Self::Value, E>
+            where
+                E: de::Error,
+            {
+                Ok(ParamValue::Null)
+            }
+
+            fn visit_bool<E>(self, value: bool) -> Result<Self::Value, E>
+            where
+                E: de::Error,
+            {
+                Ok(ParamValue::Bool(value))
+            }
+
+            fn visit_u64<E>(self, value: u64) -> Result<Self::Value, E>
+            where
+                E: de::Error,
+            {
+                Ok(ParamValue::String(value.to_string()))
+            }
+
+            fn visit_i64<E>(self, value: i64) -> Result<Self::Value, E>
+            where
+                E: de::Error,
+            {
+                Ok(ParamValue::String(value.to_string()))
+            }
+
+            fn visit_str<E>(self, value: &str) -> Result<Self::Value, E>
+            where
+                E: de::Error,
+            {
+                Ok(ParamValue::String(value.to_string()))
+            }
+
+            fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+            where
+                A: SeqAccess<'de>,
+            {
+                let mut values = Vec::new();
+                while let Some(value) = seq.next_element()? {
+                    values.push(value);
+                }
+                Ok(ParamValue::Array(values))
+            }
+
+            fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+            where
+                A: MapAccess<'de>,
+            {
+                let mut values = BTreeMap::new();
+                while let Some((key, value)) = map.next_entry()? {
+                    values.insert(key, value);
+                }
+                Ok(ParamValue::Object(values))
+            }
+        }
+
+        deserializer.deserialize_any(ParamValueVisitor)
+    }
+}
+
+impl Serialize for ParamValue {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match self {
+            ParamValue::Null => serializer.serialize_unit(),
+            ParamValue::Bool(value) => serializer.serialize_bool(*value),
+            ParamValue::String(value) => serializer.serialize_str(value),
+            ParamValue::Array(values) => values.serialize(serializer),
+            ParamValue::Object(values) => values.serialize(serializer),
+        }
+    }
+}
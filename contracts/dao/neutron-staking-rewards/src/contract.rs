@@ -1,11 +1,21 @@
 use cosmwasm_std::{
-    coin, entry_point, to_json_binary, Addr, BankMsg, Coin, Decimal, Deps, DepsMut, Env,
-    MessageInfo, Response, StdError, StdResult,
+    coin, entry_point, from_json, to_json_binary, Addr, BankMsg, Coin, Coins, Decimal, Deps,
+    DepsMut, Env, MessageInfo, Order, Reply, Response, StdError, StdResult, Storage, SubMsg,
+    SubMsgResult, Uint128, WasmMsg,
 };
 use cw2::set_contract_version;
+use cw_storage_plus::Bound;
+use std::collections::BTreeSet;
 
 use crate::state::{
-    assert_pause, is_allowed_to_pause, is_allowed_to_unpause, CONFIG, PAUSED, STATE, USERS,
+    assert_pause, is_allowed_to_pause, is_allowed_to_unpause, last_slashing_event_height,
+    load_config_at_height, load_slashing_events, load_state_at_height, load_user_at_height,
+    save_config, save_state, save_user, CampaignSettlement, TotalsBackfill, AUTO_COMPOUND,
+    CAMPAIGNS, CAMPAIGN_END_HEIGHTS, CAMPAIGN_NEXT_ID, CAMPAIGN_RESERVES, CAMPAIGN_SETTLEMENTS,
+    CLAIM_SETTINGS, CONFIG, DEFAULT_LIMIT, LEGACY_STATE, MAX_ACTIVE_CAMPAIGNS, MAX_LIMIT,
+    MAX_SETTLED_CAMPAIGNS, MAX_VESTING_ENTRIES, PAUSED, SLASHING_EVENTS, STATE, TOTALS_BACKFILL,
+    TOTAL_REWARD_WEIGHT, TOTAL_STAKE, USERS, USER_CAMPAIGN_NEXT_IDS, USER_CAMPAIGN_REWARDS,
+    VALIDATOR_MULTIPLIERS, VESTING_ENTRIES,
 };
 use neutron_staking_info_proxy_common::msg::QueryMsg as InfoProxyQueryMsg;
 use neutron_staking_rewards_common::error::ContractError;
@@ -13,14 +23,27 @@ use neutron_staking_rewards_common::error::ContractError::{
     ContractPaused, DaoStakeChangeNotTracked, InvalidStakeDenom, Unauthorized,
 };
 use neutron_staking_rewards_common::msg::{
-    ConfigResponse, InstantiateMsg, MigrateMsg, QueryMsg, RewardsResponse, StateResponse,
+    CampaignsResponse, ConfigResponse, GlobalIndexResponse, InstantiateMsg, MigrateMsg, QueryMsg,
+    RewardRateResponse, RewardsAccruedResponse, RewardsResponse, SolvencyResponse, StateResponse,
+    ValidatorMultipliersResponse, VestedRewardsResponse, VestingEntriesResponse,
 };
 use neutron_staking_rewards_common::msg::{ExecuteMsg, SlashingEventsResponse};
-use neutron_staking_rewards_common::types::{Config, State, UserInfo};
+use neutron_staking_rewards_common::types::{
+    AutoCompoundSettings, ClaimSettings, CompoundResult, Config, RateSegment, RewardCampaign,
+    State, UserCampaignRewards, UserInfo, ValidatorMultiplier, VestingEntry,
+    MAX_VALIDATOR_MULTIPLIER,
+};
+use neutron_staking_tracker_common::msg::QueryMsg as TrackerQueryMsg;
+use neutron_staking_tracker_common::types::{Delegation, Validator};
+use neutron_std::types::cosmos::authz::v1beta1::MsgExec;
+use neutron_std::types::cosmos::base::v1beta1::Coin as ProtoCoin;
+use neutron_std::types::cosmos::staking::v1beta1::{MsgDelegate, StakingQuerier};
 
 const CONTRACT_NAME: &str = "crates.io:neutron-staking-rewards";
 const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
 
+pub const COMPOUND_REPLY_ID: u64 = 1;
+
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn instantiate(
     deps: DepsMut,
@@ -35,6 +58,10 @@ pub fn instantiate(
     let dao_address = deps.api.addr_validate(&msg.dao_address)?;
     let staking_info_proxy = deps.api.addr_validate(&msg.staking_info_proxy)?;
     let security_address = deps.api.addr_validate(&msg.security_address)?;
+    let staking_tracker = msg
+        .staking_tracker
+        .map(|staking_tracker| deps.api.addr_validate(&staking_tracker))
+        .transpose()?;
 
     // Create and validate the contract configuration.
     let config = Config {
@@ -45,9 +72,15 @@ pub fn instantiate(
         blocks_per_year: msg.blocks_per_year,
         staking_denom: msg.staking_denom,
         security_address,
+        reward_rate_schedule: msg.reward_rate_schedule.unwrap_or_default(),
+        pause_accrual_on_insolvency: msg.pause_accrual_on_insolvency.unwrap_or_default(),
+        staking_tracker,
+        claim_vesting_blocks: msg.claim_vesting_blocks.unwrap_or_default(),
+        forfeit_unvested_on_unstake: msg.forfeit_unvested_on_unstake.unwrap_or_default(),
+        campaign_min_funds: msg.campaign_min_funds.unwrap_or_default(),
     };
     config.validate()?;
-    CONFIG.save(deps.storage, &config)?;
+    save_config(deps.storage, &config, env.block.height)?;
 
     // Initialize the reward distribution state.
     // The global_reward_index tracks the global index for reward distribution
@@ -55,11 +88,16 @@ pub fn instantiate(
     let state = State {
         global_reward_index: Decimal::zero(),
         global_update_height: env.block.height,
-        slashing_events: vec![],
+        total_accrued_rewards: Decimal::zero(),
+        accrual_paused: false,
     };
-    STATE.save(deps.storage, &state)?;
+    save_state(deps.storage, &state, env.block.height)?;
 
     PAUSED.save(deps.storage, &false)?;
+    TOTAL_STAKE.save(deps.storage, &Uint128::zero())?;
+    TOTAL_REWARD_WEIGHT.save(deps.storage, &Uint128::zero())?;
+    CAMPAIGN_RESERVES.save(deps.storage, &Uint128::zero())?;
+    CAMPAIGN_NEXT_ID.save(deps.storage, &0)?;
 
     Ok(Response::new()
         .add_attribute("action", "instantiate")
@@ -88,6 +126,12 @@ pub fn execute(
             staking_info_proxy,
             staking_denom,
             security_address,
+            reward_rate_schedule,
+            pause_accrual_on_insolvency,
+            staking_tracker,
+            claim_vesting_blocks,
+            forfeit_unvested_on_unstake,
+            campaign_min_funds,
         } => update_config(
             deps,
             env,
@@ -98,6 +142,12 @@ pub fn execute(
             staking_info_proxy,
             staking_denom,
             security_address,
+            reward_rate_schedule,
+            pause_accrual_on_insolvency,
+            staking_tracker,
+            claim_vesting_blocks,
+            forfeit_unvested_on_unstake,
+            campaign_min_funds,
         ),
         // Updates the stake information for a particular user
         ExecuteMsg::UpdateStake { user } => {
@@ -136,11 +186,50 @@ pub fn execute(
             }
         }),
         // Claims any accrued rewards for the caller
-        ExecuteMsg::ClaimRewards { to_address } => claim_rewards(deps, env, info, to_address),
+        ExecuteMsg::ClaimRewards { to_address } => {
+            claim_rewards(deps, env, info.sender, to_address)
+        }
+        // Withdraws the unlocked vesting rewards of the caller
+        ExecuteMsg::WithdrawVested { to_address } => {
+            withdraw_vested(deps, env, info.sender, to_address)
+        }
+        // Claims accrued rewards on behalf of a user
+        ExecuteMsg::ClaimRewardsFor { user } => claim_rewards_for(deps, env, info, user),
+        // Sets the default destination of the caller's rewards
+        ExecuteMsg::SetRewardRecipient { recipient } => set_reward_recipient(deps, info, recipient),
+        // Sets the address allowed to claim on behalf of the caller
+        ExecuteMsg::SetAuthorizedClaimer { claimer } => set_authorized_claimer(deps, info, claimer),
+        // Opts the caller in or out of auto-compounding
+        ExecuteMsg::SetAutoCompound { enabled, validator } => {
+            set_auto_compound(deps, info, enabled, validator)
+        }
+        // Delegates the staking rewards of opted-in users on their behalf
+        ExecuteMsg::Compound { users } => compound(deps, env, users),
+        // Sends the compounded rewards to a user and delegates them on the user's behalf
+        ExecuteMsg::DelegateCompounded { user, delegations } => {
+            delegate_compounded(deps, env, info, user, delegations)
+        }
         // Pauses the contract
         ExecuteMsg::Pause {} => pause(deps, info),
         // Unpauses the contract
         ExecuteMsg::Unpause {} => unpause(deps, info),
+        // Creates a reward campaign funded with the sent coin
+        ExecuteMsg::CreateCampaign {
+            start_height,
+            end_height,
+        } => create_campaign(deps, env, info, start_height, end_height),
+        // Sends the campaign rewards of the blocks without any stake back to the funder
+        ExecuteMsg::ReclaimCampaignFunds { id } => reclaim_campaign_funds(deps, env, info, id),
+        // Sets the reward multipliers of validators
+        ExecuteMsg::SetValidatorMultipliers { multipliers } => {
+            set_validator_multipliers(deps, info, multipliers)
+        }
+        // Recomputes the reward multipliers of users after validator multiplier changes
+        ExecuteMsg::RefreshRewardMultipliers { users } => {
+            refresh_reward_multipliers(deps, env, users)
+        }
+        // Computes the totals from the user infos after the migration that introduced them
+        ExecuteMsg::BackfillTotals { limit } => backfill_totals(deps, env, limit),
     }
 }
 
@@ -150,6 +239,10 @@ fn unpause(deps: DepsMut, info: MessageInfo) -> Result<Response, ContractError>
         return Err(ContractError::Unauthorized {});
     }
 
+    if TOTALS_BACKFILL.exists(deps.storage) {
+        return Err(ContractError::TotalsBackfillInProgress {});
+    }
+
     PAUSED.save(deps.storage, &false)?;
     Ok(Response::new())
 }
@@ -177,6 +270,12 @@ fn update_config(
     staking_info_proxy: Option<String>,
     staking_denom: Option<String>,
     security_address: Option<String>,
+    reward_rate_schedule: Option<Vec<RateSegment>>,
+    pause_accrual_on_insolvency: Option<bool>,
+    staking_tracker: Option<String>,
+    claim_vesting_blocks: Option<u64>,
+    forfeit_unvested_on_unstake: Option<bool>,
+    campaign_min_funds: Option<Vec<Coin>>,
 ) -> Result<Response, ContractError> {
     // Load the existing configuration
     let mut config = CONFIG.load(deps.storage)?;
@@ -189,6 +288,9 @@ fn update_config(
     // First, update the global index before changing any reward-related parameters
     // to ensure consistent reward distribution.
     update_global_index(deps.branch(), &env, config.clone())?;
+    // The rate of the segments that have already started becomes the base rate,
+    // so that an `annual_reward_rate_bps` update takes effect immediately.
+    config.apply_started_rate_segments(env.block.height);
 
     // Update fields
     if let Some(new_owner) = owner {
@@ -209,10 +311,43 @@ fn update_config(
     if let Some(security_address) = security_address {
         config.security_address = deps.api.addr_validate(&security_address)?;
     }
+    if let Some(schedule) = reward_rate_schedule {
+        // Rewards up to the current block are already accounted for, so the
+        // schedule can't be changed retroactively.
+        if let Some(segment) = schedule
+            .iter()
+            .find(|segment| segment.start_height <= env.block.height)
+        {
+            return Err(ContractError::RateSegmentInThePast {
+                start_height: segment.start_height,
+            });
+        }
+        config.reward_rate_schedule = schedule;
+    }
+    if let Some(pause_accrual_on_insolvency) = pause_accrual_on_insolvency {
+        config.pause_accrual_on_insolvency = pause_accrual_on_insolvency;
+    }
+    if let Some(staking_tracker) = staking_tracker {
+        config.staking_tracker = Some(deps.api.addr_validate(&staking_tracker)?);
+    }
+    if let Some(claim_vesting_blocks) = claim_vesting_blocks {
+        config.claim_vesting_blocks = claim_vesting_blocks;
+    }
+    if let Some(forfeit_unvested_on_unstake) = forfeit_unvested_on_unstake {
+        config.forfeit_unvested_on_unstake = forfeit_unvested_on_unstake;
+    }
+    if let Some(campaign_min_funds) = campaign_min_funds {
+        config.campaign_min_funds = campaign_min_funds;
+    }
 
     // Validate updated config and save
     config.validate()?;
-    CONFIG.save(deps.storage, &config)?;
+    save_config(deps.storage, &config, env.block.height)?;
+
+    // The accrual pause depends on the config, e.g. on the staking denom
+    let mut state = STATE.load(deps.storage)?;
+    update_accrual_pause(deps.as_ref(), &env, &config, &mut state)?;
+    save_state(deps.storage, &state, env.block.height)?;
 
     Ok(Response::new()
         .add_attribute("action", "update_config")
@@ -250,16 +385,26 @@ fn update_stake(
         return Err(DaoStakeChangeNotTracked {});
     }
 
+    // Campaign rewards are accrued on the stake known to the contract before it changes
+    let old_user_info = USERS.may_load(deps.storage, &user_addr)?;
+    let user_campaigns = get_updated_user_campaigns(deps.as_ref(), &user_addr, env.block.height)?;
+
     let (user_info, state) =
         process_slashing_events(deps.as_ref(), config.clone(), user_addr.clone())?;
 
-    let updated_state = get_updated_state(&config, &state, env.block.height)?;
+    let total_reward_weight = TOTAL_REWARD_WEIGHT
+        .may_load(deps.storage)?
+        .unwrap_or_default();
+    let mut updated_state =
+        get_updated_state(&config, &state, total_reward_weight, env.block.height)?;
+    update_accrual_pause(deps.as_ref(), &env, &config, &mut updated_state)?;
     let mut updated_user_info = get_updated_user_info(
         user_info,
         updated_state.global_reward_index,
         env.block.height,
         config.staking_denom.clone(),
     )?;
+    let old_stake = updated_user_info.stake.amount;
     // Set the user stake to current value
     updated_user_info.stake = safe_query_user_stake(
         &deps.as_ref(),
@@ -268,10 +413,43 @@ fn update_stake(
         config.staking_denom.clone(),
         env.block.height,
     )?;
-    STATE.save(deps.storage, &updated_state)?;
-    USERS.save(deps.storage, &user_addr.clone(), &updated_user_info)?;
+    updated_user_info.reward_multiplier =
+        query_reward_multiplier(deps.as_ref(), &env, &config, &user_addr)?;
+
+    let mut resp = Response::new();
+    if config.forfeit_unvested_on_unstake && updated_user_info.stake.amount < old_stake {
+        let forfeited = forfeit_unvested_rewards(
+            deps.storage,
+            &user_addr,
+            env.block.height,
+            old_stake,
+            updated_user_info.stake.amount,
+        )?;
+        updated_state.total_accrued_rewards = updated_state
+            .total_accrued_rewards
+            .saturating_sub(Decimal::checked_from_ratio(forfeited, 1u128)?);
+        // An insolvent contract can't fail the stake update
+        let amount = forfeited.min(query_available_balance(deps.as_ref(), &env, &config)?);
+        if !amount.is_zero() {
+            resp = resp.add_message(BankMsg::Send {
+                to_address: config.dao_address.to_string(),
+                amount: vec![coin(amount.u128(), config.staking_denom.clone())],
+            });
+        }
+        resp = resp.add_attribute("forfeited_amount", forfeited);
+    }
 
-    Ok(Response::new()
+    save_state(deps.storage, &updated_state, env.block.height)?;
+    save_user(
+        deps.storage,
+        &user_addr,
+        &updated_user_info,
+        env.block.height,
+    )?;
+    save_user_campaigns(deps.storage, &user_addr, user_campaigns, env.block.height)?;
+    update_totals(deps.storage, old_user_info.as_ref(), &updated_user_info)?;
+
+    Ok(resp
         .add_attribute("action", "update_stake")
         .add_attribute("user", user_addr.clone()))
 }
@@ -289,20 +467,26 @@ fn slashing(deps: DepsMut, env: Env, info: MessageInfo) -> Result<Response, Cont
     }
 
     let state = STATE.load(deps.storage)?;
-    if let Some((_, last_event_height)) = state.slashing_events.last() {
-        if *last_event_height >= env.block.height {
+    if let Some(last_event_height) = last_slashing_event_height(deps.storage)? {
+        if last_event_height >= env.block.height {
             return Ok(Response::new()
                 .add_attribute("action", "slashing")
                 .add_attribute("result", "ignored"));
         }
     }
 
-    let mut updated_state = get_updated_state(&config, &state, env.block.height)?;
-    updated_state.slashing_events.push((
-        updated_state.global_reward_index,
+    let total_reward_weight = TOTAL_REWARD_WEIGHT
+        .may_load(deps.storage)?
+        .unwrap_or_default();
+    let mut updated_state =
+        get_updated_state(&config, &state, total_reward_weight, env.block.height)?;
+    update_accrual_pause(deps.as_ref(), &env, &config, &mut updated_state)?;
+    SLASHING_EVENTS.save(
+        deps.storage,
         updated_state.global_update_height,
-    ));
-    STATE.save(deps.storage, &updated_state)?;
+        &updated_state.global_reward_index,
+    )?;
+    save_state(deps.storage, &updated_state, env.block.height)?;
 
     Ok(Response::new()
         .add_attribute("action", "slashing")
@@ -310,11 +494,12 @@ fn slashing(deps: DepsMut, env: Env, info: MessageInfo) -> Result<Response, Cont
         .add_attribute("block_height", format!("{}", env.block.height)))
 }
 
-/// Allows a user to claim any pending rewards accrued for their stake.
+/// Claims any pending rewards accrued for the user's stake. The rewards are sent to `to_address`
+/// if specified, to the user's reward recipient otherwise.
 fn claim_rewards(
     deps: DepsMut,
     env: Env,
-    info: MessageInfo,
+    user: Addr,
     to_address: Option<String>,
 ) -> Result<Response, ContractError> {
     // users can't claim rewards while the contract is on pause
@@ -322,13 +507,24 @@ fn claim_rewards(
 
     let config = CONFIG.load(deps.storage)?;
 
-    if info.sender == config.dao_address {
+    if user == config.dao_address {
         return Err(Unauthorized {});
     }
 
-    let (user_info, state) =
-        process_slashing_events(deps.as_ref(), config.clone(), info.sender.clone())?;
-    let updated_state = get_updated_state(&config, &state, env.block.height)?;
+    // Campaign rewards are accrued on the stake known to the contract before slashing
+    // events are processed
+    let old_user_info = USERS.may_load(deps.storage, &user)?;
+    let mut user_campaigns = get_updated_user_campaigns(deps.as_ref(), &user, env.block.height)?;
+
+    let (user_info, state) = process_slashing_events(deps.as_ref(), config.clone(), user.clone())?;
+    let total_reward_weight = TOTAL_REWARD_WEIGHT
+        .may_load(deps.storage)?
+        .unwrap_or_default();
+    let mut updated_state =
+        get_updated_state(&config, &state, total_reward_weight, env.block.height)?;
+    // The claim reduces the balance and the liabilities by the same amount, so the solvency
+    // can be checked before it
+    update_accrual_pause(deps.as_ref(), &env, &config, &mut updated_state)?;
     let mut updated_user_info = get_updated_user_info(
         user_info,
         updated_state.global_reward_index,
@@ -336,16 +532,61 @@ fn claim_rewards(
         config.staking_denom.clone(),
     )?;
     let pending_rewards = updated_user_info.pending_rewards;
-    updated_user_info.pending_rewards = coin(0u128, config.staking_denom);
-    STATE.save(deps.storage, &updated_state)?;
-    USERS.save(deps.storage, &info.sender, &updated_user_info)?;
+    updated_user_info.pending_rewards = coin(0u128, config.staking_denom.clone());
+    // Vesting rewards stay in the liabilities until they are withdrawn
+    let vesting_end_height = if config.claim_vesting_blocks > 0 && !pending_rewards.amount.is_zero()
+    {
+        Some(add_vesting_entry(
+            deps.storage,
+            &user,
+            pending_rewards.amount,
+            env.block.height,
+            config.claim_vesting_blocks,
+        )?)
+    } else {
+        updated_state.total_accrued_rewards = updated_state
+            .total_accrued_rewards
+            .saturating_sub(Decimal::checked_from_ratio(pending_rewards.amount, 1u128)?);
+        None
+    };
+    save_state(deps.storage, &updated_state, env.block.height)?;
+    save_user(deps.storage, &user, &updated_user_info, env.block.height)?;
+    update_totals(deps.storage, old_user_info.as_ref(), &updated_user_info)?;
+
+    let mut campaign_rewards = Coins::default();
+    for (campaign, user_rewards) in user_campaigns.campaigns.iter_mut() {
+        campaign_rewards.add(coin(
+            user_rewards.pending_rewards.u128(),
+            campaign.reward.denom.clone(),
+        ))?;
+        user_rewards.pending_rewards = Uint128::zero();
+    }
+    save_user_campaigns(deps.storage, &user, user_campaigns, env.block.height)?;
+    let campaign_reserves = CAMPAIGN_RESERVES
+        .may_load(deps.storage)?
+        .unwrap_or_default();
+    CAMPAIGN_RESERVES.save(
+        deps.storage,
+        &campaign_reserves.saturating_sub(campaign_rewards.amount_of(&config.staking_denom)),
+    )?;
 
-    let recipient = to_address.unwrap_or(info.sender.to_string());
-    let resp = Response::new();
-    let resp = if !pending_rewards.amount.is_zero() {
+    // The sent coins must be sorted by denom and unique, so everything is added to `Coins`
+    let mut rewards = campaign_rewards.clone();
+    if vesting_end_height.is_none() {
+        rewards.add(pending_rewards.clone())?;
+    }
+
+    let recipient = load_recipient(deps.as_ref(), &user, to_address)?;
+    let resp = match vesting_end_height {
+        Some(end_height) => {
+            Response::new().add_attribute("vesting_end_height", end_height.to_string())
+        }
+        None => Response::new(),
+    };
+    let resp = if !rewards.is_empty() {
         resp.add_message(BankMsg::Send {
             to_address: recipient.clone(),
-            amount: vec![pending_rewards.clone()],
+            amount: rewards.into_vec(),
         })
     } else {
         resp
@@ -354,7 +595,653 @@ fn claim_rewards(
     Ok(resp
         .add_attribute("action", "claim_rewards")
         .add_attribute("recipient", recipient)
-        .add_attribute("amount", pending_rewards.to_string()))
+        .add_attribute("amount", pending_rewards.to_string())
+        .add_attribute("campaign_amount", campaign_rewards.to_string()))
+}
+
+/// Returns the address the user's rewards are sent to: `to_address` if specified, the user's
+/// reward recipient otherwise.
+fn load_recipient(deps: Deps, user: &Addr, to_address: Option<String>) -> StdResult<String> {
+    Ok(match to_address {
+        Some(to_address) => to_address,
+        None => CLAIM_SETTINGS
+            .may_load(deps.storage, user)?
+            .and_then(|settings| settings.recipient)
+            .unwrap_or_else(|| user.clone())
+            .to_string(),
+    })
+}
+
+/// Withdraws the unlocked rewards of the user's vesting entries. The entries that are fully
+/// withdrawn are removed.
+fn withdraw_vested(
+    deps: DepsMut,
+    env: Env,
+    user: Addr,
+    to_address: Option<String>,
+) -> Result<Response, ContractError> {
+    assert_pause(deps.storage)?;
+
+    let config = CONFIG.load(deps.storage)?;
+
+    let mut amount = Uint128::zero();
+    for mut entry in load_vesting_entries(deps.storage, &user, env.block.height)? {
+        amount = amount.checked_add(entry.vested)?;
+        entry.vested = Uint128::zero();
+        if entry.locked.is_zero() {
+            VESTING_ENTRIES.remove(deps.storage, (&user, entry.start_height));
+        } else {
+            VESTING_ENTRIES.save(deps.storage, (&user, entry.start_height), &entry)?;
+        }
+    }
+
+    let mut state = STATE.load(deps.storage)?;
+    state.total_accrued_rewards = state
+        .total_accrued_rewards
+        .saturating_sub(Decimal::checked_from_ratio(amount, 1u128)?);
+    save_state(deps.storage, &state, env.block.height)?;
+
+    let recipient = load_recipient(deps.as_ref(), &user, to_address)?;
+    let resp = Response::new();
+    let resp = if !amount.is_zero() {
+        resp.add_message(BankMsg::Send {
+            to_address: recipient.clone(),
+            amount: vec![coin(amount.u128(), config.staking_denom)],
+        })
+    } else {
+        resp
+    };
+
+    Ok(resp
+        .add_attribute("action", "withdraw_vested")
+        .add_attribute("recipient", recipient)
+        .add_attribute("amount", amount))
+}
+
+/// Claims the user's rewards on their behalf. Must be called by the user's authorized claimer.
+fn claim_rewards_for(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    user: String,
+) -> Result<Response, ContractError> {
+    let user_addr = deps.api.addr_validate(&user)?;
+    let claimer = CLAIM_SETTINGS
+        .may_load(deps.storage, &user_addr)?
+        .and_then(|settings| settings.claimer);
+    if claimer != Some(info.sender) {
+        return Err(Unauthorized {});
+    }
+
+    claim_rewards(deps, env, user_addr, None)
+}
+
+/// Sets the address the caller's rewards are sent to by default.
+fn set_reward_recipient(
+    deps: DepsMut,
+    info: MessageInfo,
+    recipient: Option<String>,
+) -> Result<Response, ContractError> {
+    let recipient = recipient
+        .map(|recipient| deps.api.addr_validate(&recipient))
+        .transpose()?;
+
+    let mut settings = load_claim_settings(deps.as_ref(), &info.sender)?;
+    settings.recipient = recipient.clone();
+    save_claim_settings(deps, &info.sender, settings)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "set_reward_recipient")
+        .add_attribute("user", info.sender)
+        .add_attribute(
+            "recipient",
+            recipient.map(|r| r.to_string()).unwrap_or_default(),
+        ))
+}
+
+/// Sets the address allowed to claim the caller's rewards on their behalf.
+fn set_authorized_claimer(
+    deps: DepsMut,
+    info: MessageInfo,
+    claimer: Option<String>,
+) -> Result<Response, ContractError> {
+    let claimer = claimer
+        .map(|claimer| deps.api.addr_validate(&claimer))
+        .transpose()?;
+
+    let mut settings = load_claim_settings(deps.as_ref(), &info.sender)?;
+    settings.claimer = claimer.clone();
+    save_claim_settings(deps, &info.sender, settings)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "set_authorized_claimer")
+        .add_attribute("user", info.sender)
+        .add_attribute(
+            "claimer",
+            claimer.map(|c| c.to_string()).unwrap_or_default(),
+        ))
+}
+
+/// Opts the caller in or out of auto-compounding. Opting out removes the last compound result,
+/// and opting in is rejected while claimed rewards vest.
+fn set_auto_compound(
+    deps: DepsMut,
+    info: MessageInfo,
+    enabled: bool,
+    validator: Option<String>,
+) -> Result<Response, ContractError> {
+    let resp = Response::new()
+        .add_attribute("action", "set_auto_compound")
+        .add_attribute("user", info.sender.clone())
+        .add_attribute("enabled", enabled.to_string());
+    if !enabled {
+        AUTO_COMPOUND.remove(deps.storage, &info.sender);
+        return Ok(resp);
+    }
+
+    // Compounded rewards are delegated right away, so they would bypass vesting
+    if CONFIG.load(deps.storage)?.claim_vesting_blocks > 0 {
+        return Err(ContractError::AutoCompoundWithVesting {});
+    }
+
+    // Operator addresses have their own prefix, so they can't be validated as account
+    // addresses, the validator is looked up in the staking module instead
+    let validator = validator
+        .map(|validator| {
+            StakingQuerier::new(&deps.querier)
+                .validator(validator.clone())
+                .ok()
+                .and_then(|response| response.validator)
+                .map(|_| Addr::unchecked(&validator))
+                .ok_or(ContractError::ValidatorNotFound { address: validator })
+        })
+        .transpose()?;
+    let last_result = AUTO_COMPOUND
+        .may_load(deps.storage, &info.sender)?
+        .and_then(|settings| settings.last_result);
+    AUTO_COMPOUND.save(
+        deps.storage,
+        &info.sender,
+        &AutoCompoundSettings {
+            validator: validator.clone(),
+            last_result,
+        },
+    )?;
+
+    Ok(resp.add_attribute(
+        "validator",
+        validator.map(|v| v.to_string()).unwrap_or_default(),
+    ))
+}
+
+/// Claims the staking rewards of the given users and delegates them on the users' behalf via
+/// authz. Users that aren't opted in are skipped. Campaign rewards are left pending. The rewards
+/// are delegated right away, so they would bypass vesting and can't be compounded while it's
+/// enabled.
+fn compound(deps: DepsMut, env: Env, users: Vec<String>) -> Result<Response, ContractError> {
+    assert_pause(deps.storage)?;
+
+    if users.len() > MAX_LIMIT as usize {
+        return Err(ContractError::TooManyCompoundUsers { max: MAX_LIMIT });
+    }
+
+    let config = CONFIG.load(deps.storage)?;
+    if config.claim_vesting_blocks > 0 {
+        return Err(ContractError::AutoCompoundWithVesting {});
+    }
+
+    let mut resp = Response::new().add_attribute("action", "compound");
+    for user in users {
+        let user_addr = deps.api.addr_validate(&user)?;
+        let Some(mut settings) = AUTO_COMPOUND.may_load(deps.storage, &user_addr)? else {
+            continue;
+        };
+        if user_addr == config.dao_address {
+            continue;
+        }
+
+        // Campaign rewards are accrued on the stake known to the contract before slashing
+        // events are processed
+        let old_user_info = USERS.may_load(deps.storage, &user_addr)?;
+        let user_campaigns =
+            get_updated_user_campaigns(deps.as_ref(), &user_addr, env.block.height)?;
+
+        let (user_info, state) =
+            process_slashing_events(deps.as_ref(), config.clone(), user_addr.clone())?;
+        let total_reward_weight = TOTAL_REWARD_WEIGHT
+            .may_load(deps.storage)?
+            .unwrap_or_default();
+        let mut updated_state =
+            get_updated_state(&config, &state, total_reward_weight, env.block.height)?;
+        update_accrual_pause(deps.as_ref(), &env, &config, &mut updated_state)?;
+        let mut updated_user_info = get_updated_user_info(
+            user_info,
+            updated_state.global_reward_index,
+            env.block.height,
+            config.staking_denom.clone(),
+        )?;
+        let amount = updated_user_info.pending_rewards.amount;
+        if amount.is_zero() {
+            continue;
+        }
+
+        let delegations = match settings.validator.clone() {
+            Some(validator) => vec![(validator, amount)],
+            None => split_by_bonded_delegations(deps.as_ref(), &env, &config, &user_addr, amount)?,
+        };
+        if delegations.is_empty() {
+            // The rewards stay pending until the user delegates or chooses a validator
+            settings.last_result = Some(CompoundResult {
+                height: env.block.height,
+                amount: Uint128::zero(),
+                error: Some("no bonded validator to delegate to".to_string()),
+            });
+            AUTO_COMPOUND.save(deps.storage, &user_addr, &settings)?;
+            continue;
+        }
+
+        updated_user_info.pending_rewards = coin(0u128, config.staking_denom.clone());
+        updated_state.total_accrued_rewards = updated_state
+            .total_accrued_rewards
+            .saturating_sub(Decimal::checked_from_ratio(amount, 1u128)?);
+        save_state(deps.storage, &updated_state, env.block.height)?;
+        save_user(
+            deps.storage,
+            &user_addr,
+            &updated_user_info,
+            env.block.height,
+        )?;
+        save_user_campaigns(deps.storage, &user_addr, user_campaigns, env.block.height)?;
+        update_totals(deps.storage, old_user_info.as_ref(), &updated_user_info)?;
+
+        // The result is overwritten in the reply if the delegation fails
+        settings.last_result = Some(CompoundResult {
+            height: env.block.height,
+            amount,
+            error: None,
+        });
+        AUTO_COMPOUND.save(deps.storage, &user_addr, &settings)?;
+
+        // The rewards are sent and delegated in a call to the contract itself, so a failed
+        // delegation reverts the transfer and the reply makes the rewards pending again
+        let delegate_msg = WasmMsg::Execute {
+            contract_addr: env.contract.address.to_string(),
+            msg: to_json_binary(&ExecuteMsg::DelegateCompounded {
+                user: user_addr.to_string(),
+                delegations: delegations
+                    .into_iter()
+                    .map(|(validator, amount)| (validator.to_string(), amount))
+                    .collect(),
+            })?,
+            funds: vec![],
+        };
+        resp = resp
+            .add_submessage(
+                SubMsg::reply_on_error(delegate_msg, COMPOUND_REPLY_ID)
+                    .with_payload(to_json_binary(&(user_addr.clone(), amount))?),
+            )
+            .add_attribute("compounded", format!("{user_addr}:{amount}"));
+    }
+
+    Ok(resp)
+}
+
+/// Sends the compounded rewards to the user and delegates them on the user's behalf via authz.
+/// Only the contract itself can call this method.
+fn delegate_compounded(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    user: String,
+    delegations: Vec<(String, Uint128)>,
+) -> Result<Response, ContractError> {
+    if info.sender != env.contract.address {
+        return Err(Unauthorized {});
+    }
+
+    let config = CONFIG.load(deps.storage)?;
+    let user_addr = deps.api.addr_validate(&user)?;
+    let amount = delegations
+        .iter()
+        .try_fold(Uint128::zero(), |acc, (_, amount)| acc.checked_add(*amount))?;
+    let msg_exec = MsgExec {
+        grantee: env.contract.address.to_string(),
+        msgs: delegations
+            .into_iter()
+            .map(|(validator, amount)| {
+                MsgDelegate {
+                    delegator_address: user_addr.to_string(),
+                    validator_address: validator,
+                    amount: Some(ProtoCoin {
+                        denom: config.staking_denom.clone(),
+                        amount: amount.to_string(),
+                    }),
+                }
+                .to_any()
+            })
+            .collect(),
+    };
+
+    Ok(Response::new()
+        .add_message(BankMsg::Send {
+            to_address: user_addr.to_string(),
+            amount: vec![coin(amount.u128(), config.staking_denom)],
+        })
+        .add_message(msg_exec)
+        .add_attribute("action", "delegate_compounded")
+        .add_attribute("user", user_addr)
+        .add_attribute("amount", amount))
+}
+
+fn load_claim_settings(deps: Deps, user: &Addr) -> StdResult<ClaimSettings> {
+    Ok(CLAIM_SETTINGS
+        .may_load(deps.storage, user)?
+        .unwrap_or(ClaimSettings {
+            recipient: None,
+            claimer: None,
+        }))
+}
+
+/// Saves the claim settings, or removes them if there is nothing to store.
+fn save_claim_settings(deps: DepsMut, user: &Addr, settings: ClaimSettings) -> StdResult<()> {
+    if settings.recipient.is_none() && settings.claimer.is_none() {
+        CLAIM_SETTINGS.remove(deps.storage, user);
+        return Ok(());
+    }
+
+    CLAIM_SETTINGS.save(deps.storage, user, &settings)
+}
+
+/// Creates a reward campaign funded with the coin sent along with the message.
+fn create_campaign(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    start_height: u64,
+    end_height: u64,
+) -> Result<Response, ContractError> {
+    assert_pause(deps.storage)?;
+
+    let reward = match info.funds.as_slice() {
+        [reward] if !reward.amount.is_zero() => reward.clone(),
+        _ => return Err(ContractError::InvalidCampaignFunds {}),
+    };
+    if start_height < env.block.height || end_height <= start_height {
+        return Err(ContractError::InvalidCampaignHeights {
+            current_height: env.block.height,
+        });
+    }
+
+    // Campaigns can only be funded with the allowed denoms, with at least their minimum amounts
+    let config = CONFIG.load(deps.storage)?;
+    if !config
+        .campaign_min_funds
+        .iter()
+        .any(|min_funds| min_funds.denom == reward.denom && min_funds.amount <= reward.amount)
+    {
+        return Err(ContractError::InsufficientCampaignFunds {
+            min_funds: config
+                .campaign_min_funds
+                .iter()
+                .map(|min_funds| min_funds.to_string())
+                .collect::<Vec<_>>()
+                .join(", "),
+        });
+    }
+
+    // Every campaign that hasn't ended is loaded on stake updates
+    let active_campaigns = CAMPAIGN_END_HEIGHTS
+        .keys(
+            deps.storage,
+            Some(Bound::exclusive((env.block.height, u64::MAX))),
+            None,
+            Order::Ascending,
+        )
+        .take(MAX_ACTIVE_CAMPAIGNS as usize)
+        .count();
+    if active_campaigns >= MAX_ACTIVE_CAMPAIGNS as usize {
+        return Err(ContractError::TooManyActiveCampaigns {
+            max: MAX_ACTIVE_CAMPAIGNS,
+        });
+    }
+
+    // Campaign funds in the staking denom can't be used to pay staking rewards
+    if reward.denom == config.staking_denom {
+        let campaign_reserves = CAMPAIGN_RESERVES
+            .may_load(deps.storage)?
+            .unwrap_or_default();
+        CAMPAIGN_RESERVES.save(deps.storage, &campaign_reserves.checked_add(reward.amount)?)?;
+    }
+
+    let id = CAMPAIGN_NEXT_ID.may_load(deps.storage)?.unwrap_or_default();
+    CAMPAIGN_NEXT_ID.save(deps.storage, &(id + 1))?;
+    let campaign = RewardCampaign {
+        id,
+        funder: info.sender,
+        reward,
+        start_height,
+        end_height,
+        reward_index: Decimal::zero(),
+        last_update_height: start_height,
+        undistributed: Uint128::zero(),
+    };
+    CAMPAIGNS.save(deps.storage, id, &campaign)?;
+    CAMPAIGN_END_HEIGHTS.save(deps.storage, (end_height, id), &())?;
+
+    Ok(Response::new()
+        .add_attribute("action", "create_campaign")
+        .add_attribute("campaign_id", id.to_string())
+        .add_attribute("funder", campaign.funder)
+        .add_attribute("reward", campaign.reward.to_string())
+        .add_attribute("start_height", start_height.to_string())
+        .add_attribute("end_height", end_height.to_string()))
+}
+
+/// Sends the campaign rewards of the blocks without any stake back to the funder. Only the funder
+/// of the campaign can call this method.
+fn reclaim_campaign_funds(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    id: u64,
+) -> Result<Response, ContractError> {
+    assert_pause(deps.storage)?;
+
+    let campaign = CAMPAIGNS.load(deps.storage, id)?;
+    if info.sender != campaign.funder {
+        return Err(Unauthorized {});
+    }
+
+    let total_stake = TOTAL_STAKE.may_load(deps.storage)?.unwrap_or_default();
+    let mut campaign = get_updated_campaign(campaign, total_stake, env.block.height)?;
+    let amount = campaign.undistributed;
+    campaign.undistributed = Uint128::zero();
+    CAMPAIGNS.save(deps.storage, id, &campaign)?;
+
+    let config = CONFIG.load(deps.storage)?;
+    if campaign.reward.denom == config.staking_denom {
+        let campaign_reserves = CAMPAIGN_RESERVES
+            .may_load(deps.storage)?
+            .unwrap_or_default();
+        CAMPAIGN_RESERVES.save(deps.storage, &campaign_reserves.saturating_sub(amount))?;
+    }
+
+    let mut response = Response::new()
+        .add_attribute("action", "reclaim_campaign_funds")
+        .add_attribute("campaign_id", id.to_string())
+        .add_attribute("amount", amount.to_string());
+    if !amount.is_zero() {
+        response = response.add_message(BankMsg::Send {
+            to_address: campaign.funder.to_string(),
+            amount: vec![coin(amount.u128(), campaign.reward.denom)],
+        });
+    }
+
+    Ok(response)
+}
+
+/// Sets the reward multipliers of validators. Only the current owner can call this method.
+fn set_validator_multipliers(
+    deps: DepsMut,
+    info: MessageInfo,
+    multipliers: Vec<ValidatorMultiplier>,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    if info.sender != config.owner {
+        return Err(Unauthorized {});
+    }
+
+    for ValidatorMultiplier {
+        validator,
+        multiplier,
+    } in multipliers.iter()
+    {
+        if *multiplier > Decimal::from_ratio(MAX_VALIDATOR_MULTIPLIER, 1u64) {
+            return Err(ContractError::InvalidValidatorMultiplier {
+                multiplier: *multiplier,
+            });
+        }
+
+        // Operator addresses have their own prefix, so they can't be validated as account
+        // addresses. The staking tracker stores them unchecked as well.
+        let validator = Addr::unchecked(validator);
+        if *multiplier == Decimal::one() {
+            VALIDATOR_MULTIPLIERS.remove(deps.storage, &validator);
+        } else {
+            VALIDATOR_MULTIPLIERS.save(deps.storage, &validator, multiplier)?;
+        }
+    }
+
+    Ok(Response::new()
+        .add_attribute("action", "set_validator_multipliers")
+        .add_attribute("count", multipliers.len().to_string()))
+}
+
+/// Recomputes the reward multipliers of the given users, so that validator multiplier changes
+/// apply to them before their next stake update. The rewards accrued until now are settled with
+/// the previous multipliers. Can be called by anyone.
+fn refresh_reward_multipliers(
+    deps: DepsMut,
+    env: Env,
+    users: Vec<String>,
+) -> Result<Response, ContractError> {
+    assert_pause(deps.storage)?;
+
+    if users.len() > MAX_LIMIT as usize {
+        return Err(ContractError::TooManyRefreshUsers { max: MAX_LIMIT });
+    }
+
+    let config = CONFIG.load(deps.storage)?;
+
+    let mut refreshed: u32 = 0;
+    for user in users {
+        let user_addr = deps.api.addr_validate(&user)?;
+        // Only the users known to the contract have a reward weight
+        let Some(old_user_info) = USERS.may_load(deps.storage, &user_addr)? else {
+            continue;
+        };
+
+        // Campaign rewards are accrued on the stake known to the contract before slashing
+        // events are processed
+        let user_campaigns =
+            get_updated_user_campaigns(deps.as_ref(), &user_addr, env.block.height)?;
+
+        let (user_info, state) =
+            process_slashing_events(deps.as_ref(), config.clone(), user_addr.clone())?;
+        let total_reward_weight = TOTAL_REWARD_WEIGHT
+            .may_load(deps.storage)?
+            .unwrap_or_default();
+        let mut updated_state =
+            get_updated_state(&config, &state, total_reward_weight, env.block.height)?;
+        update_accrual_pause(deps.as_ref(), &env, &config, &mut updated_state)?;
+        let mut updated_user_info = get_updated_user_info(
+            user_info,
+            updated_state.global_reward_index,
+            env.block.height,
+            config.staking_denom.clone(),
+        )?;
+        updated_user_info.reward_multiplier =
+            query_reward_multiplier(deps.as_ref(), &env, &config, &user_addr)?;
+
+        save_state(deps.storage, &updated_state, env.block.height)?;
+        save_user(
+            deps.storage,
+            &user_addr,
+            &updated_user_info,
+            env.block.height,
+        )?;
+        save_user_campaigns(deps.storage, &user_addr, user_campaigns, env.block.height)?;
+        update_totals(deps.storage, Some(&old_user_info), &updated_user_info)?;
+        refreshed += 1;
+    }
+
+    Ok(Response::new()
+        .add_attribute("action", "refresh_reward_multipliers")
+        .add_attribute("count", refreshed.to_string()))
+}
+
+/// Adds the next page of users to the total stake, the total reward weight and the rewards
+/// accrued to users after the migration from the version that didn't track them. Once all the
+/// users are added, the pause state before the migration is restored. Can be called by anyone.
+fn backfill_totals(deps: DepsMut, env: Env, limit: Option<u32>) -> Result<Response, ContractError> {
+    let Some(mut backfill) = TOTALS_BACKFILL.may_load(deps.storage)? else {
+        return Err(ContractError::NoTotalsBackfill {});
+    };
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+
+    let config = CONFIG.load(deps.storage)?;
+    let mut total_stake = TOTAL_STAKE.load(deps.storage)?;
+    let mut total_reward_weight = TOTAL_REWARD_WEIGHT.load(deps.storage)?;
+    // The rewards of the users added so far accrue until now before more users are added
+    let mut state = get_updated_state(
+        &config,
+        &STATE.load(deps.storage)?,
+        total_reward_weight,
+        env.block.height,
+    )?;
+
+    let users = USERS
+        .range(
+            deps.storage,
+            backfill.last_user.as_ref().map(Bound::exclusive),
+            None,
+            Order::Ascending,
+        )
+        .take(limit)
+        .collect::<StdResult<Vec<_>>>()?;
+    // The user infos don't account for the unprocessed slashing events
+    for (_, user_info) in users.iter() {
+        total_stake = total_stake.checked_add(user_info.stake.amount)?;
+        let reward_weight = user_info.reward_weight()?;
+        total_reward_weight = total_reward_weight.checked_add(reward_weight)?;
+        let accrued = Decimal::checked_from_ratio(reward_weight, 1u128)?
+            .checked_mul(state.global_reward_index - user_info.user_reward_index)?
+            .checked_add(Decimal::checked_from_ratio(
+                user_info.pending_rewards.amount,
+                1u128,
+            )?)?;
+        state.total_accrued_rewards = state.total_accrued_rewards.checked_add(accrued)?;
+    }
+
+    TOTAL_STAKE.save(deps.storage, &total_stake)?;
+    TOTAL_REWARD_WEIGHT.save(deps.storage, &total_reward_weight)?;
+    save_state(deps.storage, &state, env.block.height)?;
+
+    let response = Response::new()
+        .add_attribute("action", "backfill_totals")
+        .add_attribute("count", users.len().to_string());
+    match users.last() {
+        Some((user, _)) if users.len() == limit => {
+            backfill.last_user = Some(user.clone());
+            TOTALS_BACKFILL.save(deps.storage, &backfill)?;
+            Ok(response.add_attribute("last_user", user.as_str()))
+        }
+        _ => {
+            TOTALS_BACKFILL.remove(deps.storage);
+            PAUSED.save(deps.storage, &backfill.was_paused)?;
+            Ok(response.add_attribute("done", "true"))
+        }
+    }
 }
 
 // ----------------------------------------
@@ -366,10 +1253,59 @@ pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> Result<cosmwasm_std::Binary
         QueryMsg::Config {} => Ok(to_json_binary(&query_config(deps)?)?),
         QueryMsg::State {} => Ok(to_json_binary(&query_state(deps)?)?),
         QueryMsg::Rewards { user } => Ok(to_json_binary(&query_rewards(deps, env, user)?)?),
-        QueryMsg::SlashingEvents { from_height } => {
-            Ok(to_json_binary(&query_slashing_events(deps, from_height)?)?)
-        }
+        QueryMsg::SlashingEvents { from_height, limit } => Ok(to_json_binary(
+            &query_slashing_events(deps, from_height, limit)?,
+        )?),
         QueryMsg::IsPaused {} => Ok(to_json_binary(&query_is_paused(deps)?)?),
+        QueryMsg::RewardRate { height } => {
+            Ok(to_json_binary(&query_reward_rate(deps, env, height)?)?)
+        }
+        QueryMsg::RewardsAccrued {
+            user,
+            from_height,
+            to_height,
+        } => Ok(to_json_binary(&query_rewards_accrued(
+            deps,
+            user,
+            from_height,
+            to_height,
+        )?)?),
+        QueryMsg::GlobalIndexAtHeight { height } => Ok(to_json_binary(&GlobalIndexResponse {
+            height,
+            global_reward_index: load_global_index_at_height(deps, height)?,
+        })?),
+        QueryMsg::VestedRewards { user } => {
+            Ok(to_json_binary(&query_vested_rewards(deps, env, user)?)?)
+        }
+        QueryMsg::VestingEntries {
+            user,
+            start_after,
+            limit,
+        } => Ok(to_json_binary(&query_vesting_entries(
+            deps,
+            env,
+            user,
+            start_after,
+            limit,
+        )?)?),
+        QueryMsg::ClaimSettings { user } => {
+            let user_addr = deps.api.addr_validate(&user)?;
+            Ok(to_json_binary(&load_claim_settings(deps, &user_addr)?)?)
+        }
+        QueryMsg::AutoCompound { user } => {
+            let user_addr = deps.api.addr_validate(&user)?;
+            Ok(to_json_binary(
+                &AUTO_COMPOUND.may_load(deps.storage, &user_addr)?,
+            )?)
+        }
+        QueryMsg::Solvency {} => Ok(to_json_binary(&query_solvency(deps, env)?)?),
+        QueryMsg::Campaign { id } => Ok(to_json_binary(&CAMPAIGNS.load(deps.storage, id)?)?),
+        QueryMsg::Campaigns { start_after, limit } => {
+            Ok(to_json_binary(&query_campaigns(deps, start_after, limit)?)?)
+        }
+        QueryMsg::ValidatorMultipliers { start_after, limit } => Ok(to_json_binary(
+            &query_validator_multipliers(deps, start_after, limit)?,
+        )?),
     }
 }
 
@@ -383,6 +1319,12 @@ fn query_config(deps: Deps) -> StdResult<ConfigResponse> {
         annual_reward_rate_bps: config.annual_reward_rate_bps,
         blocks_per_year: config.blocks_per_year,
         staking_denom: config.staking_denom,
+        reward_rate_schedule: config.reward_rate_schedule,
+        pause_accrual_on_insolvency: config.pause_accrual_on_insolvency,
+        staking_tracker: config.staking_tracker.map(|addr| addr.to_string()),
+        claim_vesting_blocks: config.claim_vesting_blocks,
+        forfeit_unvested_on_unstake: config.forfeit_unvested_on_unstake,
+        campaign_min_funds: config.campaign_min_funds,
     })
 }
 
@@ -396,13 +1338,195 @@ fn query_state(deps: Deps) -> StdResult<StateResponse> {
 }
 
 /// Returns slice of slashing events.
-fn query_slashing_events(deps: Deps, from_height: u64) -> StdResult<SlashingEventsResponse> {
-    let state = STATE.load(deps.storage)?;
+fn query_slashing_events(
+    deps: Deps,
+    from_height: u64,
+    limit: Option<u32>,
+) -> StdResult<SlashingEventsResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
     Ok(SlashingEventsResponse {
-        slashing_events: state.load_unprocessed_slashing_events(from_height),
+        slashing_events: load_slashing_events(deps.storage, from_height, Some(limit))?,
+    })
+}
+
+/// Returns the annual reward rate in effect at the given height.
+fn query_reward_rate(deps: Deps, env: Env, height: Option<u64>) -> StdResult<RewardRateResponse> {
+    let height = height.unwrap_or(env.block.height);
+    // Config updates collapse the started segments of the schedule into the base rate, so the rate
+    // at a past height is taken from the config at the end of that block
+    let config = match height < env.block.height {
+        true => load_config_at_height(deps.storage, height)?,
+        false => None,
+    };
+    let config = match config {
+        Some(config) => config,
+        None => CONFIG.load(deps.storage)?,
+    };
+    Ok(RewardRateResponse {
+        height,
+        annual_reward_rate_bps: config.reward_rate_bps_at(height),
+    })
+}
+
+/// Returns the solvency of the contract, simulating a global index update at the current block.
+fn query_solvency(deps: Deps, env: Env) -> Result<SolvencyResponse, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let state = STATE.load(deps.storage)?;
+    let total_reward_weight = TOTAL_REWARD_WEIGHT
+        .may_load(deps.storage)?
+        .unwrap_or_default();
+    let updated_state = get_updated_state(&config, &state, total_reward_weight, env.block.height)?;
+
+    let balance = query_available_balance(deps, &env, &config)?;
+    let liabilities = updated_state.total_accrued_rewards.to_uint_ceil();
+
+    // Rewards accrued per block = total_reward_weight * annual_rate / blocks_per_year
+    let rewards_per_block = Decimal::checked_from_ratio(
+        total_reward_weight
+            .checked_mul(Uint128::from(config.reward_rate_bps_at(env.block.height)))?,
+        Uint128::from(config.blocks_per_year).checked_mul(Uint128::new(10_000))?,
+    )?;
+    let runway_blocks = if updated_state.accrual_paused || rewards_per_block.is_zero() {
+        None
+    } else {
+        let surplus = Decimal::checked_from_ratio(balance.saturating_sub(liabilities), 1u128)?;
+        // The runway doesn't fit into a Decimal if the rewards per block are tiny
+        Some(
+            surplus
+                .checked_div(rewards_per_block)
+                .map_or(u64::MAX, |runway| {
+                    runway.to_uint_floor().u128().try_into().unwrap_or(u64::MAX)
+                }),
+        )
+    };
+
+    Ok(SolvencyResponse {
+        balance,
+        liabilities,
+        runway_blocks,
+        accrual_paused: updated_state.accrual_paused,
+    })
+}
+
+/// Returns reward campaigns ordered by id.
+fn query_campaigns(
+    deps: Deps,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<CampaignsResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let campaigns = CAMPAIGNS
+        .range(
+            deps.storage,
+            start_after.map(Bound::exclusive),
+            None,
+            Order::Ascending,
+        )
+        .take(limit)
+        .map(|item| item.map(|(_, campaign)| campaign))
+        .collect::<StdResult<Vec<_>>>()?;
+    Ok(CampaignsResponse { campaigns })
+}
+
+/// Returns the staking rewards accrued to the user in the blocks after `from_height` up to and
+/// including `to_height`.
+fn query_rewards_accrued(
+    deps: Deps,
+    user: String,
+    from_height: u64,
+    to_height: u64,
+) -> Result<RewardsAccruedResponse, ContractError> {
+    if from_height > to_height {
+        return Err(ContractError::InvalidHeightRange {});
+    }
+
+    let config = CONFIG.load(deps.storage)?;
+    let user_addr = deps.api.addr_validate(&user)?;
+    let rewards =
+        load_cumulative_rewards_at_height(deps, &config, &user_addr, to_height)?.saturating_sub(
+            load_cumulative_rewards_at_height(deps, &config, &user_addr, from_height)?,
+        );
+
+    Ok(RewardsAccruedResponse {
+        from_height,
+        to_height,
+        rewards: coin(rewards.u128(), config.staking_denom),
+    })
+}
+
+/// Returns the user's vesting rewards, unlocked up to the current block.
+fn query_vested_rewards(deps: Deps, env: Env, user: String) -> StdResult<VestedRewardsResponse> {
+    let config = CONFIG.load(deps.storage)?;
+    let user_addr = deps.api.addr_validate(&user)?;
+
+    let mut withdrawable = Uint128::zero();
+    let mut locked = Uint128::zero();
+    for entry in load_vesting_entries(deps.storage, &user_addr, env.block.height)? {
+        withdrawable = withdrawable.checked_add(entry.vested)?;
+        locked = locked.checked_add(entry.locked)?;
+    }
+
+    Ok(VestedRewardsResponse {
+        withdrawable: coin(withdrawable.u128(), config.staking_denom.clone()),
+        locked: coin(locked.u128(), config.staking_denom),
     })
 }
 
+/// Returns the user's vesting entries ordered by claim height, unlocked up to the current block.
+fn query_vesting_entries(
+    deps: Deps,
+    env: Env,
+    user: String,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<VestingEntriesResponse> {
+    let user_addr = deps.api.addr_validate(&user)?;
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let entries = VESTING_ENTRIES
+        .prefix(&user_addr)
+        .range(
+            deps.storage,
+            start_after.map(Bound::exclusive),
+            None,
+            Order::Ascending,
+        )
+        .take(limit)
+        .map(|item| {
+            item.map(|(_, mut entry)| {
+                entry.vest(env.block.height);
+                entry
+            })
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+    Ok(VestingEntriesResponse { entries })
+}
+
+/// Returns the validator reward multipliers ordered by validator address.
+fn query_validator_multipliers(
+    deps: Deps,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<ValidatorMultipliersResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start_after = start_after.map(Addr::unchecked);
+    let multipliers = VALIDATOR_MULTIPLIERS
+        .range(
+            deps.storage,
+            start_after.as_ref().map(Bound::exclusive),
+            None,
+            Order::Ascending,
+        )
+        .take(limit)
+        .map(|item| {
+            item.map(|(validator, multiplier)| ValidatorMultiplier {
+                validator: validator.to_string(),
+                multiplier,
+            })
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+    Ok(ValidatorMultipliersResponse { multipliers })
+}
+
 /// Returns true if contract is paused, false if not
 fn query_is_paused(deps: Deps) -> StdResult<bool> {
     let is_paused = PAUSED.load(deps.storage)?;
@@ -415,9 +1539,22 @@ fn query_rewards(deps: Deps, env: Env, user: String) -> Result<RewardsResponse,
     let config = CONFIG.load(deps.storage)?;
     let user_addr = deps.api.addr_validate(&user)?;
 
+    let mut campaign_rewards = Coins::default();
+    for (campaign, user_rewards) in
+        get_updated_user_campaigns(deps, &user_addr, env.block.height)?.campaigns
+    {
+        campaign_rewards.add(coin(
+            user_rewards.pending_rewards.u128(),
+            campaign.reward.denom,
+        ))?;
+    }
+
     let (user_info, state) = process_slashing_events(deps, config.clone(), user_addr)?;
 
-    let updated_state = get_updated_state(&config, &state, env.block.height)?;
+    let total_reward_weight = TOTAL_REWARD_WEIGHT
+        .may_load(deps.storage)?
+        .unwrap_or_default();
+    let updated_state = get_updated_state(&config, &state, total_reward_weight, env.block.height)?;
     let updated_user_info = get_updated_user_info(
         user_info,
         updated_state.global_reward_index,
@@ -427,23 +1564,115 @@ fn query_rewards(deps: Deps, env: Env, user: String) -> Result<RewardsResponse,
 
     Ok(RewardsResponse {
         pending_rewards: updated_user_info.pending_rewards,
+        campaign_rewards: campaign_rewards.into_vec(),
+        reward_multiplier: updated_user_info
+            .reward_multiplier
+            .unwrap_or(Decimal::one()),
     })
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn migrate(deps: DepsMut, _env: Env, _msg: MigrateMsg) -> Result<Response, ContractError> {
+pub fn migrate(deps: DepsMut, env: Env, _msg: MigrateMsg) -> Result<Response, ContractError> {
     // Set contract to version to latest
     set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    // Move the slashing events stored in the state to their own map. The legacy layout can't be
+    // loaded once the state is migrated.
+    if let Ok(legacy_state) = LEGACY_STATE.load(deps.storage) {
+        for (global_index, height) in legacy_state.slashing_events {
+            SLASHING_EVENTS.save(deps.storage, height, &global_index)?;
+        }
+        save_state(
+            deps.storage,
+            &State {
+                global_reward_index: legacy_state.global_reward_index,
+                global_update_height: legacy_state.global_update_height,
+                total_accrued_rewards: legacy_state.total_accrued_rewards,
+                accrual_paused: legacy_state.accrual_paused,
+            },
+            env.block.height,
+        )?;
+    }
+
+    // The history of the config and the state starts with the migration to the version that
+    // records it, and the history of a user with their last update before it
+    if load_config_at_height(deps.storage, env.block.height)?.is_none() {
+        save_config(deps.storage, &CONFIG.load(deps.storage)?, env.block.height)?;
+        save_state(deps.storage, &STATE.load(deps.storage)?, env.block.height)?;
+    }
+
+    // The totals are tracked incrementally once they exist. When migrating from the version that
+    // didn't track them, they are computed from the user infos page by page with
+    // `BackfillTotals`, and the contract stays paused so that no stake update changes them in
+    // the meantime.
+    if !TOTAL_STAKE.exists(deps.storage) {
+        TOTAL_STAKE.save(deps.storage, &Uint128::zero())?;
+        TOTAL_REWARD_WEIGHT.save(deps.storage, &Uint128::zero())?;
+        let mut state = STATE.load(deps.storage)?;
+        state.total_accrued_rewards = Decimal::zero();
+        save_state(deps.storage, &state, env.block.height)?;
+        TOTALS_BACKFILL.save(
+            deps.storage,
+            &TotalsBackfill {
+                last_user: None,
+                was_paused: PAUSED.may_load(deps.storage)?.unwrap_or_default(),
+            },
+        )?;
+        PAUSED.save(deps.storage, &true)?;
+    }
+    if !CAMPAIGN_RESERVES.exists(deps.storage) {
+        CAMPAIGN_RESERVES.save(deps.storage, &Uint128::zero())?;
+    }
+
     Ok(Response::default())
 }
 
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn reply(deps: DepsMut, env: Env, msg: Reply) -> Result<Response, ContractError> {
+    match msg.id {
+        COMPOUND_REPLY_ID => {
+            let SubMsgResult::Err(error) = msg.result else {
+                return Ok(Response::new());
+            };
+
+            // The transfer is reverted along with the delegation, so the rewards are pending
+            // again
+            let (user_addr, amount): (Addr, Uint128) = from_json(&msg.payload)?;
+            let mut user_info = USERS.load(deps.storage, &user_addr)?;
+            user_info.pending_rewards.amount =
+                user_info.pending_rewards.amount.checked_add(amount)?;
+            save_user(deps.storage, &user_addr, &user_info, env.block.height)?;
+            let mut state = STATE.load(deps.storage)?;
+            state.total_accrued_rewards = state
+                .total_accrued_rewards
+                .checked_add(Decimal::checked_from_ratio(amount, 1u128)?)?;
+            save_state(deps.storage, &state, env.block.height)?;
+
+            let mut settings = AUTO_COMPOUND.load(deps.storage, &user_addr)?;
+            if let Some(result) = settings.last_result.as_mut() {
+                result.error = Some(error.clone());
+            }
+            AUTO_COMPOUND.save(deps.storage, &user_addr, &settings)?;
+
+            Ok(Response::new()
+                .add_attribute("action", "compound_failed")
+                .add_attribute("user", user_addr)
+                .add_attribute("error", error))
+        }
+        id => Err(StdError::generic_err(format!("unknown reply id: {id}")).into()),
+    }
+}
+
 // ----------------------------------------------------------------------------
 //  Internal Logic
 // ----------------------------------------------------------------------------
 
+/// Advances the global reward index to the given height, and adds the rewards accrued on the
+/// total reward weight to the liabilities. Nothing accrues while the accrual is paused.
 fn get_updated_state(
     config: &Config,
     state: &State,
+    total_reward_weight: Uint128,
     new_height: u64,
 ) -> Result<State, ContractError> {
     let mut state = state.clone();
@@ -454,12 +1683,43 @@ fn get_updated_state(
         state.global_reward_index,
         state.global_update_height,
     )?;
-    state.global_reward_index = new_global_index;
+    if !state.accrual_paused {
+        let accrued = Decimal::checked_from_ratio(total_reward_weight, 1u128)?
+            .checked_mul(new_global_index - state.global_reward_index)?;
+        state.total_accrued_rewards = state.total_accrued_rewards.checked_add(accrued)?;
+        state.global_reward_index = new_global_index;
+    }
     state.global_update_height = new_height;
 
     Ok(state)
 }
 
+/// Pauses the reward accrual if the accrued rewards exceed the balance available to pay them,
+/// and resumes it once the contract is funded again. The rewards of the blocks in between are
+/// not accrued.
+fn update_accrual_pause(
+    deps: Deps,
+    env: &Env,
+    config: &Config,
+    state: &mut State,
+) -> Result<(), ContractError> {
+    state.accrual_paused = config.pause_accrual_on_insolvency
+        && state.total_accrued_rewards.to_uint_ceil() > query_available_balance(deps, env, config)?;
+
+    Ok(())
+}
+
+/// Returns the contract balance in the staking denom, excluding unclaimed campaign funds.
+fn query_available_balance(deps: Deps, env: &Env, config: &Config) -> StdResult<Uint128> {
+    let balance = deps
+        .querier
+        .query_balance(&env.contract.address, &config.staking_denom)?;
+    let campaign_reserves = CAMPAIGN_RESERVES
+        .may_load(deps.storage)?
+        .unwrap_or_default();
+    Ok(balance.amount.saturating_sub(campaign_reserves))
+}
+
 fn get_updated_user_info(
     user_info: UserInfo,
     global_index: Decimal,
@@ -470,6 +1730,9 @@ fn get_updated_user_info(
 
     // Calculate and accumulate any pending rewards
     let pending_rewards = get_user_pending_rewards(user_info.clone(), global_index, staking_denom)?;
+    user_info.cumulative_rewards = user_info
+        .cumulative_rewards
+        .checked_add(pending_rewards.amount - user_info.pending_rewards.amount)?;
     user_info.pending_rewards = pending_rewards;
     user_info.user_reward_index = global_index;
     user_info.last_update_block = new_height;
@@ -488,48 +1751,107 @@ fn process_slashing_events(
         load_user_or_default(deps, user_addr.clone(), config.staking_denom.clone())?;
 
     if existed {
-        let slashing_events = state.load_unprocessed_slashing_events(user_info.last_update_block);
-        for (slashing_event_global_index, slashing_event_height) in slashing_events.into_iter() {
-            user_info = get_updated_user_info(
-                user_info.clone(),
-                slashing_event_global_index,
-                slashing_event_height,
-                config.staking_denom.clone(),
-            )?;
+        user_info = apply_slashing_events(deps, &config, &user_addr, user_info, u64::MAX)?;
+    }
 
-            // Set the user stake to the value after the slashing event
-            user_info.stake = safe_query_user_stake(
-                &deps,
-                user_addr.clone(),
-                config.staking_info_proxy.clone(),
-                config.staking_denom.clone(),
-                slashing_event_height,
-            )?;
+    Ok((user_info, state))
+}
+
+/// Accrues the user's rewards up to each slashing event since the user's last update, and sets
+/// the user's stake to the value after it. Only the events up to `to_height` are applied.
+fn apply_slashing_events(
+    deps: Deps,
+    config: &Config,
+    user_addr: &Addr,
+    mut user_info: UserInfo,
+    to_height: u64,
+) -> Result<UserInfo, ContractError> {
+    let slashing_events = load_slashing_events(deps.storage, user_info.last_update_block, None)?;
+    for (slashing_event_global_index, slashing_event_height) in slashing_events
+        .into_iter()
+        .take_while(|(_, height)| *height <= to_height)
+    {
+        user_info = get_updated_user_info(
+            user_info.clone(),
+            slashing_event_global_index,
+            slashing_event_height,
+            config.staking_denom.clone(),
+        )?;
+
+        // Set the user stake to the value after the slashing event
+        user_info.stake = safe_query_user_stake(
+            &deps,
+            user_addr.clone(),
+            config.staking_info_proxy.clone(),
+            config.staking_denom.clone(),
+            slashing_event_height,
+        )?;
+    }
+
+    Ok(user_info)
+}
+
+/// Returns the global reward index at the end of the given block. The index is advanced from the
+/// state recorded at the end of that block, with the config in effect at that height.
+fn load_global_index_at_height(deps: Deps, height: u64) -> Result<Decimal, ContractError> {
+    let state = load_state_at_height(deps.storage, height)?;
+    let config = load_config_at_height(deps.storage, height)?;
+    match (state, config) {
+        (Some(state), Some(config)) if state.global_update_height <= height => {
+            if state.accrual_paused {
+                return Ok(state.global_reward_index);
+            }
+            get_updated_global_index(
+                config,
+                height,
+                state.global_reward_index,
+                state.global_update_height,
+            )
         }
+        _ => Err(ContractError::HistoryNotAvailable { height }),
     }
+}
 
-    Ok((user_info, state))
+/// Returns the staking rewards accrued to the user up to the end of the given block, since the
+/// reward history is recorded.
+fn load_cumulative_rewards_at_height(
+    deps: Deps,
+    config: &Config,
+    user_addr: &Addr,
+    height: u64,
+) -> Result<Uint128, ContractError> {
+    let Some(user_info) = load_user_at_height(deps.storage, user_addr, height)? else {
+        return Ok(Uint128::zero());
+    };
+
+    let user_info = apply_slashing_events(deps, config, user_addr, user_info, height)?;
+    let global_index = load_global_index_at_height(deps, height)?;
+    let user_info = get_updated_user_info(
+        user_info,
+        global_index,
+        height,
+        config.staking_denom.clone(),
+    )?;
+
+    Ok(user_info.cumulative_rewards)
 }
 
 /// Updates the global reward index in state based on how many blocks have passed since last update.
 fn update_global_index(deps: DepsMut, env: &Env, config: Config) -> Result<Decimal, ContractError> {
-    let mut state = STATE.load(deps.storage)?;
+    let state = STATE.load(deps.storage)?;
+    let total_reward_weight = TOTAL_REWARD_WEIGHT
+        .may_load(deps.storage)?
+        .unwrap_or_default();
 
-    let new_global_index = get_updated_global_index(
-        config.clone(),
-        env.block.height,
-        state.global_reward_index,
-        state.global_update_height,
-    )?;
-    state.global_reward_index = new_global_index;
-    state.global_update_height = env.block.height;
-    STATE.save(deps.storage, &state)?;
+    let state = get_updated_state(&config, &state, total_reward_weight, env.block.height)?;
+    save_state(deps.storage, &state, env.block.height)?;
 
-    Ok(new_global_index)
+    Ok(state.global_reward_index)
 }
 
 /// Computes what the global reward index should be, given the elapsed time and the configured
-/// reward rate.
+/// reward rate schedule. The elapsed blocks are split at the rate changes, and each part accrues
+/// at the rate in effect for it.
 fn get_updated_global_index(
     config: Config,
     current_block: u64,
@@ -547,21 +1869,508 @@ fn get_updated_global_index(
         return Ok(old_global_index);
     }
 
-    // Calculate number of blocks since last global index update
-    let delta_t = current_block - last_global_update_block;
-
-    // Convert annual reward rate in basis points (bps) to a Decimal (e.g., 500 bps = 5%)
-    let annual_rate = Decimal::from_ratio(config.annual_reward_rate_bps, 10_000u64);
     // Convert blocks_per_year to a Decimal
     let blocks_per_year = Decimal::from_atomics(config.blocks_per_year, 0).unwrap_or_default();
-    // Reward rate per block = (annual_rate / blocks_per_year)
-    let rate_per_block = annual_rate / blocks_per_year;
 
-    // Increase in index over the time delta
-    let delta_index = rate_per_block * Decimal::from_atomics(delta_t, 0).unwrap();
+    let mut new_global_index = old_global_index;
+    let mut height = last_global_update_block;
+    while height < current_block {
+        // The rate is constant until the next rate change
+        let segment_end = config
+            .next_rate_change_height(height)
+            .map_or(current_block, |h| h.min(current_block));
+        // Calculate number of blocks in the segment
+        let delta_t = segment_end - height;
+
+        // Convert annual reward rate in basis points (bps) to a Decimal (e.g., 500 bps = 5%)
+        let annual_rate = Decimal::from_ratio(config.reward_rate_bps_at(height), 10_000u64);
+        // Reward rate per block = (annual_rate / blocks_per_year)
+        let rate_per_block = annual_rate / blocks_per_year;
+
+        // Increase in index over the time delta
+        let delta_index = rate_per_block * Decimal::from_atomics(delta_t, 0).unwrap();
+        new_global_index += delta_index;
+
+        height = segment_end;
+    }
 
     // The new global index is the old index plus any delta over the elapsed blocks
-    Ok(delta_index + old_global_index)
+    Ok(new_global_index)
+}
+
+/// Replaces the user's stake and reward weight in the totals known to the contract. The old user
+/// info is the one known to the contract, i.e. without unprocessed slashing events.
+fn update_totals(
+    storage: &mut dyn Storage,
+    old_user_info: Option<&UserInfo>,
+    new_user_info: &UserInfo,
+) -> Result<(), ContractError> {
+    let (old_stake, old_reward_weight) = match old_user_info {
+        Some(user_info) => (user_info.stake.amount, user_info.reward_weight()?),
+        None => (Uint128::zero(), Uint128::zero()),
+    };
+
+    let total_stake = TOTAL_STAKE.may_load(storage)?.unwrap_or_default();
+    TOTAL_STAKE.save(
+        storage,
+        &total_stake
+            .checked_sub(old_stake)?
+            .checked_add(new_user_info.stake.amount)?,
+    )?;
+    let total_reward_weight = TOTAL_REWARD_WEIGHT.may_load(storage)?.unwrap_or_default();
+    TOTAL_REWARD_WEIGHT.save(
+        storage,
+        &total_reward_weight
+            .checked_sub(old_reward_weight)?
+            .checked_add(new_user_info.reward_weight()?)?,
+    )?;
+
+    Ok(())
+}
+
+/// Loads the user's vesting entries, unlocked up to the given height.
+fn load_vesting_entries(
+    storage: &dyn Storage,
+    user_addr: &Addr,
+    height: u64,
+) -> StdResult<Vec<VestingEntry>> {
+    VESTING_ENTRIES
+        .prefix(user_addr)
+        .range(storage, None, None, Order::Ascending)
+        .map(|item| {
+            item.map(|(_, mut entry)| {
+                entry.vest(height);
+                entry
+            })
+        })
+        .collect()
+}
+
+/// Locks the claimed rewards in a vesting entry unlocking over `vesting_blocks`, and returns the
+/// height the entry is fully unlocked at. The claims made at the same height share an entry.
+fn add_vesting_entry(
+    storage: &mut dyn Storage,
+    user_addr: &Addr,
+    amount: Uint128,
+    height: u64,
+    vesting_blocks: u64,
+) -> Result<u64, ContractError> {
+    let entry = match VESTING_ENTRIES.may_load(storage, (user_addr, height))? {
+        Some(mut entry) => {
+            entry.locked = entry.locked.checked_add(amount)?;
+            entry
+        }
+        None => {
+            let entries_count = VESTING_ENTRIES
+                .prefix(user_addr)
+                .keys(storage, None, None, Order::Ascending)
+                .count();
+            if entries_count >= MAX_VESTING_ENTRIES as usize {
+                return Err(ContractError::TooManyVestingEntries {
+                    max: MAX_VESTING_ENTRIES,
+                });
+            }
+            VestingEntry {
+                start_height: height,
+                end_height: height + vesting_blocks,
+                locked: amount,
+                vested: Uint128::zero(),
+                update_height: height,
+            }
+        }
+    };
+    VESTING_ENTRIES.save(storage, (user_addr, height), &entry)?;
+
+    Ok(entry.end_height)
+}
+
+/// Forfeits the locked rewards of the user's vesting entries in proportion to the unstaked
+/// tokens, and returns the forfeited amount.
+fn forfeit_unvested_rewards(
+    storage: &mut dyn Storage,
+    user_addr: &Addr,
+    height: u64,
+    old_stake: Uint128,
+    new_stake: Uint128,
+) -> Result<Uint128, ContractError> {
+    let unstaked = old_stake.checked_sub(new_stake)?;
+
+    let mut forfeited = Uint128::zero();
+    for mut entry in load_vesting_entries(storage, user_addr, height)? {
+        let entry_forfeited = entry.locked.multiply_ratio(unstaked, old_stake);
+        entry.locked -= entry_forfeited;
+        forfeited = forfeited.checked_add(entry_forfeited)?;
+        if entry.locked.is_zero() && entry.vested.is_zero() {
+            VESTING_ENTRIES.remove(storage, (user_addr, entry.start_height));
+        } else {
+            VESTING_ENTRIES.save(storage, (user_addr, entry.start_height), &entry)?;
+        }
+    }
+
+    Ok(forfeited)
+}
+
+/// Computes the user's reward multiplier from their delegations in the staking tracker: the
+/// average of the validator multipliers weighted by the delegated tokens. As with the user's
+/// stake, only the delegations to bonded validators count. Returns `None` if no validator is
+/// boosted, the user has no such delegations or the staking tracker can't be queried.
+fn query_reward_multiplier(
+    deps: Deps,
+    env: &Env,
+    config: &Config,
+    user_addr: &Addr,
+) -> Result<Option<Decimal>, ContractError> {
+    let Some(staking_tracker) = config.staking_tracker.as_ref() else {
+        return Ok(None);
+    };
+    if VALIDATOR_MULTIPLIERS.is_empty(deps.storage) {
+        return Ok(None);
+    }
+
+    // A failing staking tracker can't fail the stake update, the stake isn't boosted then
+    let Ok(delegations) = query_bonded_delegations(deps, env, staking_tracker, user_addr) else {
+        return Ok(None);
+    };
+
+    let mut total_tokens = Uint128::zero();
+    let mut weighted_tokens = Uint128::zero();
+    for (validator, tokens) in delegations {
+        let multiplier = VALIDATOR_MULTIPLIERS
+            .may_load(deps.storage, &validator)?
+            .unwrap_or(Decimal::one());
+        total_tokens = total_tokens.checked_add(tokens)?;
+        weighted_tokens = weighted_tokens.checked_add(tokens.checked_mul_floor(multiplier)?)?;
+    }
+
+    to_reward_multiplier(weighted_tokens, total_tokens)
+}
+
+/// Splits the amount between the bonded validators the user delegates to, pro-rata to the
+/// delegated tokens. The rounding remainder goes to the first validator.
+fn split_by_bonded_delegations(
+    deps: Deps,
+    env: &Env,
+    config: &Config,
+    user_addr: &Addr,
+    amount: Uint128,
+) -> Result<Vec<(Addr, Uint128)>, ContractError> {
+    let Some(staking_tracker) = config.staking_tracker.as_ref() else {
+        return Ok(vec![]);
+    };
+
+    let delegations = query_bonded_delegations(deps, env, staking_tracker, user_addr)?;
+    let total_tokens = delegations
+        .iter()
+        .try_fold(Uint128::zero(), |acc, (_, tokens)| acc.checked_add(*tokens))?;
+    if total_tokens.is_zero() {
+        return Ok(vec![]);
+    }
+
+    let mut split: Vec<(Addr, Uint128)> = delegations
+        .into_iter()
+        .map(|(validator, tokens)| (validator, amount.multiply_ratio(tokens, total_tokens)))
+        .collect();
+    let distributed = split
+        .iter()
+        .try_fold(Uint128::zero(), |acc, (_, a)| acc.checked_add(*a))?;
+    split[0].1 = split[0].1.checked_add(amount.checked_sub(distributed)?)?;
+    split.retain(|(_, a)| !a.is_zero());
+
+    Ok(split)
+}
+
+/// Queries the user's delegations to bonded validators from the staking tracker, and returns the
+/// validators with the delegated tokens, i.e. the shares converted at the validators' exchange
+/// rates.
+fn query_bonded_delegations(
+    deps: Deps,
+    env: &Env,
+    staking_tracker: &Addr,
+    user_addr: &Addr,
+) -> Result<Vec<(Addr, Uint128)>, ContractError> {
+    // increment height because the staking tracker returns the state before the changes made at
+    // the height
+    let height = env.block.height + 1;
+    let bonded_validators: Vec<Addr> = deps.querier.query_wasm_smart(
+        staking_tracker,
+        &TrackerQueryMsg::BondedValidators {
+            height: Some(height),
+        },
+    )?;
+
+    let mut bonded_delegations = vec![];
+    // Delegations are keyed by (delegator, validator), so the user's delegations start right
+    // after (user, "")
+    let mut start_after = (user_addr.clone(), Addr::unchecked(""));
+    loop {
+        let delegations: Vec<Delegation> = deps.querier.query_wasm_smart(
+            staking_tracker,
+            &TrackerQueryMsg::ListDelegations {
+                start_after: Some(start_after.clone()),
+                limit: Some(MAX_LIMIT),
+            },
+        )?;
+        let last_page = delegations.len() < MAX_LIMIT as usize;
+
+        for delegation in delegations.iter() {
+            if delegation.delegator_address != *user_addr {
+                return Ok(bonded_delegations);
+            }
+            if !bonded_validators.contains(&delegation.validator_address) {
+                continue;
+            }
+
+            let validator: Option<Validator> = deps.querier.query_wasm_smart(
+                staking_tracker,
+                &TrackerQueryMsg::Validator {
+                    validator: delegation.validator_address.to_string(),
+                    height: Some(height),
+                },
+            )?;
+            let tokens = match validator {
+                Some(validator) if !validator.total_shares.is_zero() => delegation
+                    .shares
+                    .multiply_ratio(validator.total_tokens, validator.total_shares),
+                _ => Uint128::zero(),
+            };
+            bonded_delegations.push((delegation.validator_address.clone(), tokens));
+        }
+
+        match delegations.last() {
+            Some(delegation) if !last_page => {
+                start_after = (user_addr.clone(), delegation.validator_address.clone())
+            }
+            _ => return Ok(bonded_delegations),
+        }
+    }
+}
+
+fn to_reward_multiplier(
+    weighted_tokens: Uint128,
+    total_tokens: Uint128,
+) -> Result<Option<Decimal>, ContractError> {
+    if total_tokens.is_zero() {
+        return Ok(None);
+    }
+
+    let multiplier = Decimal::checked_from_ratio(weighted_tokens, total_tokens)?;
+    Ok(if multiplier == Decimal::one() {
+        None
+    } else {
+        Some(multiplier)
+    })
+}
+
+/// Computes the campaign reward index at the given height. The rewards of the blocks without any
+/// stake are not distributed, they are left for the funder to reclaim.
+fn get_updated_campaign(
+    mut campaign: RewardCampaign,
+    total_stake: Uint128,
+    height: u64,
+) -> Result<RewardCampaign, ContractError> {
+    let to_height = height.min(campaign.end_height);
+    if to_height <= campaign.last_update_height {
+        return Ok(campaign);
+    }
+
+    if !total_stake.is_zero() {
+        // reward * blocks / (duration * total_stake)
+        let blocks = Uint128::from(to_height - campaign.last_update_height);
+        let duration = Uint128::from(campaign.end_height - campaign.start_height);
+        campaign.reward_index += Decimal::checked_from_ratio(
+            campaign.reward.amount.checked_mul(blocks)?,
+            duration.checked_mul(total_stake)?,
+        )?;
+    } else {
+        let blocks = to_height - campaign.last_update_height;
+        let duration = campaign.end_height - campaign.start_height;
+        campaign.undistributed = campaign
+            .undistributed
+            .checked_add(campaign.reward.amount.multiply_ratio(blocks, duration))?;
+    }
+    campaign.last_update_height = to_height;
+
+    Ok(campaign)
+}
+
+/// The updated campaigns and campaign rewards of a user, along with the progress of the user's
+/// campaign settlements.
+struct UserCampaigns {
+    campaigns: Vec<(RewardCampaign, UserCampaignRewards)>,
+    /// The settlements that were loaded, keyed by the id they started from, with the id to
+    /// continue from if they aren't done.
+    settlements: Vec<(u64, Option<(u64, CampaignSettlement)>)>,
+    next_campaign_id: u64,
+}
+
+/// Returns the campaigns updated to the given height, along with the user's campaign rewards
+/// accrued on the stake known to the contract. Only the campaigns the user has rewards data of,
+/// the campaigns that haven't ended, and up to `MAX_SETTLED_CAMPAIGNS` campaigns created since the
+/// user's last update that have ended are loaded.
+fn get_updated_user_campaigns(
+    deps: Deps,
+    user_addr: &Addr,
+    height: u64,
+) -> Result<UserCampaigns, ContractError> {
+    let user_info = USERS.may_load(deps.storage, user_addr)?;
+    let total_stake = TOTAL_STAKE.may_load(deps.storage)?.unwrap_or_default();
+    let stake = user_info
+        .as_ref()
+        .map(|user_info| user_info.stake.amount)
+        .unwrap_or_default();
+
+    let mut ids = USER_CAMPAIGN_REWARDS
+        .prefix(user_addr)
+        .keys(deps.storage, None, None, Order::Ascending)
+        .collect::<StdResult<BTreeSet<u64>>>()?;
+    for item in CAMPAIGN_END_HEIGHTS.keys(
+        deps.storage,
+        Some(Bound::exclusive((height, u64::MAX))),
+        None,
+        Order::Ascending,
+    ) {
+        let (_, id) = item?;
+        ids.insert(id);
+    }
+
+    let mut user_campaigns = vec![];
+    for id in ids {
+        let campaign = CAMPAIGNS.load(deps.storage, id)?;
+        // A campaign that hasn't ended and that the user has no rewards data of was created after
+        // the last update of the user, so the user's stake hasn't changed since the campaign start
+        let user_rewards = USER_CAMPAIGN_REWARDS
+            .may_load(deps.storage, (user_addr, id))?
+            .unwrap_or(UserCampaignRewards {
+                reward_index: Decimal::zero(),
+                pending_rewards: Uint128::zero(),
+            });
+
+        let campaign = get_updated_campaign(campaign, total_stake, height)?;
+        let newly_accrued =
+            stake.checked_mul_floor(campaign.reward_index - user_rewards.reward_index)?;
+        let user_rewards = UserCampaignRewards {
+            reward_index: campaign.reward_index,
+            pending_rewards: user_rewards.pending_rewards.checked_add(newly_accrued)?,
+        };
+        user_campaigns.push((campaign, user_rewards));
+    }
+
+    // The campaigns created since the last update of the user that have ended are settled with
+    // the user's stake since then. There can be any number of them, so they are settled a few at
+    // a time, and the rest are left to the next updates. The users that haven't been updated
+    // since campaigns were added have no rewards data of any campaign.
+    let next_campaign_id = CAMPAIGN_NEXT_ID.may_load(deps.storage)?.unwrap_or_default();
+    let first_new_id = USER_CAMPAIGN_NEXT_IDS
+        .may_load(deps.storage, user_addr)?
+        .unwrap_or_default();
+    let mut budget = MAX_SETTLED_CAMPAIGNS as u64;
+    let mut settlements = vec![];
+    for item in
+        CAMPAIGN_SETTLEMENTS
+            .prefix(user_addr)
+            .range(deps.storage, None, None, Order::Ascending)
+    {
+        if budget == 0 {
+            break;
+        }
+        let (start_id, settlement) = item?;
+        let next_id = settle_user_campaigns(
+            deps,
+            start_id,
+            &settlement,
+            budget,
+            total_stake,
+            height,
+            &mut user_campaigns,
+        )?;
+        budget -= next_id - start_id;
+        settlements.push((
+            start_id,
+            (next_id < settlement.end_id).then_some((next_id, settlement)),
+        ));
+    }
+    if first_new_id < next_campaign_id && !stake.is_zero() {
+        let settlement = CampaignSettlement {
+            end_id: next_campaign_id,
+            until_height: height,
+            stake,
+        };
+        let next_id = settle_user_campaigns(
+            deps,
+            first_new_id,
+            &settlement,
+            budget,
+            total_stake,
+            height,
+            &mut user_campaigns,
+        )?;
+        settlements.push((
+            first_new_id,
+            (next_id < settlement.end_id).then_some((next_id, settlement)),
+        ));
+    }
+
+    Ok(UserCampaigns {
+        campaigns: user_campaigns,
+        settlements,
+        next_campaign_id,
+    })
+}
+
+/// Settles the campaigns of a settlement from `start_id` that had ended by its height, loading
+/// up to `budget` campaigns, and returns the id to continue from.
+fn settle_user_campaigns(
+    deps: Deps,
+    start_id: u64,
+    settlement: &CampaignSettlement,
+    budget: u64,
+    total_stake: Uint128,
+    height: u64,
+    user_campaigns: &mut Vec<(RewardCampaign, UserCampaignRewards)>,
+) -> Result<u64, ContractError> {
+    let next_id = settlement.end_id.min(start_id.saturating_add(budget));
+    for id in start_id..next_id {
+        let campaign = CAMPAIGNS.load(deps.storage, id)?;
+        if campaign.end_height > settlement.until_height {
+            continue;
+        }
+
+        let campaign = get_updated_campaign(campaign, total_stake, height)?;
+        let user_rewards = UserCampaignRewards {
+            reward_index: campaign.reward_index,
+            pending_rewards: settlement.stake.checked_mul_floor(campaign.reward_index)?,
+        };
+        user_campaigns.push((campaign, user_rewards));
+    }
+
+    Ok(next_id)
+}
+
+/// Saves the updated campaigns and the user's campaign rewards. The user's rewards of campaigns
+/// that have ended are removed once they are claimed.
+fn save_user_campaigns(
+    storage: &mut dyn Storage,
+    user_addr: &Addr,
+    user_campaigns: UserCampaigns,
+    height: u64,
+) -> StdResult<()> {
+    for (campaign, user_rewards) in user_campaigns.campaigns {
+        CAMPAIGNS.save(storage, campaign.id, &campaign)?;
+        if campaign.end_height <= height && user_rewards.pending_rewards.is_zero() {
+            USER_CAMPAIGN_REWARDS.remove(storage, (user_addr, campaign.id));
+        } else {
+            USER_CAMPAIGN_REWARDS.save(storage, (user_addr, campaign.id), &user_rewards)?;
+        }
+    }
+    for (start_id, settlement) in user_campaigns.settlements {
+        CAMPAIGN_SETTLEMENTS.remove(storage, (user_addr, start_id));
+        if let Some((next_id, settlement)) = settlement {
+            CAMPAIGN_SETTLEMENTS.save(storage, (user_addr, next_id), &settlement)?;
+        }
+    }
+    USER_CAMPAIGN_NEXT_IDS.save(storage, user_addr, &user_campaigns.next_campaign_id)?;
+
+    Ok(())
 }
 
 /// Loads user info from state, or returns a default if the user has no entry yet.
@@ -578,25 +2387,24 @@ fn load_user_or_default(
         stake: coin(0u128, staking_denom.clone()),
         last_update_block: 0u64,
         pending_rewards: coin(0u128, staking_denom.clone()),
+        reward_multiplier: None,
+        cumulative_rewards: Uint128::zero(),
     });
     Ok((user_info, existed))
 }
 
-/// Calculates a user’s pending rewards given their current stake, the global reward index, and
-/// the user’s last recorded reward index.
+/// Calculates a user’s pending rewards given their current reward weight, the global reward index,
+/// and the user’s last recorded reward index.
 fn get_user_pending_rewards(
     user_info: UserInfo,
     global_reward_index: Decimal,
     staking_denom: String,
 ) -> Result<Coin, ContractError> {
     let delta_index = global_reward_index - user_info.user_reward_index;
-    if !delta_index.is_zero() && !user_info.stake.amount.is_zero() {
+    let reward_weight = user_info.reward_weight()?;
+    if !delta_index.is_zero() && !reward_weight.is_zero() {
         let newly_accrued = coin(
-            user_info
-                .stake
-                .amount
-                .checked_mul_floor(delta_index)?
-                .u128(),
+            reward_weight.checked_mul_floor(delta_index)?.u128(),
             staking_denom.clone(),
         );
         return Ok(coin(
@@ -1,14 +1,214 @@
-use cosmwasm_std::{Addr, Storage};
-use cw_storage_plus::{Item, Map};
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{Addr, Decimal, Order, StdResult, Storage, Uint128};
+use cw_storage_plus::{Bound, Item, Map};
 use neutron_staking_rewards_common::error::ContractError;
-use neutron_staking_rewards_common::types::{Config, State, UserInfo};
+use neutron_staking_rewards_common::types::{
+    AutoCompoundSettings, ClaimSettings, Config, GlobalRewardIndex, RewardCampaign, SlashingEvent,
+    State, UserCampaignRewards, UserInfo, VestingEntry,
+};
 
+// The config, state and user infos are recorded in height-keyed history maps to answer historical
+// reward queries. They are saved with `save_config`, `save_state` and `save_user`.
 pub const CONFIG: Item<Config> = Item::new("config");
 pub const STATE: Item<State> = Item::new("state");
 pub const USERS: Map<&Addr, UserInfo> = Map::new("users");
+/// The config at the end of each block it was updated in.
+pub const CONFIG_HISTORY: Map<u64, Config> = Map::new("config_history");
+/// The state at the end of each block it was updated in.
+pub const STATE_HISTORY: Map<u64, State> = Map::new("state_history");
+/// The previous infos of each user, keyed by user and the height of the update they were saved
+/// at. The current user info is recorded when the user is updated at a later height.
+pub const USER_HISTORY: Map<(&Addr, u64), UserInfo> = Map::new("user_history");
+pub const CLAIM_SETTINGS: Map<&Addr, ClaimSettings> = Map::new("claim_settings");
+/// Settings of the users opted in to auto-compounding.
+pub const AUTO_COMPOUND: Map<&Addr, AutoCompoundSettings> = Map::new("auto_compound");
+/// Global reward index at the height of each slashing event.
+pub const SLASHING_EVENTS: Map<u64, GlobalRewardIndex> = Map::new("slashing_events");
 
 pub const PAUSED: Item<bool> = Item::new("paused");
 
+/// The sum of user stakes known to the contract, used to distribute campaign rewards.
+pub const TOTAL_STAKE: Item<Uint128> = Item::new("total_stake");
+/// The sum of user reward weights (stakes with the reward multipliers applied) known to the
+/// contract, which the staking rewards accrue on.
+pub const TOTAL_REWARD_WEIGHT: Item<Uint128> = Item::new("total_reward_weight");
+/// Reward multipliers of boosted validators, keyed by operator address.
+pub const VALIDATOR_MULTIPLIERS: Map<&Addr, Decimal> = Map::new("validator_multipliers");
+/// Campaign funds in the staking denom that haven't been claimed yet, which can't pay staking rewards.
+pub const CAMPAIGN_RESERVES: Item<Uint128> = Item::new("campaign_reserves");
+pub const CAMPAIGN_NEXT_ID: Item<u64> = Item::new("campaign_next_id");
+pub const CAMPAIGNS: Map<u64, RewardCampaign> = Map::new("campaigns");
+/// Campaign ids keyed by the campaign end height, used to load only the campaigns that hadn't
+/// ended at a user's last update.
+pub const CAMPAIGN_END_HEIGHTS: Map<(u64, u64), ()> = Map::new("campaign_end_heights");
+/// The maximum number of campaigns that haven't ended yet, which are all loaded on stake updates.
+pub const MAX_ACTIVE_CAMPAIGNS: u32 = 30;
+pub const USER_CAMPAIGN_REWARDS: Map<(&Addr, u64), UserCampaignRewards> =
+    Map::new("user_campaign_rewards");
+/// The next campaign id at each user's last update. The campaigns from it on were created after
+/// the update, so the user has no rewards data of them.
+pub const USER_CAMPAIGN_NEXT_IDS: Map<&Addr, u64> = Map::new("user_campaign_next_ids");
+
+/// Campaigns created between two updates of a user that had ended by the later one, which are
+/// settled with the user's stake in between.
+#[cw_serde]
+pub struct CampaignSettlement {
+    /// The id after the last campaign to settle.
+    pub end_id: u64,
+    /// The height of the later update, the campaigns that hadn't ended by then are settled as
+    /// active campaigns.
+    pub until_height: u64,
+    /// The user's stake between the updates.
+    pub stake: Uint128,
+}
+
+/// Campaign settlements left to later updates, keyed by user and the next campaign id to settle.
+pub const CAMPAIGN_SETTLEMENTS: Map<(&Addr, u64), CampaignSettlement> =
+    Map::new("campaign_settlements");
+/// The maximum number of campaigns loaded by a stake update to settle the ones that have ended
+/// since the user's last update.
+pub const MAX_SETTLED_CAMPAIGNS: u32 = 30;
+
+/// Vesting entries of claimed staking rewards, keyed by user and claim height.
+pub const VESTING_ENTRIES: Map<(&Addr, u64), VestingEntry> = Map::new("vesting_entries");
+/// The maximum number of a user's vesting entries, which are all loaded on stake updates.
+pub const MAX_VESTING_ENTRIES: u32 = 30;
+
+/// Progress of the computation of the totals from the user infos after the migration from the
+/// version that didn't track them. The contract stays paused until it's done.
+#[cw_serde]
+pub struct TotalsBackfill {
+    /// The last user added to the totals.
+    pub last_user: Option<Addr>,
+    /// Whether the contract was paused before the migration.
+    pub was_paused: bool,
+}
+
+pub const TOTALS_BACKFILL: Item<TotalsBackfill> = Item::new("totals_backfill");
+
+pub const DEFAULT_LIMIT: u32 = 10;
+pub const MAX_LIMIT: u32 = 30;
+
+/// The state layout before the slashing events were moved to `SLASHING_EVENTS`, used to migrate
+/// them.
+#[cw_serde]
+pub struct LegacyState {
+    pub global_reward_index: GlobalRewardIndex,
+    pub global_update_height: u64,
+    pub slashing_events: Vec<SlashingEvent>,
+    #[serde(default)]
+    pub total_accrued_rewards: Decimal,
+    #[serde(default)]
+    pub accrual_paused: bool,
+}
+
+pub const LEGACY_STATE: Item<LegacyState> = Item::new("state");
+
+/// Saves the config and records it as the config at the end of the block.
+pub fn save_config(storage: &mut dyn Storage, config: &Config, height: u64) -> StdResult<()> {
+    CONFIG.save(storage, config)?;
+    CONFIG_HISTORY.save(storage, height, config)
+}
+
+/// Saves the state and records it as the state at the end of the block.
+pub fn save_state(storage: &mut dyn Storage, state: &State, height: u64) -> StdResult<()> {
+    STATE.save(storage, state)?;
+    STATE_HISTORY.save(storage, height, state)
+}
+
+/// Saves the user info, recording the previous one if it was saved at an earlier height. The
+/// user infos are saved at their `last_update_block`.
+pub fn save_user(
+    storage: &mut dyn Storage,
+    user: &Addr,
+    user_info: &UserInfo,
+    height: u64,
+) -> StdResult<()> {
+    if let Some(previous) = USERS.may_load(storage, user)? {
+        if previous.last_update_block < height {
+            USER_HISTORY.save(storage, (user, previous.last_update_block), &previous)?;
+        }
+    }
+    USERS.save(storage, user, user_info)
+}
+
+/// Loads the config at the end of the given block, if its history was recorded by then.
+pub fn load_config_at_height(storage: &dyn Storage, height: u64) -> StdResult<Option<Config>> {
+    CONFIG_HISTORY
+        .range(
+            storage,
+            None,
+            Some(Bound::inclusive(height)),
+            Order::Descending,
+        )
+        .next()
+        .transpose()
+        .map(|item| item.map(|(_, config)| config))
+}
+
+/// Loads the state at the end of the given block, if its history was recorded by then.
+pub fn load_state_at_height(storage: &dyn Storage, height: u64) -> StdResult<Option<State>> {
+    STATE_HISTORY
+        .range(
+            storage,
+            None,
+            Some(Bound::inclusive(height)),
+            Order::Descending,
+        )
+        .next()
+        .transpose()
+        .map(|item| item.map(|(_, state)| state))
+}
+
+/// Loads the user info at the end of the given block, if the user had been updated by then.
+pub fn load_user_at_height(
+    storage: &dyn Storage,
+    user: &Addr,
+    height: u64,
+) -> StdResult<Option<UserInfo>> {
+    match USERS.may_load(storage, user)? {
+        Some(user_info) if user_info.last_update_block <= height => Ok(Some(user_info)),
+        Some(_) => USER_HISTORY
+            .prefix(user)
+            .range(
+                storage,
+                None,
+                Some(Bound::inclusive(height)),
+                Order::Descending,
+            )
+            .next()
+            .transpose()
+            .map(|item| item.map(|(_, user_info)| user_info)),
+        None => Ok(None),
+    }
+}
+
+/// Loads the slashing events recorded at `from_height` or later, ordered by height.
+pub fn load_slashing_events(
+    storage: &dyn Storage,
+    from_height: u64,
+    limit: Option<usize>,
+) -> StdResult<Vec<SlashingEvent>> {
+    SLASHING_EVENTS
+        .range(
+            storage,
+            Some(Bound::inclusive(from_height)),
+            None,
+            Order::Ascending,
+        )
+        .take(limit.unwrap_or(usize::MAX))
+        .map(|item| item.map(|(height, global_index)| (global_index, height)))
+        .collect()
+}
+
+/// Returns the height of the last slashing event, if any.
+pub fn last_slashing_event_height(storage: &dyn Storage) -> StdResult<Option<u64>> {
+    SLASHING_EVENTS
+        .keys(storage, None, None, Order::Descending)
+        .next()
+        .transpose()
+}
+
 pub fn is_allowed_to_pause(config: &Config, addr: &Addr) -> bool {
     addr == config.owner || addr == config.security_address
 }
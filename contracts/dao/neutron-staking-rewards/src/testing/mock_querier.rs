@@ -1,9 +1,16 @@
 use cosmwasm_std::testing::{MockApi, MockQuerier, MockStorage};
 use cosmwasm_std::{
-    coin, from_json, to_json_binary, Binary, Coin, ContractResult, Empty, OwnedDeps, Querier,
-    QuerierResult, QueryRequest, StdResult, SystemError, SystemResult, WasmQuery,
+    coin, from_json, to_json_binary, Addr, Binary, Coin, ContractResult, Empty, GrpcQuery,
+    OwnedDeps, Querier, QuerierResult, QueryRequest, StdResult, SystemError, SystemResult, Uint128,
+    WasmQuery,
 };
 use neutron_staking_info_proxy_common::msg::QueryMsg as InfoProxyQueryMsg;
+use neutron_staking_tracker_common::msg::QueryMsg as TrackerQueryMsg;
+use neutron_staking_tracker_common::types::{Delegation, Validator};
+use neutron_std::types::cosmos::staking::v1beta1::{
+    QueryValidatorRequest, QueryValidatorResponse, Validator as ChainValidator,
+};
+use prost::Message;
 use std::collections::HashMap;
 use std::marker::PhantomData;
 
@@ -22,6 +29,11 @@ pub fn mock_dependencies() -> OwnedDeps<MockStorage, MockApi, WasmMockQuerier> {
 pub struct WasmMockQuerier {
     base: MockQuerier,
     pub user_balances: HashMap<String, Vec<(u64, Coin)>>,
+    pub bonded_validators: Vec<Addr>,
+    pub delegations: Vec<Delegation>,
+    pub validators: HashMap<Addr, Validator>,
+    pub tracker_unavailable: bool,
+    pub chain_validators: Vec<String>,
 }
 
 impl WasmMockQuerier {
@@ -31,6 +43,36 @@ impl WasmMockQuerier {
             .or_default()
             .push((height, amount));
     }
+
+    pub fn update_delegations(
+        &mut self,
+        bonded_validators: Vec<Addr>,
+        delegations: Vec<Delegation>,
+    ) {
+        self.bonded_validators = bonded_validators;
+        self.delegations = delegations;
+        self.delegations.sort_by(|a, b| {
+            (&a.delegator_address, &a.validator_address)
+                .cmp(&(&b.delegator_address, &b.validator_address))
+        });
+    }
+
+    /// Sets the exchange rates of the validators. The validators that aren't set have one token
+    /// per share.
+    pub fn update_validators(&mut self, validators: Vec<Validator>) {
+        self.validators = validators
+            .into_iter()
+            .map(|validator| (validator.oper_address.clone(), validator))
+            .collect();
+    }
+
+    pub fn update_chain_validators(&mut self, validators: Vec<String>) {
+        self.chain_validators = validators;
+    }
+
+    pub fn update_balance(&mut self, address: String, balance: Vec<Coin>) {
+        self.base.bank.update_balance(address, balance);
+    }
 }
 
 impl Querier for WasmMockQuerier {
@@ -50,6 +92,8 @@ impl Querier for WasmMockQuerier {
 
 const STAKING_INFO_PROXY_CONTRACT: &str =
     "neutron1zfqexm2d6w4ddrl8h77lap2tjdvjd0r83lrjxalp29nq0zgkyfaq629dj9";
+const STAKING_TRACKER_CONTRACT: &str =
+    "neutron10tjyjhyrm4ej8dcuj3eemhr6gh078jm2s5wrrlqaqsxd637wmxzsx5rxq7";
 
 impl WasmMockQuerier {
     pub fn handle_query(&self, request: &QueryRequest<Empty>) -> QuerierResult {
@@ -74,9 +118,72 @@ impl WasmMockQuerier {
                         };
                         SystemResult::Ok(ContractResult::from(resp))
                     }
+                    STAKING_TRACKER_CONTRACT if self.tracker_unavailable => {
+                        SystemResult::Err(SystemError::NoSuchContract {
+                            addr: contract_addr.to_string(),
+                        })
+                    }
+                    STAKING_TRACKER_CONTRACT => {
+                        let q: TrackerQueryMsg = from_json(msg).unwrap();
+                        let resp: StdResult<Binary> = match q {
+                            TrackerQueryMsg::BondedValidators { .. } => {
+                                to_json_binary(&self.bonded_validators)
+                            }
+                            TrackerQueryMsg::ListDelegations { start_after, limit } => {
+                                let page: Vec<&Delegation> = self
+                                    .delegations
+                                    .iter()
+                                    .filter(|d| {
+                                        start_after.as_ref().map_or(
+                                            true,
+                                            |(delegator, validator)| {
+                                                (&d.delegator_address, &d.validator_address)
+                                                    > (delegator, validator)
+                                            },
+                                        )
+                                    })
+                                    .take(limit.map_or(usize::MAX, |l| l as usize))
+                                    .collect();
+                                to_json_binary(&page)
+                            }
+                            TrackerQueryMsg::Validator { validator, .. } => {
+                                let validator = Addr::unchecked(validator);
+                                let validator =
+                                    self.validators
+                                        .get(&validator)
+                                        .cloned()
+                                        .unwrap_or(Validator {
+                                            oper_address: validator,
+                                            total_tokens: Uint128::new(1),
+                                            total_shares: Uint128::new(1),
+                                        });
+                                to_json_binary(&Some(validator))
+                            }
+                            _ => unimplemented!(),
+                        };
+                        SystemResult::Ok(ContractResult::from(resp))
+                    }
                     _ => todo!(),
                 }
             }
+            QueryRequest::Grpc(GrpcQuery { path, data })
+                if path == "/cosmos.staking.v1beta1.Query/Validator" =>
+            {
+                let request: QueryValidatorRequest = Message::decode(&data[..]).unwrap();
+                if !self.chain_validators.contains(&request.validator_addr) {
+                    return SystemResult::Err(SystemError::InvalidRequest {
+                        error: format!("Validator not found: {}", request.validator_addr),
+                        request: Binary::default(),
+                    });
+                }
+                let response = QueryValidatorResponse {
+                    validator: Some(ChainValidator {
+                        operator_address: request.validator_addr,
+                        ..Default::default()
+                    }),
+                };
+                SystemResult::Ok(ContractResult::Ok(Binary::from(response.encode_to_vec())))
+            }
             _ => self.base.handle_query(request),
         }
     }
@@ -87,6 +194,11 @@ impl WasmMockQuerier {
         WasmMockQuerier {
             base,
             user_balances: Default::default(),
+            bonded_validators: vec![],
+            delegations: vec![],
+            validators: Default::default(),
+            tracker_unavailable: false,
+            chain_validators: vec![],
         }
     }
 }
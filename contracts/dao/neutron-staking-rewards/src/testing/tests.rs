@@ -1,15 +1,30 @@
-use crate::contract::{execute, instantiate, query};
-use crate::state::{assert_pause, CONFIG, PAUSED, STATE};
+use crate::contract::{execute, instantiate, migrate, query, reply, COMPOUND_REPLY_ID};
+use crate::state::{
+    assert_pause, CONFIG, PAUSED, STATE, TOTALS_BACKFILL, TOTAL_REWARD_WEIGHT, TOTAL_STAKE,
+};
 use crate::testing::mock_querier::mock_dependencies;
 use cosmwasm_std::testing::MockApi;
 use cosmwasm_std::{
     coin,
     testing::{message_info, mock_env},
-    BankMsg, CosmosMsg, Response, Uint128,
+    to_json_binary, BankMsg, CosmosMsg, Decimal, Reply, Response, SubMsg, SubMsgResult, Uint128,
+    WasmMsg,
 };
-use cosmwasm_std::{Addr, DepsMut, Env};
+use cosmwasm_std::{Addr, Deps, DepsMut, Env};
 use neutron_staking_rewards_common::error::ContractError;
-use neutron_staking_rewards_common::msg::{ExecuteMsg, InstantiateMsg, QueryMsg, RewardsResponse};
+use neutron_staking_rewards_common::msg::{
+    ExecuteMsg, GlobalIndexResponse, InstantiateMsg, MigrateMsg, QueryMsg, RewardRateResponse,
+    RewardsAccruedResponse, RewardsResponse, SlashingEventsResponse, SolvencyResponse,
+    ValidatorMultipliersResponse, VestedRewardsResponse, VestingEntriesResponse,
+};
+use neutron_staking_rewards_common::types::{
+    AutoCompoundSettings, ClaimSettings, CompoundResult, RateSegment, RewardCampaign,
+    ValidatorMultiplier,
+};
+use neutron_staking_tracker_common::types::{Delegation, Validator};
+use neutron_std::types::cosmos::authz::v1beta1::MsgExec;
+use neutron_std::types::cosmos::base::v1beta1::Coin as ProtoCoin;
+use neutron_std::types::cosmos::staking::v1beta1::MsgDelegate;
 
 // Helper to create a default instantiate message
 fn default_init_msg(api: MockApi) -> InstantiateMsg {
@@ -21,6 +36,12 @@ fn default_init_msg(api: MockApi) -> InstantiateMsg {
         staking_info_proxy: api.addr_make("proxy").into(),
         staking_denom: "untrn".to_string(),
         security_address: api.addr_make("security_address").into(),
+        reward_rate_schedule: None,
+        pause_accrual_on_insolvency: None,
+        staking_tracker: None,
+        claim_vesting_blocks: None,
+        forfeit_unvested_on_unstake: None,
+        campaign_min_funds: Some(vec![coin(100, "uatom")]),
     }
 }
 
@@ -135,6 +156,12 @@ fn test_update_config_unauthorized() {
         staking_info_proxy: None,
         staking_denom: None,
         security_address: None,
+        reward_rate_schedule: None,
+        pause_accrual_on_insolvency: None,
+        staking_tracker: None,
+        claim_vesting_blocks: None,
+        forfeit_unvested_on_unstake: None,
+        campaign_min_funds: None,
     };
     let info_non_owner = message_info(&non_owner, &[]);
     let err = execute(
@@ -881,6 +908,12 @@ fn test_slashing_no_effect() {
         blocks_per_year: 10_000,      // 10,000 blocks per year.
         staking_denom: "untrn".to_string(),
         security_address: deps.api.addr_make("security_address").into(),
+        reward_rate_schedule: None,
+        pause_accrual_on_insolvency: None,
+        staking_tracker: None,
+        claim_vesting_blocks: None,
+        forfeit_unvested_on_unstake: None,
+        campaign_min_funds: None,
     };
     let _res = instantiate(
         deps.as_mut(),
@@ -982,6 +1015,12 @@ fn test_slashing_single_event() {
         blocks_per_year: 10_000,      // e.g. 10,000 blocks per year
         staking_denom: "untrn".to_string(),
         security_address: deps.api.addr_make("security_address").into(),
+        reward_rate_schedule: None,
+        pause_accrual_on_insolvency: None,
+        staking_tracker: None,
+        claim_vesting_blocks: None,
+        forfeit_unvested_on_unstake: None,
+        campaign_min_funds: None,
     };
     let _res = instantiate(
         deps.as_mut(),
@@ -1125,6 +1164,12 @@ fn test_multiple_slashing_events() {
         blocks_per_year: 10_000,
         staking_denom: "untrn".to_string(),
         security_address: deps.api.addr_make("security_address").into(),
+        reward_rate_schedule: None,
+        pause_accrual_on_insolvency: None,
+        staking_tracker: None,
+        claim_vesting_blocks: None,
+        forfeit_unvested_on_unstake: None,
+        campaign_min_funds: None,
     };
     let _ = instantiate(
         deps.as_mut(),
@@ -1224,6 +1269,12 @@ fn test_update_and_slash_same_block() {
         blocks_per_year: 10_000,
         staking_denom: "untrn".to_string(),
         security_address: deps.api.addr_make("security_address").into(),
+        reward_rate_schedule: None,
+        pause_accrual_on_insolvency: None,
+        staking_tracker: None,
+        claim_vesting_blocks: None,
+        forfeit_unvested_on_unstake: None,
+        campaign_min_funds: None,
     };
     let _ = instantiate(
         deps.as_mut(),
@@ -1336,6 +1387,12 @@ fn test_two_users_with_slashing() {
         blocks_per_year: 100,
         staking_denom: "untrn".to_string(),
         security_address: deps.api.addr_make("security_address").into(),
+        reward_rate_schedule: None,
+        pause_accrual_on_insolvency: None,
+        staking_tracker: None,
+        claim_vesting_blocks: None,
+        forfeit_unvested_on_unstake: None,
+        campaign_min_funds: None,
     };
     let _ = instantiate(
         deps.as_mut(),
@@ -1446,6 +1503,12 @@ fn test_user_with_slashing_and_config_change() {
         blocks_per_year: 100,
         staking_denom: "untrn".to_string(),
         security_address: deps.api.addr_make("security_address").into(),
+        reward_rate_schedule: None,
+        pause_accrual_on_insolvency: None,
+        staking_tracker: None,
+        claim_vesting_blocks: None,
+        forfeit_unvested_on_unstake: None,
+        campaign_min_funds: None,
     };
     let _ = instantiate(
         deps.as_mut(),
@@ -1483,6 +1546,12 @@ fn test_user_with_slashing_and_config_change() {
         staking_info_proxy: None,
         staking_denom: None,
         security_address: None,
+        reward_rate_schedule: None,
+        pause_accrual_on_insolvency: None,
+        staking_tracker: None,
+        claim_vesting_blocks: None,
+        forfeit_unvested_on_unstake: None,
+        campaign_min_funds: None,
     };
     let _ = execute(
         deps.as_mut(),
@@ -1516,16 +1585,1706 @@ fn test_user_with_slashing_and_config_change() {
     assert_eq!(actual_user_claimed.u128() as f64, expected_user_claimed);
 }
 
-// helpers
-fn unwrap_send_amount_from_update_stake(res: Response) -> Uint128 {
-    res.messages
-        .into_iter()
-        .find_map(|m| match m.msg {
-            CosmosMsg::Bank(BankMsg::Send {
-                to_address: _,
-                amount,
-            }) => return Some(amount.first().unwrap().amount),
-            _ => None,
-        })
-        .unwrap()
+// - stake with user 500ntrn
+// - the reward rate schedule changes the rate twice between the stake update and the claim
+// - rewards should accrue at the rate in effect for every part of the interval
+#[test]
+fn test_reward_rate_schedule() {
+    let mut deps = mock_dependencies();
+    let mut env = mock_env();
+    env.block.height = 0;
+
+    let owner = deps.api.addr_make("owner");
+    let proxy = deps.api.addr_make("proxy");
+    let user = deps.api.addr_make("user");
+
+    let owner_info = message_info(&owner, &[]);
+    let user_info = message_info(&user, &[]);
+    let proxy_info = message_info(&proxy, &[]);
+
+    let user_stake = 500_000_000u128;
+
+    // Instantiate the contract.
+    let instantiate_msg = InstantiateMsg {
+        owner: owner.to_string(),
+        dao_address: deps.api.addr_make("dao").to_string(),
+        staking_info_proxy: proxy.to_string(),
+        annual_reward_rate_bps: 1000, // 10%
+        blocks_per_year: 100,
+        staking_denom: "untrn".to_string(),
+        security_address: deps.api.addr_make("security_address").into(),
+        reward_rate_schedule: Some(vec![
+            RateSegment {
+                start_height: 50,
+                rate_bps: 2000, // 20%
+            },
+            RateSegment {
+                start_height: 150,
+                rate_bps: 0,
+            },
+        ]),
+        pause_accrual_on_insolvency: None,
+        staking_tracker: None,
+        claim_vesting_blocks: None,
+        forfeit_unvested_on_unstake: None,
+        campaign_min_funds: None,
+    };
+    let _ = instantiate(
+        deps.as_mut(),
+        env.clone(),
+        owner_info.clone(),
+        instantiate_msg,
+    )
+    .unwrap();
+
+    for (height, expected_rate) in [(0, 1000), (49, 1000), (50, 2000), (149, 2000), (150, 0)] {
+        let bin = query(
+            deps.as_ref(),
+            env.clone(),
+            QueryMsg::RewardRate {
+                height: Some(height),
+            },
+        )
+        .unwrap();
+        let rate: RewardRateResponse = cosmwasm_std::from_json(bin).unwrap();
+        assert_eq!(rate.annual_reward_rate_bps, expected_rate);
+    }
+
+    deps.querier.update_stake(
+        user.to_string(),
+        env.block.height,
+        coin(user_stake, "untrn"),
+    );
+    let update_msg = ExecuteMsg::UpdateStake {
+        user: user.to_string(),
+    };
+    let _ = execute(deps.as_mut(), env.clone(), proxy_info.clone(), update_msg).unwrap();
+
+    // pass two years
+    env.block.height += 200;
+
+    let claim_msg = ExecuteMsg::ClaimRewards { to_address: None };
+    let res = execute(deps.as_mut(), env.clone(), user_info.clone(), claim_msg).unwrap();
+    let actual_user_claimed = unwrap_send_amount_from_update_stake(res);
+
+    // - 50 blocks with 10% annual rewards
+    // - 100 blocks with 20% annual rewards
+    // - 50 blocks with 0% annual rewards
+    let expected_user_claimed = user_stake / 20 + user_stake / 5;
+    assert_eq!(actual_user_claimed.u128(), expected_user_claimed);
+
+    // The schedule can't be changed retroactively.
+    let update_config_msg = ExecuteMsg::UpdateConfig {
+        owner: None,
+        annual_reward_rate_bps: None,
+        blocks_per_year: None,
+        staking_info_proxy: None,
+        staking_denom: None,
+        security_address: None,
+        reward_rate_schedule: Some(vec![RateSegment {
+            start_height: env.block.height,
+            rate_bps: 500,
+        }]),
+        pause_accrual_on_insolvency: None,
+        staking_tracker: None,
+        claim_vesting_blocks: None,
+        forfeit_unvested_on_unstake: None,
+        campaign_min_funds: None,
+    };
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        owner_info.clone(),
+        update_config_msg,
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::RateSegmentInThePast {
+            start_height: env.block.height
+        }
+    );
+
+    // The base rate update takes effect immediately, even though a segment has started.
+    let update_config_msg = ExecuteMsg::UpdateConfig {
+        owner: None,
+        annual_reward_rate_bps: Some(5000), // 50%
+        blocks_per_year: None,
+        staking_info_proxy: None,
+        staking_denom: None,
+        security_address: None,
+        reward_rate_schedule: None,
+        pause_accrual_on_insolvency: None,
+        staking_tracker: None,
+        claim_vesting_blocks: None,
+        forfeit_unvested_on_unstake: None,
+        campaign_min_funds: None,
+    };
+    let _ = execute(deps.as_mut(), env.clone(), owner_info, update_config_msg).unwrap();
+
+    // pass a year
+    env.block.height += 100;
+
+    let claim_msg = ExecuteMsg::ClaimRewards { to_address: None };
+    let res = execute(deps.as_mut(), env.clone(), user_info, claim_msg).unwrap();
+    let actual_user_claimed = unwrap_send_amount_from_update_stake(res);
+    assert_eq!(actual_user_claimed.u128(), user_stake / 2);
+
+    // The rates at past heights don't change with the config update
+    for (height, expected_rate) in [(0, 1000), (50, 2000), (199, 0), (201, 5000), (300, 5000)] {
+        let bin = query(
+            deps.as_ref(),
+            env.clone(),
+            QueryMsg::RewardRate {
+                height: Some(height),
+            },
+        )
+        .unwrap();
+        let rate: RewardRateResponse = cosmwasm_std::from_json(bin).unwrap();
+        assert_eq!(rate.annual_reward_rate_bps, expected_rate);
+    }
+}
+
+/// Tests the following scenario:
+///     1.  user1 stakes 300 and user2 stakes 100
+///     2.  Someone funds a campaign of 1000 uatom distributed over 100 blocks
+///     3.  50 blocks of the campaign pass, user1 claims 3/4 of the distributed rewards (375 uatom)
+///     4.  The campaign ends, user2 has 1/4 of the campaign rewards (250 uatom)
+///     5.  user1 claims the rest of their rewards (375 uatom)
+#[test]
+fn test_reward_campaign() {
+    let mut deps = mock_dependencies();
+    let mut env = mock_env();
+
+    let info = message_info(&deps.api.addr_make("owner"), &[]);
+    let msg = default_init_msg(deps.api);
+    let _ = instantiate(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+    let proxy_info = message_info(&deps.api.addr_make("proxy"), &[]);
+    let user1 = deps.api.addr_make("user1");
+    let user2 = deps.api.addr_make("user2");
+    for (user, stake) in [(user1.clone(), 300u128), (user2.clone(), 100u128)] {
+        deps.querier
+            .update_stake(user.to_string(), env.block.height, coin(stake, "untrn"));
+        let update_msg = ExecuteMsg::UpdateStake {
+            user: user.to_string(),
+        };
+        let _ = execute(deps.as_mut(), env.clone(), proxy_info.clone(), update_msg).unwrap();
+    }
+
+    // A campaign must be funded with exactly one coin and start in the future
+    let funder = deps.api.addr_make("funder");
+    let create_msg = ExecuteMsg::CreateCampaign {
+        start_height: env.block.height + 10,
+        end_height: env.block.height + 110,
+    };
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        message_info(&funder, &[]),
+        create_msg.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::InvalidCampaignFunds {});
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        message_info(&funder, &[coin(1000, "uatom")]),
+        ExecuteMsg::CreateCampaign {
+            start_height: env.block.height - 1,
+            end_height: env.block.height + 110,
+        },
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::InvalidCampaignHeights {
+            current_height: env.block.height
+        }
+    );
+    let _ = execute(
+        deps.as_mut(),
+        env.clone(),
+        message_info(&funder, &[coin(1000, "uatom")]),
+        create_msg,
+    )
+    .unwrap();
+
+    env.block.height += 60;
+    let claim_msg = ExecuteMsg::ClaimRewards { to_address: None };
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        message_info(&user1, &[]),
+        claim_msg.clone(),
+    )
+    .unwrap();
+    assert_eq!(
+        unwrap_send_amount_of_denom(res.clone(), "uatom"),
+        Uint128::new(375)
+    );
+    // staking rewards are sent along: 300 * 10% * 60 / 10_000 = 1.8
+    assert_eq!(unwrap_send_amount_of_denom(res, "untrn"), Uint128::new(1));
+
+    env.block.height += 100;
+    let bin = query(
+        deps.as_ref(),
+        env.clone(),
+        QueryMsg::Rewards {
+            user: user2.to_string(),
+        },
+    )
+    .unwrap();
+    let rewards: RewardsResponse = cosmwasm_std::from_json(bin).unwrap();
+    assert_eq!(rewards.campaign_rewards, vec![coin(250, "uatom")]);
+
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        message_info(&user1, &[]),
+        claim_msg,
+    )
+    .unwrap();
+    assert_eq!(unwrap_send_amount_of_denom(res, "uatom"), Uint128::new(375));
+
+    let bin = query(deps.as_ref(), env.clone(), QueryMsg::Campaign { id: 0 }).unwrap();
+    let campaign: RewardCampaign = cosmwasm_std::from_json(bin).unwrap();
+    assert_eq!(campaign.last_update_height, campaign.end_height);
+}
+
+/// Tests that the campaigns that have ended since the last update of a user are settled a few at a
+/// time.
+#[test]
+fn test_reward_campaign_settlement() {
+    let mut deps = mock_dependencies();
+    let mut env = mock_env();
+
+    let info = message_info(&deps.api.addr_make("owner"), &[]);
+    let msg = default_init_msg(deps.api);
+    let _ = instantiate(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+    let proxy_info = message_info(&deps.api.addr_make("proxy"), &[]);
+    let user = deps.api.addr_make("user");
+    deps.querier
+        .update_stake(user.to_string(), env.block.height, coin(100, "untrn"));
+    let _ = execute(
+        deps.as_mut(),
+        env.clone(),
+        proxy_info,
+        ExecuteMsg::UpdateStake {
+            user: user.to_string(),
+        },
+    )
+    .unwrap();
+
+    // 35 campaigns of 100 uatom each end without any update of the user
+    let funder = deps.api.addr_make("funder");
+    for campaigns in [30, 5] {
+        for _ in 0..campaigns {
+            let _ = execute(
+                deps.as_mut(),
+                env.clone(),
+                message_info(&funder, &[coin(100, "uatom")]),
+                ExecuteMsg::CreateCampaign {
+                    start_height: env.block.height + 1,
+                    end_height: env.block.height + 11,
+                },
+            )
+            .unwrap();
+        }
+        env.block.height += 20;
+    }
+
+    // Up to 30 ended campaigns are settled at a time
+    let claim_msg = ExecuteMsg::ClaimRewards { to_address: None };
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        message_info(&user, &[]),
+        claim_msg.clone(),
+    )
+    .unwrap();
+    assert_eq!(
+        unwrap_send_amount_of_denom(res, "uatom"),
+        Uint128::new(3_000)
+    );
+
+    // The rest are settled on the next update, and the query shows them in advance
+    env.block.height += 1;
+    let bin = query(
+        deps.as_ref(),
+        env.clone(),
+        QueryMsg::Rewards {
+            user: user.to_string(),
+        },
+    )
+    .unwrap();
+    let rewards: RewardsResponse = cosmwasm_std::from_json(bin).unwrap();
+    assert_eq!(rewards.campaign_rewards, vec![coin(500, "uatom")]);
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        message_info(&user, &[]),
+        claim_msg,
+    )
+    .unwrap();
+    assert_eq!(unwrap_send_amount_of_denom(res, "uatom"), Uint128::new(500));
+}
+
+/// Tests the following scenario:
+///     1.  Campaigns can't be funded below the minimum funds or in other denoms
+///     2.  Someone funds a campaign of 1000 uatom distributed over 100 blocks
+///     3.  50 blocks of the campaign pass without any stake, then the user stakes 100
+///     4.  The campaign ends, the funder reclaims the rewards of the first 50 blocks (500 uatom)
+///         and the user claims the rest (500 uatom)
+///     5.  At most 30 campaigns can be active at a time, ended campaigns don't count
+#[test]
+fn test_reward_campaign_funds() {
+    let mut deps = mock_dependencies();
+    let mut env = mock_env();
+
+    let info = message_info(&deps.api.addr_make("owner"), &[]);
+    let msg = default_init_msg(deps.api);
+    let _ = instantiate(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+    let funder = deps.api.addr_make("funder");
+    let create_msg = ExecuteMsg::CreateCampaign {
+        start_height: env.block.height + 10,
+        end_height: env.block.height + 110,
+    };
+    for funds in [coin(99, "uatom"), coin(1000, "ufoo")] {
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            message_info(&funder, &[funds]),
+            create_msg.clone(),
+        )
+        .unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::InsufficientCampaignFunds {
+                min_funds: "100uatom".to_string()
+            }
+        );
+    }
+    let _ = execute(
+        deps.as_mut(),
+        env.clone(),
+        message_info(&funder, &[coin(1000, "uatom")]),
+        create_msg,
+    )
+    .unwrap();
+
+    env.block.height += 60;
+    let user = deps.api.addr_make("user");
+    deps.querier
+        .update_stake(user.to_string(), env.block.height, coin(100, "untrn"));
+    let update_msg = ExecuteMsg::UpdateStake {
+        user: user.to_string(),
+    };
+    let proxy_info = message_info(&deps.api.addr_make("proxy"), &[]);
+    let _ = execute(deps.as_mut(), env.clone(), proxy_info, update_msg).unwrap();
+
+    env.block.height += 50;
+    let reclaim_msg = ExecuteMsg::ReclaimCampaignFunds { id: 0 };
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        message_info(&user, &[]),
+        reclaim_msg.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        message_info(&funder, &[]),
+        reclaim_msg.clone(),
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(BankMsg::Send {
+            to_address: funder.to_string(),
+            amount: vec![coin(500, "uatom")],
+        })]
+    );
+    // The reclaimed funds are sent only once
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        message_info(&funder, &[]),
+        reclaim_msg,
+    )
+    .unwrap();
+    assert!(res.messages.is_empty());
+
+    let claim_msg = ExecuteMsg::ClaimRewards { to_address: None };
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        message_info(&user, &[]),
+        claim_msg,
+    )
+    .unwrap();
+    assert_eq!(unwrap_send_amount_of_denom(res, "uatom"), Uint128::new(500));
+
+    let create_msg = ExecuteMsg::CreateCampaign {
+        start_height: env.block.height,
+        end_height: env.block.height + 100,
+    };
+    for _ in 0..30 {
+        let _ = execute(
+            deps.as_mut(),
+            env.clone(),
+            message_info(&funder, &[coin(100, "uatom")]),
+            create_msg.clone(),
+        )
+        .unwrap();
+    }
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        message_info(&funder, &[coin(100, "uatom")]),
+        create_msg,
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::TooManyActiveCampaigns { max: 30 });
+}
+
+/// Tests the following scenario:
+///     (Yearly blocks: 10_000, APR: 10%, accrual is paused on insolvency)
+///     1.  User stakes 1_000_000, the contract has 150 untrn, the runway is 15 blocks
+///     2.  20 blocks pass, the stake update pauses the accrual (200 untrn accrued)
+///     3.  20 blocks pass, nothing accrues
+///     4.  The contract is funded, the claim resumes the accrual and pays 200 untrn
+#[test]
+fn test_solvency() {
+    let mut deps = mock_dependencies();
+    let mut env = mock_env();
+
+    let info = message_info(&deps.api.addr_make("owner"), &[]);
+    let mut msg = default_init_msg(deps.api);
+    msg.pause_accrual_on_insolvency = Some(true);
+    let _ = instantiate(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+    let proxy_info = message_info(&deps.api.addr_make("proxy"), &[]);
+    let user = deps.api.addr_make("user");
+    deps.querier
+        .update_stake(user.to_string(), env.block.height, coin(1_000_000, "untrn"));
+    deps.querier
+        .update_balance(env.contract.address.to_string(), vec![coin(150, "untrn")]);
+    let update_msg = ExecuteMsg::UpdateStake {
+        user: user.to_string(),
+    };
+    let _ = execute(
+        deps.as_mut(),
+        env.clone(),
+        proxy_info.clone(),
+        update_msg.clone(),
+    )
+    .unwrap();
+
+    let query_solvency = |deps: Deps, env: Env| -> SolvencyResponse {
+        cosmwasm_std::from_json(query(deps, env, QueryMsg::Solvency {}).unwrap()).unwrap()
+    };
+    assert_eq!(
+        query_solvency(deps.as_ref(), env.clone()),
+        SolvencyResponse {
+            balance: Uint128::new(150),
+            liabilities: Uint128::zero(),
+            runway_blocks: Some(15),
+            accrual_paused: false,
+        }
+    );
+
+    env.block.height += 20;
+    let _ = execute(deps.as_mut(), env.clone(), proxy_info, update_msg).unwrap();
+    assert_eq!(
+        query_solvency(deps.as_ref(), env.clone()),
+        SolvencyResponse {
+            balance: Uint128::new(150),
+            liabilities: Uint128::new(200),
+            runway_blocks: None,
+            accrual_paused: true,
+        }
+    );
+
+    env.block.height += 20;
+    let bin = query(
+        deps.as_ref(),
+        env.clone(),
+        QueryMsg::Rewards {
+            user: user.to_string(),
+        },
+    )
+    .unwrap();
+    let rewards: RewardsResponse = cosmwasm_std::from_json(bin).unwrap();
+    assert_eq!(rewards.pending_rewards.amount, Uint128::new(200));
+
+    deps.querier
+        .update_balance(env.contract.address.to_string(), vec![coin(1_000, "untrn")]);
+    let claim_msg = ExecuteMsg::ClaimRewards { to_address: None };
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        message_info(&user, &[]),
+        claim_msg,
+    )
+    .unwrap();
+    assert_eq!(unwrap_send_amount_from_update_stake(res), Uint128::new(200));
+    let solvency = query_solvency(deps.as_ref(), env.clone());
+    assert!(!solvency.accrual_paused);
+    assert_eq!(solvency.liabilities, Uint128::zero());
+
+    env.block.height += 10;
+    assert_eq!(
+        query_solvency(deps.as_ref(), env.clone()).liabilities,
+        Uint128::new(100)
+    );
+}
+
+/// Tests that the totals are backfilled page by page after the migration from the version that
+/// didn't track them, and that they are kept on later migrations.
+#[test]
+fn test_migrate_backfill_totals() {
+    let mut deps = mock_dependencies();
+    let mut env = mock_env();
+
+    let owner_info = message_info(&deps.api.addr_make("owner"), &[]);
+    let msg = default_init_msg(deps.api);
+    let _ = instantiate(deps.as_mut(), env.clone(), owner_info.clone(), msg).unwrap();
+
+    let proxy_info = message_info(&deps.api.addr_make("proxy"), &[]);
+    for name in ["user1", "user2", "user3"] {
+        let user = deps.api.addr_make(name);
+        deps.querier
+            .update_stake(user.to_string(), env.block.height, coin(1_000_000, "untrn"));
+        let _ = execute(
+            deps.as_mut(),
+            env.clone(),
+            proxy_info.clone(),
+            ExecuteMsg::UpdateStake {
+                user: user.to_string(),
+            },
+        )
+        .unwrap();
+    }
+    env.block.height += 100;
+
+    // The version before the migration didn't track the totals
+    TOTAL_STAKE.remove(deps.as_mut().storage);
+    TOTAL_REWARD_WEIGHT.remove(deps.as_mut().storage);
+    let _ = migrate(deps.as_mut(), env.clone(), MigrateMsg {}).unwrap();
+    assert!(PAUSED.load(&deps.storage).unwrap());
+    assert_eq!(
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            owner_info.clone(),
+            ExecuteMsg::Unpause {}
+        )
+        .unwrap_err(),
+        ContractError::TotalsBackfillInProgress {}
+    );
+
+    let backfill_msg = ExecuteMsg::BackfillTotals { limit: Some(2) };
+    let anyone_info = message_info(&deps.api.addr_make("anyone"), &[]);
+    let _ = execute(
+        deps.as_mut(),
+        env.clone(),
+        anyone_info.clone(),
+        backfill_msg.clone(),
+    )
+    .unwrap();
+    assert!(TOTALS_BACKFILL.exists(&deps.storage));
+    assert!(PAUSED.load(&deps.storage).unwrap());
+    assert_eq!(
+        TOTAL_STAKE.load(&deps.storage).unwrap(),
+        Uint128::new(2_000_000)
+    );
+
+    let _ = execute(
+        deps.as_mut(),
+        env.clone(),
+        anyone_info.clone(),
+        backfill_msg.clone(),
+    )
+    .unwrap();
+    assert!(!TOTALS_BACKFILL.exists(&deps.storage));
+    assert!(!PAUSED.load(&deps.storage).unwrap());
+    assert_eq!(
+        TOTAL_STAKE.load(&deps.storage).unwrap(),
+        Uint128::new(3_000_000)
+    );
+    assert_eq!(
+        TOTAL_REWARD_WEIGHT.load(&deps.storage).unwrap(),
+        Uint128::new(3_000_000)
+    );
+    let solvency: SolvencyResponse =
+        cosmwasm_std::from_json(query(deps.as_ref(), env.clone(), QueryMsg::Solvency {}).unwrap())
+            .unwrap();
+    assert_eq!(solvency.liabilities, Uint128::new(3_000));
+    assert_eq!(
+        execute(deps.as_mut(), env.clone(), anyone_info, backfill_msg).unwrap_err(),
+        ContractError::NoTotalsBackfill {}
+    );
+
+    // The tracked totals are kept on later migrations
+    env.block.height += 10;
+    let _ = migrate(deps.as_mut(), env.clone(), MigrateMsg {}).unwrap();
+    assert!(!PAUSED.load(&deps.storage).unwrap());
+    let solvency: SolvencyResponse =
+        cosmwasm_std::from_json(query(deps.as_ref(), env.clone(), QueryMsg::Solvency {}).unwrap())
+            .unwrap();
+    assert_eq!(solvency.liabilities, Uint128::new(3_300));
+}
+
+/// Tests that slashing events are recorded once per height and returned page by page.
+#[test]
+fn test_slashing_events_pagination() {
+    let mut deps = mock_dependencies();
+    let mut env = mock_env();
+
+    let info = message_info(&deps.api.addr_make("owner"), &[]);
+    let msg = default_init_msg(deps.api);
+    let _ = instantiate(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+    let proxy_info = message_info(&deps.api.addr_make("proxy"), &[]);
+    let mut slashing_heights = vec![];
+    for _ in 0..3 {
+        env.block.height += 10;
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            proxy_info.clone(),
+            ExecuteMsg::Slashing {},
+        )
+        .unwrap();
+        assert!(res
+            .attributes
+            .iter()
+            .any(|attr| attr.key == "result" && attr.value == "acknowledged"));
+        slashing_heights.push(env.block.height);
+    }
+
+    // A second slashing event at the same height is ignored
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        proxy_info,
+        ExecuteMsg::Slashing {},
+    )
+    .unwrap();
+    assert!(res
+        .attributes
+        .iter()
+        .any(|attr| attr.key == "result" && attr.value == "ignored"));
+
+    let query_heights = |deps: Deps, from_height: u64| -> Vec<u64> {
+        let bin = query(
+            deps,
+            mock_env(),
+            QueryMsg::SlashingEvents {
+                from_height,
+                limit: Some(2),
+            },
+        )
+        .unwrap();
+        let resp: SlashingEventsResponse = cosmwasm_std::from_json(bin).unwrap();
+        resp.slashing_events
+            .into_iter()
+            .map(|(_, height)| height)
+            .collect()
+    };
+    let first_page = query_heights(deps.as_ref(), 0);
+    assert_eq!(first_page, slashing_heights[..2].to_vec());
+    let second_page = query_heights(deps.as_ref(), first_page.last().unwrap() + 1);
+    assert_eq!(second_page, slashing_heights[2..].to_vec());
+}
+
+/// Tests the following scenario:
+///     1.  User stakes and sets a reward recipient and an authorized claimer
+///     2.  Only the authorized claimer can claim on behalf of the user, to the recipient
+///     3.  The user's own claims go to the recipient, unless another address is specified
+#[test]
+fn test_claim_settings() {
+    let mut deps = mock_dependencies();
+    let mut env = mock_env();
+
+    let info = message_info(&deps.api.addr_make("owner"), &[]);
+    let msg = default_init_msg(deps.api);
+    let _ = instantiate(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+    let user = deps.api.addr_make("user");
+    let recipient = deps.api.addr_make("recipient");
+    let claimer = deps.api.addr_make("claimer");
+    let user_info = message_info(&user, &[]);
+
+    deps.querier
+        .update_stake(user.to_string(), env.block.height, coin(1_000_000, "untrn"));
+    let update_msg = ExecuteMsg::UpdateStake {
+        user: user.to_string(),
+    };
+    let proxy_info = message_info(&deps.api.addr_make("proxy"), &[]);
+    let _ = execute(deps.as_mut(), env.clone(), proxy_info, update_msg).unwrap();
+
+    let _ = execute(
+        deps.as_mut(),
+        env.clone(),
+        user_info.clone(),
+        ExecuteMsg::SetRewardRecipient {
+            recipient: Some(recipient.to_string()),
+        },
+    )
+    .unwrap();
+    let _ = execute(
+        deps.as_mut(),
+        env.clone(),
+        user_info.clone(),
+        ExecuteMsg::SetAuthorizedClaimer {
+            claimer: Some(claimer.to_string()),
+        },
+    )
+    .unwrap();
+    let bin = query(
+        deps.as_ref(),
+        env.clone(),
+        QueryMsg::ClaimSettings {
+            user: user.to_string(),
+        },
+    )
+    .unwrap();
+    let settings: ClaimSettings = cosmwasm_std::from_json(bin).unwrap();
+    assert_eq!(
+        settings,
+        ClaimSettings {
+            recipient: Some(recipient.clone()),
+            claimer: Some(claimer.clone()),
+        }
+    );
+
+    let claim_for_msg = ExecuteMsg::ClaimRewardsFor {
+        user: user.to_string(),
+    };
+    env.block.height += 100;
+    let someone_else_info = message_info(&deps.api.addr_make("someone_else"), &[]);
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        someone_else_info,
+        claim_for_msg.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        message_info(&claimer, &[]),
+        claim_for_msg,
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages[0].msg,
+        CosmosMsg::Bank(BankMsg::Send {
+            to_address: recipient.to_string(),
+            amount: vec![coin(1_000, "untrn")],
+        })
+    );
+
+    env.block.height += 100;
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        user_info.clone(),
+        ExecuteMsg::ClaimRewards { to_address: None },
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages[0].msg,
+        CosmosMsg::Bank(BankMsg::Send {
+            to_address: recipient.to_string(),
+            amount: vec![coin(1_000, "untrn")],
+        })
+    );
+
+    env.block.height += 100;
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        user_info.clone(),
+        ExecuteMsg::ClaimRewards {
+            to_address: Some(user.to_string()),
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages[0].msg,
+        CosmosMsg::Bank(BankMsg::Send {
+            to_address: user.to_string(),
+            amount: vec![coin(1_000, "untrn")],
+        })
+    );
+
+    // Resetting the recipient sends the rewards to the user again
+    let _ = execute(
+        deps.as_mut(),
+        env.clone(),
+        user_info.clone(),
+        ExecuteMsg::SetRewardRecipient { recipient: None },
+    )
+    .unwrap();
+    env.block.height += 100;
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        user_info,
+        ExecuteMsg::ClaimRewards { to_address: None },
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages[0].msg,
+        CosmosMsg::Bank(BankMsg::Send {
+            to_address: user.to_string(),
+            amount: vec![coin(1_000, "untrn")],
+        })
+    );
+}
+
+// helpers
+fn unwrap_send_amount_of_denom(res: Response, denom: &str) -> Uint128 {
+    res.messages
+        .into_iter()
+        .find_map(|m| match m.msg {
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: _,
+                amount,
+            }) => amount
+                .into_iter()
+                .find(|c| c.denom == denom)
+                .map(|c| c.amount),
+            _ => None,
+        })
+        .unwrap_or_default()
+}
+
+fn unwrap_send_amount_from_update_stake(res: Response) -> Uint128 {
+    res.messages
+        .into_iter()
+        .find_map(|m| match m.msg {
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: _,
+                amount,
+            }) => return Some(amount.first().unwrap().amount),
+            _ => None,
+        })
+        .unwrap()
+}
+
+/// Tests validator-weighted reward boosts:
+///     (Yearly blocks: 10_000, APR: 10%)
+///     1.  Validator A is boosted 2x, validator C 3x, but C is not bonded
+///     2.  User1 stakes 1_000_000 with A, user2 stakes 500_000 with B and delegates to C
+///     3.  After 10 blocks user1 has 200 untrn, user2 has 50 untrn
+///     4.  User3 delegates the same number of tokens to A, slashed by half, and B: 1.5x
+///     5.  The stake isn't boosted while the staking tracker can't be queried
+///     6.  Refreshing the multiplier of user3 boosts the stake again
+///     7.  The boost of A is removed, it applies to user3 on refresh and to user1 on the stake
+///         update, user1 gets 100 untrn in the next 10 blocks
+#[test]
+fn test_validator_multipliers() {
+    let mut deps = mock_dependencies();
+    let mut env = mock_env();
+
+    let owner_info = message_info(&deps.api.addr_make("owner"), &[]);
+    let mut msg = default_init_msg(deps.api);
+    msg.staking_tracker = Some(deps.api.addr_make("tracker").to_string());
+    let _ = instantiate(deps.as_mut(), env.clone(), owner_info.clone(), msg).unwrap();
+
+    let val_a = Addr::unchecked("neutronvaloper1a");
+    let val_b = Addr::unchecked("neutronvaloper1b");
+    let val_c = Addr::unchecked("neutronvaloper1c");
+    let set_msg = ExecuteMsg::SetValidatorMultipliers {
+        multipliers: vec![
+            ValidatorMultiplier {
+                validator: val_a.to_string(),
+                multiplier: Decimal::from_ratio(2u128, 1u128),
+            },
+            ValidatorMultiplier {
+                validator: val_c.to_string(),
+                multiplier: Decimal::from_ratio(3u128, 1u128),
+            },
+        ],
+    };
+    let user1_info = message_info(&deps.api.addr_make("user1"), &[]);
+    let err = execute(deps.as_mut(), env.clone(), user1_info, set_msg.clone()).unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        owner_info.clone(),
+        ExecuteMsg::SetValidatorMultipliers {
+            multipliers: vec![ValidatorMultiplier {
+                validator: val_a.to_string(),
+                multiplier: Decimal::from_ratio(11u128, 1u128),
+            }],
+        },
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::InvalidValidatorMultiplier {
+            multiplier: Decimal::from_ratio(11u128, 1u128)
+        }
+    );
+    let _ = execute(deps.as_mut(), env.clone(), owner_info.clone(), set_msg).unwrap();
+
+    let bin = query(
+        deps.as_ref(),
+        env.clone(),
+        QueryMsg::ValidatorMultipliers {
+            start_after: None,
+            limit: None,
+        },
+    )
+    .unwrap();
+    let resp: ValidatorMultipliersResponse = cosmwasm_std::from_json(bin).unwrap();
+    assert_eq!(
+        resp.multipliers
+            .iter()
+            .map(|m| m.validator.clone())
+            .collect::<Vec<_>>(),
+        vec![val_a.to_string(), val_c.to_string()]
+    );
+
+    let user1 = deps.api.addr_make("user1");
+    let user2 = deps.api.addr_make("user2");
+    let user3 = deps.api.addr_make("user3");
+    let delegation = |delegator: &Addr, validator: &Addr, shares: u128| Delegation {
+        delegator_address: delegator.clone(),
+        validator_address: validator.clone(),
+        shares: Uint128::new(shares),
+    };
+    deps.querier.update_delegations(
+        vec![val_a.clone(), val_b.clone()],
+        vec![
+            delegation(&user1, &val_a, 1_000_000),
+            delegation(&user2, &val_b, 500_000),
+            delegation(&user2, &val_c, 1_000_000),
+            delegation(&user3, &val_a, 1_000),
+            delegation(&user3, &val_b, 500),
+        ],
+    );
+    // validator A got slashed by half, so its shares are worth half a token
+    deps.querier.update_validators(vec![Validator {
+        oper_address: val_a.clone(),
+        total_tokens: Uint128::new(500_500),
+        total_shares: Uint128::new(1_001_000),
+    }]);
+    deps.querier.update_stake(
+        user1.to_string(),
+        env.block.height,
+        coin(1_000_000, "untrn"),
+    );
+    deps.querier
+        .update_stake(user2.to_string(), env.block.height, coin(500_000, "untrn"));
+
+    let proxy_info = message_info(&deps.api.addr_make("proxy"), &[]);
+    for user in [&user1, &user2] {
+        let update_msg = ExecuteMsg::UpdateStake {
+            user: user.to_string(),
+        };
+        let _ = execute(deps.as_mut(), env.clone(), proxy_info.clone(), update_msg).unwrap();
+    }
+
+    let query_rewards = |deps: Deps, env: Env, user: &Addr| -> RewardsResponse {
+        let msg = QueryMsg::Rewards {
+            user: user.to_string(),
+        };
+        cosmwasm_std::from_json(query(deps, env, msg).unwrap()).unwrap()
+    };
+    env.block.height += 10;
+    let rewards = query_rewards(deps.as_ref(), env.clone(), &user1);
+    assert_eq!(rewards.pending_rewards.amount, Uint128::new(200));
+    assert_eq!(rewards.reward_multiplier, Decimal::from_ratio(2u128, 1u128));
+    // The delegation to the unbonded validator C doesn't count
+    let rewards = query_rewards(deps.as_ref(), env.clone(), &user2);
+    assert_eq!(rewards.pending_rewards.amount, Uint128::new(50));
+    assert_eq!(rewards.reward_multiplier, Decimal::one());
+
+    let bin = query(deps.as_ref(), env.clone(), QueryMsg::Solvency {}).unwrap();
+    let solvency: SolvencyResponse = cosmwasm_std::from_json(bin).unwrap();
+    assert_eq!(solvency.liabilities, Uint128::new(250));
+
+    // The multiplier is weighted by the delegated tokens rather than the shares
+    deps.querier
+        .update_stake(user3.to_string(), env.block.height, coin(1_000, "untrn"));
+    let update_msg = ExecuteMsg::UpdateStake {
+        user: user3.to_string(),
+    };
+    let _ = execute(
+        deps.as_mut(),
+        env.clone(),
+        proxy_info.clone(),
+        update_msg.clone(),
+    )
+    .unwrap();
+    let rewards = query_rewards(deps.as_ref(), env.clone(), &user3);
+    assert_eq!(rewards.reward_multiplier, Decimal::from_ratio(3u128, 2u128));
+
+    // The stake isn't boosted while the staking tracker can't be queried
+    deps.querier.tracker_unavailable = true;
+    let _ = execute(deps.as_mut(), env.clone(), proxy_info.clone(), update_msg).unwrap();
+    let rewards = query_rewards(deps.as_ref(), env.clone(), &user3);
+    assert_eq!(rewards.reward_multiplier, Decimal::one());
+    deps.querier.tracker_unavailable = false;
+
+    // Anyone can refresh the multipliers of up to 30 users
+    let anyone_info = message_info(&deps.api.addr_make("anyone"), &[]);
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        anyone_info.clone(),
+        ExecuteMsg::RefreshRewardMultipliers {
+            users: vec![user3.to_string(); 31],
+        },
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::TooManyRefreshUsers { max: 30 });
+    let refresh_msg = ExecuteMsg::RefreshRewardMultipliers {
+        users: vec![user3.to_string(), deps.api.addr_make("unknown").to_string()],
+    };
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        anyone_info.clone(),
+        refresh_msg.clone(),
+    )
+    .unwrap();
+    assert!(res
+        .attributes
+        .iter()
+        .any(|attr| attr.key == "count" && attr.value == "1"));
+    let rewards = query_rewards(deps.as_ref(), env.clone(), &user3);
+    assert_eq!(rewards.reward_multiplier, Decimal::from_ratio(3u128, 2u128));
+
+    // Removing the boost takes effect on the user's next stake update or refresh
+    let _ = execute(
+        deps.as_mut(),
+        env.clone(),
+        owner_info,
+        ExecuteMsg::SetValidatorMultipliers {
+            multipliers: vec![ValidatorMultiplier {
+                validator: val_a.to_string(),
+                multiplier: Decimal::one(),
+            }],
+        },
+    )
+    .unwrap();
+    let rewards = query_rewards(deps.as_ref(), env.clone(), &user3);
+    assert_eq!(rewards.reward_multiplier, Decimal::from_ratio(3u128, 2u128));
+    let _ = execute(deps.as_mut(), env.clone(), anyone_info, refresh_msg).unwrap();
+    let rewards = query_rewards(deps.as_ref(), env.clone(), &user3);
+    assert_eq!(rewards.reward_multiplier, Decimal::one());
+
+    let update_msg = ExecuteMsg::UpdateStake {
+        user: user1.to_string(),
+    };
+    let _ = execute(deps.as_mut(), env.clone(), proxy_info, update_msg).unwrap();
+
+    env.block.height += 10;
+    let rewards = query_rewards(deps.as_ref(), env.clone(), &user1);
+    assert_eq!(rewards.pending_rewards.amount, Uint128::new(300));
+    assert_eq!(rewards.reward_multiplier, Decimal::one());
+}
+
+/// Tests linear vesting of claimed rewards:
+///     (Yearly blocks: 10_000, APR: 10%, vesting over 100 blocks, unstaking forfeits)
+///     1.  User stakes 1_000_000 and claims 1_000 untrn after 100 blocks, nothing is sent
+///     2.  50 blocks later the user withdraws 500 untrn
+///     3.  The user unstakes half, half of the 500 locked untrn is forfeited to the DAO
+///     4.  50 blocks later the user withdraws the remaining 250 untrn
+#[test]
+fn test_claim_vesting() {
+    let mut deps = mock_dependencies();
+    let mut env = mock_env();
+
+    let info = message_info(&deps.api.addr_make("owner"), &[]);
+    let mut msg = default_init_msg(deps.api);
+    msg.claim_vesting_blocks = Some(100);
+    msg.forfeit_unvested_on_unstake = Some(true);
+    let _ = instantiate(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+    let proxy_info = message_info(&deps.api.addr_make("proxy"), &[]);
+    let user = deps.api.addr_make("user");
+    let user_info = message_info(&user, &[]);
+    deps.querier.update_balance(
+        env.contract.address.to_string(),
+        vec![coin(10_000, "untrn")],
+    );
+    deps.querier
+        .update_stake(user.to_string(), env.block.height, coin(1_000_000, "untrn"));
+    let update_msg = ExecuteMsg::UpdateStake {
+        user: user.to_string(),
+    };
+    let _ = execute(
+        deps.as_mut(),
+        env.clone(),
+        proxy_info.clone(),
+        update_msg.clone(),
+    )
+    .unwrap();
+
+    let query_vested = |deps: Deps, env: Env| -> VestedRewardsResponse {
+        let msg = QueryMsg::VestedRewards {
+            user: user.to_string(),
+        };
+        cosmwasm_std::from_json(query(deps, env, msg).unwrap()).unwrap()
+    };
+
+    env.block.height += 100;
+    let claim_msg = ExecuteMsg::ClaimRewards { to_address: None };
+    let res = execute(deps.as_mut(), env.clone(), user_info.clone(), claim_msg).unwrap();
+    assert!(res.messages.is_empty());
+    let vested = query_vested(deps.as_ref(), env.clone());
+    assert_eq!(vested.withdrawable.amount, Uint128::zero());
+    assert_eq!(vested.locked.amount, Uint128::new(1_000));
+
+    env.block.height += 50;
+    let withdraw_msg = ExecuteMsg::WithdrawVested { to_address: None };
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        user_info.clone(),
+        withdraw_msg.clone(),
+    )
+    .unwrap();
+    assert_eq!(unwrap_send_amount_from_update_stake(res), Uint128::new(500));
+    // 500 untrn are locked, and 500 untrn accrued since the claim
+    let bin = query(deps.as_ref(), env.clone(), QueryMsg::Solvency {}).unwrap();
+    let solvency: SolvencyResponse = cosmwasm_std::from_json(bin).unwrap();
+    assert_eq!(solvency.liabilities, Uint128::new(1_000));
+
+    deps.querier
+        .update_stake(user.to_string(), env.block.height, coin(500_000, "untrn"));
+    let res = execute(deps.as_mut(), env.clone(), proxy_info, update_msg).unwrap();
+    assert_eq!(
+        res.messages[0].msg,
+        CosmosMsg::Bank(BankMsg::Send {
+            to_address: deps.api.addr_make("dao").to_string(),
+            amount: vec![coin(250, "untrn")],
+        })
+    );
+    assert_eq!(
+        query_vested(deps.as_ref(), env.clone()).locked.amount,
+        Uint128::new(250)
+    );
+
+    env.block.height += 50;
+    let res = execute(deps.as_mut(), env.clone(), user_info, withdraw_msg).unwrap();
+    assert_eq!(unwrap_send_amount_from_update_stake(res), Uint128::new(250));
+    let bin = query(
+        deps.as_ref(),
+        env.clone(),
+        QueryMsg::VestingEntries {
+            user: user.to_string(),
+            start_after: None,
+            limit: None,
+        },
+    )
+    .unwrap();
+    let entries: VestingEntriesResponse = cosmwasm_std::from_json(bin).unwrap();
+    assert!(entries.entries.is_empty());
+    // The 500 untrn accrued before the unstake, and 250 untrn after it
+    let bin = query(deps.as_ref(), env.clone(), QueryMsg::Solvency {}).unwrap();
+    let solvency: SolvencyResponse = cosmwasm_std::from_json(bin).unwrap();
+    assert_eq!(solvency.liabilities, Uint128::new(750));
+}
+
+/// Tests historical reward queries:
+///     (Yearly blocks: 10_000, APR: 10%)
+///     1.  User stakes 1_000_000, earning 10 untrn per block
+///     2.  The user claims after 100 blocks, and doubles the stake after 150 blocks
+///     3.  After 200 blocks the rewards accrued over any period are known
+#[test]
+fn test_reward_history() {
+    let mut deps = mock_dependencies();
+    let mut env = mock_env();
+    let start_height = env.block.height;
+
+    let info = message_info(&deps.api.addr_make("owner"), &[]);
+    let msg = default_init_msg(deps.api);
+    let _ = instantiate(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+    let proxy_info = message_info(&deps.api.addr_make("proxy"), &[]);
+    let user = deps.api.addr_make("user");
+    deps.querier
+        .update_stake(user.to_string(), env.block.height, coin(1_000_000, "untrn"));
+    let update_msg = ExecuteMsg::UpdateStake {
+        user: user.to_string(),
+    };
+    let _ = execute(
+        deps.as_mut(),
+        env.clone(),
+        proxy_info.clone(),
+        update_msg.clone(),
+    )
+    .unwrap();
+
+    env.block.height += 100;
+    let claim_msg = ExecuteMsg::ClaimRewards { to_address: None };
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        message_info(&user, &[]),
+        claim_msg,
+    )
+    .unwrap();
+    assert_eq!(
+        unwrap_send_amount_from_update_stake(res),
+        Uint128::new(1_000)
+    );
+
+    env.block.height += 50;
+    deps.querier
+        .update_stake(user.to_string(), env.block.height, coin(2_000_000, "untrn"));
+    let _ = execute(deps.as_mut(), env.clone(), proxy_info, update_msg).unwrap();
+
+    env.block.height += 50;
+    let query_global_index = |deps: Deps, env: Env, height: u64| -> Decimal {
+        let msg = QueryMsg::GlobalIndexAtHeight { height };
+        let resp: GlobalIndexResponse =
+            cosmwasm_std::from_json(query(deps, env, msg).unwrap()).unwrap();
+        resp.global_reward_index
+    };
+    assert_eq!(
+        query_global_index(deps.as_ref(), env.clone(), start_height + 50),
+        Decimal::from_ratio(5u128, 10_000u128)
+    );
+    assert_eq!(
+        query_global_index(deps.as_ref(), env.clone(), start_height + 200),
+        Decimal::from_ratio(20u128, 10_000u128)
+    );
+    let err = query(
+        deps.as_ref(),
+        env.clone(),
+        QueryMsg::GlobalIndexAtHeight {
+            height: start_height - 1,
+        },
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::HistoryNotAvailable {
+            height: start_height - 1
+        }
+    );
+
+    let query_rewards_accrued = |deps: Deps, env: Env, from: u64, to: u64| -> Uint128 {
+        let msg = QueryMsg::RewardsAccrued {
+            user: user.to_string(),
+            from_height: start_height + from,
+            to_height: start_height + to,
+        };
+        let resp: RewardsAccruedResponse =
+            cosmwasm_std::from_json(query(deps, env, msg).unwrap()).unwrap();
+        resp.rewards.amount
+    };
+    assert_eq!(
+        query_rewards_accrued(deps.as_ref(), env.clone(), 0, 100),
+        Uint128::new(1_000)
+    );
+    // 100 blocks at 10 untrn per block and 25 blocks at 20 untrn per block
+    assert_eq!(
+        query_rewards_accrued(deps.as_ref(), env.clone(), 50, 175),
+        Uint128::new(1_500)
+    );
+    assert_eq!(
+        query_rewards_accrued(deps.as_ref(), env.clone(), 100, 200),
+        Uint128::new(1_500)
+    );
+
+    let err = query(
+        deps.as_ref(),
+        env.clone(),
+        QueryMsg::RewardsAccrued {
+            user: user.to_string(),
+            from_height: start_height + 10,
+            to_height: start_height,
+        },
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::InvalidHeightRange {});
+}
+
+/// Tests that rewards can't be compounded while claimed rewards vest, since they would bypass
+/// vesting.
+#[test]
+fn test_auto_compound_with_vesting() {
+    let mut deps = mock_dependencies();
+    let env = mock_env();
+
+    let owner_info = message_info(&deps.api.addr_make("owner"), &[]);
+    let msg = default_init_msg(deps.api);
+    let _ = instantiate(deps.as_mut(), env.clone(), owner_info.clone(), msg).unwrap();
+
+    let user = deps.api.addr_make("user");
+    let _ = execute(
+        deps.as_mut(),
+        env.clone(),
+        message_info(&user, &[]),
+        ExecuteMsg::SetAutoCompound {
+            enabled: true,
+            validator: None,
+        },
+    )
+    .unwrap();
+
+    let _ = execute(
+        deps.as_mut(),
+        env.clone(),
+        owner_info,
+        ExecuteMsg::UpdateConfig {
+            owner: None,
+            annual_reward_rate_bps: None,
+            blocks_per_year: None,
+            staking_info_proxy: None,
+            staking_denom: None,
+            security_address: None,
+            reward_rate_schedule: None,
+            pause_accrual_on_insolvency: None,
+            staking_tracker: None,
+            claim_vesting_blocks: Some(100),
+            forfeit_unvested_on_unstake: None,
+            campaign_min_funds: None,
+        },
+    )
+    .unwrap();
+
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        message_info(&deps.api.addr_make("anyone"), &[]),
+        ExecuteMsg::Compound {
+            users: vec![user.to_string()],
+        },
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::AutoCompoundWithVesting {});
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        message_info(&user, &[]),
+        ExecuteMsg::SetAutoCompound {
+            enabled: true,
+            validator: None,
+        },
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::AutoCompoundWithVesting {});
+
+    // Users can still opt out
+    let _ = execute(
+        deps.as_mut(),
+        env.clone(),
+        message_info(&user, &[]),
+        ExecuteMsg::SetAutoCompound {
+            enabled: false,
+            validator: None,
+        },
+    )
+    .unwrap();
+}
+
+/// Tests auto-compounding:
+///     (Yearly blocks: 10_000, APR: 10%)
+///     1.  User1 stakes 1_000_000 and delegates to two bonded validators and an unbonded one,
+///         user2 stakes 1_000_000 without delegations known to the staking tracker
+///     2.  After 100 blocks, user1's 1_000 untrn are delegated pro-rata to the bonded validators
+///     3.  User2's rewards stay pending since there is no validator to delegate to
+///     4.  A failed delegation is recorded in the reply, and user1's rewards are pending again
+#[test]
+fn test_auto_compound() {
+    let mut deps = mock_dependencies();
+    let mut env = mock_env();
+
+    let info = message_info(&deps.api.addr_make("owner"), &[]);
+    let mut msg = default_init_msg(deps.api);
+    msg.staking_tracker = Some(deps.api.addr_make("tracker").to_string());
+    let _ = instantiate(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+    let proxy_info = message_info(&deps.api.addr_make("proxy"), &[]);
+    let anyone_info = message_info(&deps.api.addr_make("anyone"), &[]);
+    let user1 = deps.api.addr_make("user1");
+    let user2 = deps.api.addr_make("user2");
+    let val_a = Addr::unchecked("neutronvaloper1a");
+    let val_b = Addr::unchecked("neutronvaloper1b");
+    let val_c = Addr::unchecked("neutronvaloper1c");
+    let delegation = |validator: &Addr, shares: u128| Delegation {
+        delegator_address: user1.clone(),
+        validator_address: validator.clone(),
+        shares: Uint128::new(shares),
+    };
+    deps.querier.update_delegations(
+        vec![val_a.clone(), val_b.clone()],
+        vec![
+            delegation(&val_a, 3_000),
+            delegation(&val_b, 1_000),
+            delegation(&val_c, 1_000),
+        ],
+    );
+    deps.querier.update_chain_validators(vec![
+        val_a.to_string(),
+        val_b.to_string(),
+        val_c.to_string(),
+    ]);
+    deps.querier.update_balance(
+        env.contract.address.to_string(),
+        vec![coin(10_000, "untrn")],
+    );
+    for user in [&user1, &user2] {
+        deps.querier
+            .update_stake(user.to_string(), env.block.height, coin(1_000_000, "untrn"));
+        let _ = execute(
+            deps.as_mut(),
+            env.clone(),
+            proxy_info.clone(),
+            ExecuteMsg::UpdateStake {
+                user: user.to_string(),
+            },
+        )
+        .unwrap();
+        let _ = execute(
+            deps.as_mut(),
+            env.clone(),
+            message_info(user, &[]),
+            ExecuteMsg::SetAutoCompound {
+                enabled: true,
+                validator: None,
+            },
+        )
+        .unwrap();
+    }
+
+    let query_auto_compound = |deps: Deps, user: &Addr| -> Option<AutoCompoundSettings> {
+        let msg = QueryMsg::AutoCompound {
+            user: user.to_string(),
+        };
+        cosmwasm_std::from_json(query(deps, mock_env(), msg).unwrap()).unwrap()
+    };
+
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        anyone_info.clone(),
+        ExecuteMsg::Compound {
+            users: vec![user1.to_string(); 31],
+        },
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::TooManyCompoundUsers { max: 30 });
+
+    env.block.height += 100;
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        anyone_info.clone(),
+        ExecuteMsg::Compound {
+            users: vec![user1.to_string(), user2.to_string()],
+        },
+    )
+    .unwrap();
+    let delegate_msg = ExecuteMsg::DelegateCompounded {
+        user: user1.to_string(),
+        delegations: vec![
+            (val_a.to_string(), Uint128::new(750)),
+            (val_b.to_string(), Uint128::new(250)),
+        ],
+    };
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::reply_on_error(
+            WasmMsg::Execute {
+                contract_addr: env.contract.address.to_string(),
+                msg: to_json_binary(&delegate_msg).unwrap(),
+                funds: vec![],
+            },
+            COMPOUND_REPLY_ID
+        )
+        .with_payload(to_json_binary(&(user1.clone(), Uint128::new(1_000))).unwrap())]
+    );
+
+    // The rewards are sent and delegated in a call to the contract itself
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        anyone_info.clone(),
+        delegate_msg.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        message_info(&env.contract.address, &[]),
+        delegate_msg,
+    )
+    .unwrap();
+    assert_eq!(res.messages.len(), 2);
+    assert_eq!(
+        res.messages[0].msg,
+        CosmosMsg::Bank(BankMsg::Send {
+            to_address: user1.to_string(),
+            amount: vec![coin(1_000, "untrn")],
+        })
+    );
+    let delegate = |validator: &Addr, amount: u128| {
+        MsgDelegate {
+            delegator_address: user1.to_string(),
+            validator_address: validator.to_string(),
+            amount: Some(ProtoCoin {
+                denom: "untrn".to_string(),
+                amount: amount.to_string(),
+            }),
+        }
+        .to_any()
+    };
+    let msg_exec = MsgExec {
+        grantee: env.contract.address.to_string(),
+        msgs: vec![delegate(&val_a, 750), delegate(&val_b, 250)],
+    };
+    assert_eq!(res.messages[1].msg, CosmosMsg::from(msg_exec));
+
+    let bin = query(
+        deps.as_ref(),
+        env.clone(),
+        QueryMsg::Rewards {
+            user: user1.to_string(),
+        },
+    )
+    .unwrap();
+    let rewards: RewardsResponse = cosmwasm_std::from_json(bin).unwrap();
+    assert_eq!(rewards.pending_rewards, coin(0, "untrn"));
+    let settings = query_auto_compound(deps.as_ref(), &user1).unwrap();
+    assert_eq!(
+        settings.last_result,
+        Some(CompoundResult {
+            height: env.block.height,
+            amount: Uint128::new(1_000),
+            error: None,
+        })
+    );
+
+    // User2's rewards are left pending
+    let bin = query(
+        deps.as_ref(),
+        env.clone(),
+        QueryMsg::Rewards {
+            user: user2.to_string(),
+        },
+    )
+    .unwrap();
+    let rewards: RewardsResponse = cosmwasm_std::from_json(bin).unwrap();
+    assert_eq!(rewards.pending_rewards, coin(1_000, "untrn"));
+    let settings = query_auto_compound(deps.as_ref(), &user2).unwrap();
+    assert_eq!(
+        settings.last_result.unwrap().error,
+        Some("no bonded validator to delegate to".to_string())
+    );
+
+    // The failed delegation is recorded, and the rewards are pending again since the transfer is
+    // reverted along with it
+    let msg = Reply {
+        id: COMPOUND_REPLY_ID,
+        payload: to_json_binary(&(user1.clone(), Uint128::new(1_000))).unwrap(),
+        gas_used: 0,
+        result: SubMsgResult::Err("authorization not found".to_string()),
+    };
+    let _ = reply(deps.as_mut(), env.clone(), msg).unwrap();
+    let settings = query_auto_compound(deps.as_ref(), &user1).unwrap();
+    assert_eq!(
+        settings.last_result.unwrap().error,
+        Some("authorization not found".to_string())
+    );
+    let bin = query(
+        deps.as_ref(),
+        env.clone(),
+        QueryMsg::Rewards {
+            user: user1.to_string(),
+        },
+    )
+    .unwrap();
+    let rewards: RewardsResponse = cosmwasm_std::from_json(bin).unwrap();
+    assert_eq!(rewards.pending_rewards, coin(1_000, "untrn"));
+    let bin = query(deps.as_ref(), env.clone(), QueryMsg::Solvency {}).unwrap();
+    let solvency: SolvencyResponse = cosmwasm_std::from_json(bin).unwrap();
+    assert_eq!(solvency.liabilities, Uint128::new(2_000));
+
+    // Only the validators known to the staking module can be chosen
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        message_info(&user2, &[]),
+        ExecuteMsg::SetAutoCompound {
+            enabled: true,
+            validator: Some("neutronvaloper1unknown".to_string()),
+        },
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::ValidatorNotFound {
+            address: "neutronvaloper1unknown".to_string()
+        }
+    );
+
+    // User2 chooses a validator and the rewards of both users are compounded
+    let _ = execute(
+        deps.as_mut(),
+        env.clone(),
+        message_info(&user2, &[]),
+        ExecuteMsg::SetAutoCompound {
+            enabled: true,
+            validator: Some(val_c.to_string()),
+        },
+    )
+    .unwrap();
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        anyone_info.clone(),
+        ExecuteMsg::Compound {
+            users: vec![user1.to_string(), user2.to_string()],
+        },
+    )
+    .unwrap();
+    assert_eq!(res.messages.len(), 2);
+    assert_eq!(
+        res.messages[1].payload,
+        to_json_binary(&(user2.clone(), Uint128::new(1_000))).unwrap()
+    );
+
+    // Opting out removes the settings
+    let _ = execute(
+        deps.as_mut(),
+        env.clone(),
+        message_info(&user1, &[]),
+        ExecuteMsg::SetAutoCompound {
+            enabled: false,
+            validator: None,
+        },
+    )
+    .unwrap();
+    assert_eq!(query_auto_compound(deps.as_ref(), &user1), None);
+    env.block.height += 100;
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        anyone_info,
+        ExecuteMsg::Compound {
+            users: vec![user1.to_string()],
+        },
+    )
+    .unwrap();
+    assert!(res.messages.is_empty());
 }
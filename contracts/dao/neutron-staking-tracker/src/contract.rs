@@ -1,20 +1,29 @@
-use crate::state::{BONDED_VALIDATORS_SET, CONFIG, DELEGATIONS, VALIDATORS};
+use crate::state::{
+    BONDED_VALIDATORS_SET, CONFIG, DELEGATIONS, DELEGATOR_VALIDATORS, IMPORT_SEALED,
+    UNBONDING_DELEGATORS, UNBONDING_ENTRIES, UNBONDING_SYNC_CURSORS, UNBONDING_SYNC_LIMIT,
+    VALIDATORS, VALIDATOR_DELEGATORS, VALIDATOR_DELEGATORS_BACKFILL_LIMIT,
+    VALIDATOR_DELEGATORS_PAGE_LIMIT, VALIDATOR_SLASHES, VALIDATOR_STATUSES,
+};
 use neutron_staking_info_proxy_common::msg::ExecuteMsg as StakingInfoProxyExecuteMsg;
 use neutron_staking_tracker_common::error::ContractError;
 use neutron_staking_tracker_common::msg::{
-    ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg, SudoMsg,
+    DelegationTokens, ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg, SudoMsg,
+    ValidatorAtHeightResponse, ValidatorDelegatorsResponse,
+};
+use neutron_staking_tracker_common::types::{
+    BondStatus, Config, Delegation, UnbondingEntry, Validator, ValidatorSlash, ValidatorStatus,
 };
-use neutron_staking_tracker_common::types::{Config, Delegation, Validator};
-use std::collections::HashSet;
+use std::collections::{BTreeSet, HashSet};
 
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
 use cosmwasm_std::{
-    to_json_binary, Addr, Binary, Decimal256, Deps, DepsMut, Env, MessageInfo, Order, Reply,
-    Response, StdResult, SubMsg, Uint128, Uint256, WasmMsg,
+    to_json_binary, Addr, Binary, Decimal256, Deps, DepsMut, Empty, Env, MessageInfo, Order, Reply,
+    Response, StdResult, SubMsg, Timestamp, Uint128, Uint256, WasmMsg,
 };
 use cw2::set_contract_version;
 use cw_storage_plus::Bound;
+use neutron_std::shim::Timestamp as ProtoTimestamp;
 use neutron_std::types::cosmos::staking::v1beta1::{QueryValidatorResponse, StakingQuerier};
 use std::str::FromStr;
 
@@ -70,7 +79,7 @@ pub fn instantiate(
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn execute(
     deps: DepsMut,
-    _env: Env,
+    env: Env,
     info: MessageInfo,
     msg: ExecuteMsg,
 ) -> Result<Response, ContractError> {
@@ -88,6 +97,17 @@ pub fn execute(
             description,
             staking_proxy_info_contract_address,
         ),
+        ExecuteMsg::ImportState {
+            validators,
+            delegations,
+        } => execute_import_state(deps, env, info, validators, delegations),
+        ExecuteMsg::SealImport {} => execute_seal_import(deps, info),
+        ExecuteMsg::BackfillValidatorDelegators { start_after, limit } => {
+            execute_backfill_validator_delegators(deps, start_after, limit)
+        }
+        ExecuteMsg::SyncUnbondingEntries { validator, limit } => {
+            execute_sync_unbonding_entries(deps, env, validator, limit)
+        }
     }
 }
 
@@ -135,6 +155,199 @@ pub fn execute_update_config(
         ))
 }
 
+pub fn execute_import_state(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    validators: Vec<Validator>,
+    delegations: Vec<Delegation>,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+    if IMPORT_SEALED.may_load(deps.storage)?.unwrap_or_default() {
+        return Err(ContractError::ImportSealed {});
+    }
+
+    let mut resp = Response::new()
+        .add_attribute("action", "import_state")
+        .add_attribute("validators", validators.len().to_string())
+        .add_attribute("delegations", delegations.len().to_string());
+
+    let previous_bonded_validators = BONDED_VALIDATORS_SET.load(deps.storage)?;
+    let mut bonded_validators = previous_bonded_validators.clone();
+    for validator in validators {
+        let valoper_address = validator.oper_address.to_string();
+        let validator_data = StakingQuerier::new(&deps.querier)
+            .validator(valoper_address.clone())?
+            .validator
+            .ok_or_else(|| ContractError::ValidatorNotFound {
+                address: valoper_address.clone(),
+            })?;
+        if validator.total_tokens != Uint128::from_str(&validator_data.tokens)?
+            || validator.total_shares != Uint128::from_str(&validator_data.delegator_shares)?
+        {
+            return Err(ContractError::ImportedValidatorMismatch {
+                address: valoper_address,
+            });
+        }
+
+        let bond_status = to_bond_status(validator_data.status);
+        bonded_validators.retain(|a| a != &valoper_address);
+        if bond_status == BondStatus::Bonded {
+            bonded_validators.push(valoper_address);
+        }
+
+        VALIDATORS.save(
+            deps.storage,
+            &validator.oper_address,
+            &validator,
+            env.block.height,
+        )?;
+        VALIDATOR_STATUSES.save(
+            deps.storage,
+            &validator.oper_address,
+            &ValidatorStatus {
+                unbonding_completion_time: match bond_status {
+                    BondStatus::Unbonding => validator_data.unbonding_time.map(to_timestamp),
+                    _ => None,
+                },
+                bond_status,
+                jailed: validator_data.jailed,
+            },
+            env.block.height,
+        )?;
+    }
+
+    if bonded_validators != previous_bonded_validators {
+        BONDED_VALIDATORS_SET.save(deps.storage, &bonded_validators, env.block.height)?;
+
+        // Call proxy info to notify about change of stake
+        resp = with_slashing_event(
+            resp,
+            deps.as_ref(),
+            REPLY_ON_AFTER_VALIDATOR_BONDED_ERROR_STAKING_PROXY_ID,
+        )?;
+    }
+
+    let mut delegators = BTreeSet::new();
+    for delegation in delegations {
+        let delegator = deps
+            .api
+            .addr_validate(delegation.delegator_address.as_str())?;
+        let validator = delegation.validator_address.clone();
+        if !VALIDATORS.has(deps.storage, &validator) {
+            return Err(ContractError::ImportedDelegationValidatorNotFound {
+                delegator: delegator.to_string(),
+                validator: validator.to_string(),
+            });
+        }
+
+        // A missing delegation can't match the imported one
+        let shares = StakingQuerier::new(&deps.querier)
+            .delegation(delegator.to_string(), validator.to_string())
+            .ok()
+            .and_then(|resp| resp.delegation_response)
+            .and_then(|resp| resp.delegation)
+            .map(|del| Uint128::from_str(&del.shares))
+            .transpose()?;
+        if shares != Some(delegation.shares) {
+            return Err(ContractError::ImportedDelegationMismatch {
+                delegator: delegator.to_string(),
+                validator: validator.to_string(),
+            });
+        }
+
+        DELEGATIONS.save(
+            deps.storage,
+            (&delegator, &validator),
+            &delegation,
+            env.block.height,
+        )?;
+        VALIDATOR_DELEGATORS.save(deps.storage, (&validator, &delegator), &Empty {})?;
+        DELEGATOR_VALIDATORS.save(deps.storage, (&delegator, &validator), &Empty {})?;
+        update_unbonding_entries(deps.branch(), &env, &delegator, &validator, Uint128::zero())?;
+
+        delegators.insert(delegator);
+    }
+
+    // Call proxy info to notify about change of stake
+    for delegator in delegators {
+        resp = with_update_stake_msg(
+            resp,
+            deps.as_ref(),
+            &delegator,
+            REPLY_ON_AFTER_DELEGATION_MODIFIED_ERROR_STAKING_PROXY_ID,
+        )?;
+    }
+
+    Ok(resp)
+}
+
+pub fn execute_seal_import(deps: DepsMut, info: MessageInfo) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+    if IMPORT_SEALED.may_load(deps.storage)?.unwrap_or_default() {
+        return Err(ContractError::ImportSealed {});
+    }
+
+    IMPORT_SEALED.save(deps.storage, &true)?;
+
+    Ok(Response::new().add_attribute("action", "seal_import"))
+}
+
+/// Indexes the delegations by validator and by delegator from the delegation history, so that the
+/// delegations removed before the indexes were added are listed at earlier heights as well.
+pub fn execute_backfill_validator_delegators(
+    deps: DepsMut,
+    start_after: Option<(String, String)>,
+    limit: Option<u32>,
+) -> Result<Response, ContractError> {
+    let start_after = start_after
+        .map(|(delegator, validator)| -> StdResult<_> {
+            Ok((
+                deps.api.addr_validate(&delegator)?,
+                Addr::unchecked(validator),
+            ))
+        })
+        .transpose()?;
+    let limit = limit
+        .unwrap_or(VALIDATOR_DELEGATORS_BACKFILL_LIMIT)
+        .min(VALIDATOR_DELEGATORS_BACKFILL_LIMIT) as usize;
+
+    // Every delegation that has ever been stored has a history entry, and the entries of a
+    // delegation are skipped at once by starting after its last possible height
+    let range_min = start_after
+        .as_ref()
+        .map(|(delegator, validator)| Bound::exclusive(((delegator, validator), u64::MAX)));
+    let mut delegations = DELEGATIONS
+        .changelog()
+        .keys(deps.storage, range_min, None, Order::Ascending)
+        .take(limit + 1)
+        .collect::<StdResult<Vec<((Addr, Addr), u64)>>>()?;
+    let backfill_pending = delegations.len() > limit;
+    delegations.truncate(limit);
+
+    for ((delegator, validator), _) in delegations.iter() {
+        VALIDATOR_DELEGATORS.save(deps.storage, (validator, delegator), &Empty {})?;
+        DELEGATOR_VALIDATORS.save(deps.storage, (delegator, validator), &Empty {})?;
+    }
+
+    let mut resp = Response::new()
+        .add_attribute("action", "backfill_validator_delegators")
+        .add_attribute("backfill_pending", backfill_pending.to_string());
+    if let Some(((delegator, validator), _)) = delegations.last().filter(|_| backfill_pending) {
+        resp = resp
+            .add_attribute("last_delegator", delegator.to_string())
+            .add_attribute("last_validator", validator.to_string());
+    }
+
+    Ok(resp)
+}
+
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn sudo(deps: DepsMut, env: Env, msg: SudoMsg) -> Result<Response, ContractError> {
     match msg {
@@ -194,6 +407,16 @@ pub(crate) fn after_validator_created(
         &new_validator,
         env.block.height,
     )?;
+    VALIDATOR_STATUSES.save(
+        deps.storage,
+        &valoper_addr,
+        &ValidatorStatus {
+            bond_status: to_bond_status(validator_data.status),
+            jailed: validator_data.jailed,
+            unbonding_completion_time: None,
+        },
+        env.block.height,
+    )?;
 
     Ok(Response::new()
         .add_attribute("action", "after_validator_created")
@@ -212,6 +435,17 @@ pub(crate) fn after_validator_removed(
     // Remove validator
     VALIDATORS.remove(deps.storage, &validator_addr, env.block.height)?;
 
+    // The staking module only removes unbonded validators, the last status is kept
+    let mut status = VALIDATOR_STATUSES
+        .may_load(deps.storage, &validator_addr)?
+        .unwrap_or(ValidatorStatus {
+            bond_status: BondStatus::Unbonded,
+            jailed: false,
+            unbonding_completion_time: None,
+        });
+    status.bond_status = BondStatus::Unbonded;
+    VALIDATOR_STATUSES.save(deps.storage, &validator_addr, &status, env.block.height)?;
+
     Ok(Response::new()
         .add_attribute("action", "after_validator_removed")
         .add_attribute("valoper_address", valoper_address))
@@ -262,6 +496,16 @@ pub(crate) fn after_validator_bonded(
 
     bonded_validators.push(valoper_addr.to_string());
     BONDED_VALIDATORS_SET.save(deps.storage, &bonded_validators, env.block.height)?;
+    VALIDATOR_STATUSES.save(
+        deps.storage,
+        &valoper_addr,
+        &ValidatorStatus {
+            bond_status: BondStatus::Bonded,
+            jailed: validator_info.jailed,
+            unbonding_completion_time: None,
+        },
+        env.block.height,
+    )?;
 
     // Call proxy info to notify about change of stake
     let resp = with_slashing_event(
@@ -278,16 +522,31 @@ pub(crate) fn after_validator_bonded(
 }
 
 pub fn before_validator_slashed(
-    deps: DepsMut,
+    mut deps: DepsMut,
     env: Env,
     valoper_address: String,
-    _slashing_fraction: Decimal256,
+    slashing_fraction: Decimal256,
     tokens_to_burn: Uint128,
 ) -> Result<Response, ContractError> {
     let validator_addr = Addr::unchecked(&valoper_address);
 
     let mut validator = VALIDATORS.load(deps.storage, &validator_addr)?;
 
+    // Log the slashing, a validator can get slashed more than once at a height
+    VALIDATOR_SLASHES.update(
+        deps.storage,
+        (&validator_addr, env.block.height),
+        |slashes| -> StdResult<_> {
+            let mut slashes = slashes.unwrap_or_default();
+            slashes.push(ValidatorSlash {
+                height: env.block.height,
+                fraction: slashing_fraction,
+                tokens_burned: tokens_to_burn,
+            });
+            Ok(slashes)
+        },
+    )?;
+
     let mut resp = Response::new();
 
     // Defensive check to avoid excessive callback calls.
@@ -306,12 +565,86 @@ pub fn before_validator_slashed(
         )?;
     }
 
+    // The staking module slashes the unbonding entries before calling the hook, so the entries
+    // of the validator are updated from the chain. An earlier pending update starts over.
+    let sync_pending = sync_unbonding_entries(
+        deps.branch(),
+        &env,
+        &validator_addr,
+        None,
+        UNBONDING_SYNC_LIMIT,
+    )?;
+
     Ok(resp
         .add_attribute("action", "before_validator_slashed")
         .add_attribute("valoper_address", valoper_address)
         .add_attribute("total_tokens", validator.total_tokens.to_string())
         .add_attribute("total_shares", validator.total_shares.to_string())
-        .add_attribute("tokens_to_burn", tokens_to_burn.to_string()))
+        .add_attribute("tokens_to_burn", tokens_to_burn.to_string())
+        .add_attribute("unbonding_sync_pending", sync_pending.to_string()))
+}
+
+/// Continues updating the unbonding entries of a slashed validator from the cursor left by the
+/// slashing hook.
+pub fn execute_sync_unbonding_entries(
+    mut deps: DepsMut,
+    env: Env,
+    valoper_address: String,
+    limit: Option<u32>,
+) -> Result<Response, ContractError> {
+    let validator_addr = Addr::unchecked(&valoper_address);
+    let cursor = UNBONDING_SYNC_CURSORS.may_load(deps.storage, &validator_addr)?;
+
+    let mut sync_pending = false;
+    if let Some(cursor) = cursor {
+        let limit = limit
+            .unwrap_or(UNBONDING_SYNC_LIMIT)
+            .min(UNBONDING_SYNC_LIMIT);
+        sync_pending =
+            sync_unbonding_entries(deps.branch(), &env, &validator_addr, Some(cursor), limit)?;
+    }
+
+    Ok(Response::new()
+        .add_attribute("action", "sync_unbonding_entries")
+        .add_attribute("valoper_address", valoper_address)
+        .add_attribute("unbonding_sync_pending", sync_pending.to_string()))
+}
+
+/// Updates the unbonding entries of up to `limit` delegators of the validator after
+/// `start_after` from the chain, and saves the cursor if there are more left. Returns whether
+/// there are.
+fn sync_unbonding_entries(
+    mut deps: DepsMut,
+    env: &Env,
+    validator: &Addr,
+    start_after: Option<Addr>,
+    limit: u32,
+) -> Result<bool, ContractError> {
+    let mut unbonding_delegators = UNBONDING_DELEGATORS
+        .prefix(validator)
+        .keys(
+            deps.storage,
+            start_after.as_ref().map(Bound::exclusive),
+            None,
+            Order::Ascending,
+        )
+        .take(limit as usize + 1)
+        .collect::<StdResult<Vec<Addr>>>()?;
+    let sync_pending = unbonding_delegators.len() > limit as usize;
+    unbonding_delegators.truncate(limit as usize);
+
+    for delegator in unbonding_delegators.iter() {
+        update_unbonding_entries(deps.branch(), env, delegator, validator, Uint128::zero())?;
+    }
+
+    match unbonding_delegators.last() {
+        Some(last_delegator) if sync_pending => {
+            UNBONDING_SYNC_CURSORS.save(deps.storage, validator, last_delegator)?
+        }
+        _ => UNBONDING_SYNC_CURSORS.remove(deps.storage, validator),
+    }
+
+    Ok(sync_pending)
 }
 
 pub(crate) fn after_validator_begin_unbonding(
@@ -337,6 +670,25 @@ pub(crate) fn after_validator_begin_unbonding(
     bonded_vals.retain(|a| a != &valoper_addr.to_string());
     BONDED_VALIDATORS_SET.save(deps.storage, &bonded_vals, env.block.height)?;
 
+    // Query the validator from the chain to know whether it's jailed and when it's unbonded
+    // (fallback to not jailed if query fails)
+    let validator_data = StakingQuerier::new(&deps.querier)
+        .validator(valoper_address.clone())
+        .ok()
+        .and_then(|resp| resp.validator);
+    VALIDATOR_STATUSES.save(
+        deps.storage,
+        &valoper_addr,
+        &ValidatorStatus {
+            bond_status: BondStatus::Unbonding,
+            jailed: validator_data.as_ref().is_some_and(|v| v.jailed),
+            unbonding_completion_time: validator_data
+                .and_then(|v| v.unbonding_time)
+                .map(to_timestamp),
+        },
+        env.block.height,
+    )?;
+
     // Call proxy info to notify about change of stake
     resp = with_slashing_event(
         resp,
@@ -348,7 +700,7 @@ pub(crate) fn after_validator_begin_unbonding(
 }
 
 pub(crate) fn after_delegation_modified(
-    deps: DepsMut,
+    mut deps: DepsMut,
     env: Env,
     delegator_address: String,
     valoper_address: String,
@@ -393,6 +745,8 @@ pub(crate) fn after_delegation_modified(
         },
         env.block.height,
     )?;
+    VALIDATOR_DELEGATORS.save(deps.storage, (&valoper_addr, &delegator), &Empty {})?;
+    DELEGATOR_VALIDATORS.save(deps.storage, (&delegator, &valoper_addr), &Empty {})?;
 
     // Load the validator by `valoper_address`.
     let mut validator = VALIDATORS.load(deps.storage, &valoper_addr)?;
@@ -411,10 +765,11 @@ pub(crate) fn after_delegation_modified(
     //   - This is because the validator's `total_tokens` and `total_shares` are **already updated**
     //     by the time this function is executed.
     //
+    let mut undelegated_tokens = Uint128::zero();
     if current_shares < previous_shares {
         let undelegated_shares = previous_shares - current_shares;
 
-        validator.remove_del_shares(undelegated_shares)?;
+        undelegated_tokens = validator.remove_del_shares(undelegated_shares)?;
     } else {
         // Query validator data to get the latest **total tokens & shares**
         let validator_data = querier
@@ -431,6 +786,14 @@ pub(crate) fn after_delegation_modified(
     // Save updated validator state
     VALIDATORS.save(deps.storage, &valoper_addr, &validator, env.block.height)?;
 
+    update_unbonding_entries(
+        deps.branch(),
+        &env,
+        &delegator,
+        &valoper_addr,
+        undelegated_tokens,
+    )?;
+
     // Update user stake information for rewards.
     // Note that we want to call this even for delegations in unbonded validators.
     // Not doing so will mess up replay unprocessed slashing events in rewards.
@@ -451,7 +814,7 @@ pub(crate) fn after_delegation_modified(
 }
 
 pub(crate) fn before_delegation_removed(
-    deps: DepsMut,
+    mut deps: DepsMut,
     env: Env,
     delegator_address: String,
     valoper_address: String,
@@ -467,13 +830,21 @@ pub(crate) fn before_delegation_removed(
     // Load the validator by `valoper_address`.
     let mut validator = VALIDATORS.load(deps.storage, &valoper_addr)?;
     // Since it's `before_delegation_removed`, we can safely remove all shares from validator
-    validator.remove_del_shares(shares)?;
+    let undelegated_tokens = validator.remove_del_shares(shares)?;
 
     // Save the updated validator state
     VALIDATORS.save(deps.storage, &valoper_addr, &validator, env.block.height)?;
 
     DELEGATIONS.remove(deps.storage, (&delegator, &valoper_addr), env.block.height)?;
 
+    update_unbonding_entries(
+        deps.branch(),
+        &env,
+        &delegator,
+        &valoper_addr,
+        undelegated_tokens,
+    )?;
+
     let resp = with_update_stake_msg(
         Response::new(),
         deps.as_ref(),
@@ -487,6 +858,154 @@ pub(crate) fn before_delegation_removed(
         .add_attribute("valoper_address", valoper_addr.to_string()))
 }
 
+/// Updates the tracked unbonding entries of the delegator from the staking module, which drops
+/// completed entries and applies slashing, and adds an entry for `undelegated_tokens` of the
+/// delegation to `validator`.
+///
+/// The staking module creates the entry of an undelegation after calling the hooks, so the new
+/// entry is derived from the undelegated tokens and the validator's status, and merged with an
+/// entry of the same creation height and completion time like the staking module does. The
+/// entries of an unbonding validator complete with the validator's unbonding, and the tokens
+/// undelegated from an unbonded validator are returned right away without an entry.
+/// Redelegations can't be told apart from undelegations in the source validator hooks, so they
+/// are recorded as unbonding entries at first. The staking module calls the hooks of the
+/// destination validator in the same message, before either entry is stored, so the entries of
+/// every validator are re-synced there, which drops the entry of the redelegation.
+fn update_unbonding_entries(
+    deps: DepsMut,
+    env: &Env,
+    delegator: &Addr,
+    validator: &Addr,
+    undelegated_tokens: Uint128,
+) -> Result<(), ContractError> {
+    let querier = StakingQuerier::new(&deps.querier);
+
+    let previous_entries = UNBONDING_ENTRIES
+        .may_load(deps.storage, delegator)?
+        .unwrap_or_default();
+    let mut validators: BTreeSet<Addr> = previous_entries
+        .iter()
+        .map(|entry| entry.validator_address.clone())
+        .collect();
+    validators.insert(validator.clone());
+
+    let mut entries = vec![];
+    for entry_validator in validators.iter() {
+        // Query **current unbonding entries** from the chain (fallback to none if query fails)
+        let chain_entries = querier
+            .unbonding_delegation(delegator.to_string(), entry_validator.to_string())
+            .ok() // If query fails, treat as no unbonding delegation
+            .and_then(|resp| resp.unbond)
+            .map(|unbond| unbond.entries)
+            .unwrap_or_default();
+        for entry in chain_entries {
+            entries.push(UnbondingEntry {
+                validator_address: entry_validator.clone(),
+                creation_height: entry.creation_height as u64,
+                completion_time: to_timestamp(entry.completion_time.unwrap_or_default()),
+                initial_balance: Uint128::from_str(&entry.initial_balance)?,
+                balance: Uint128::from_str(&entry.balance)?,
+            });
+        }
+    }
+    // The entries that have matured are completed at the end of the block
+    entries.retain(|entry| entry.completion_time > env.block.time);
+
+    let new_entry = if undelegated_tokens.is_zero() {
+        None
+    } else {
+        // Query the validator from the chain to know its status (fallback to bonded if query
+        // fails)
+        let validator_data = querier
+            .validator(validator.to_string())
+            .ok()
+            .and_then(|resp| resp.validator);
+        match validator_data {
+            Some(data) if to_bond_status(data.status) == BondStatus::Unbonded => None,
+            Some(data) if to_bond_status(data.status) == BondStatus::Unbonding => Some((
+                data.unbonding_height as u64,
+                to_timestamp(
+                    data.unbonding_time
+                        .ok_or(ContractError::UnbondingTimeNotFound {})?,
+                ),
+            )),
+            _ => {
+                let unbonding_time = querier
+                    .params()?
+                    .params
+                    .and_then(|params| params.unbonding_time)
+                    .ok_or(ContractError::UnbondingTimeNotFound {})?;
+                Some((
+                    env.block.height,
+                    env.block
+                        .time
+                        .plus_seconds(unbonding_time.seconds as u64)
+                        .plus_nanos(unbonding_time.nanos as u64),
+                ))
+            }
+        }
+    };
+    // The entry of an unbonding validator that has matured is completed at the end of the block
+    if let Some((creation_height, completion_time)) =
+        new_entry.filter(|(_, completion_time)| *completion_time > env.block.time)
+    {
+        match entries.iter_mut().find(|entry| {
+            entry.validator_address == *validator
+                && entry.creation_height == creation_height
+                && entry.completion_time == completion_time
+        }) {
+            Some(entry) => {
+                entry.initial_balance = entry.initial_balance.checked_add(undelegated_tokens)?;
+                entry.balance = entry.balance.checked_add(undelegated_tokens)?;
+            }
+            None => entries.push(UnbondingEntry {
+                validator_address: validator.clone(),
+                creation_height,
+                completion_time,
+                initial_balance: undelegated_tokens,
+                balance: undelegated_tokens,
+            }),
+        }
+    }
+    entries.sort_by_key(|entry| entry.creation_height);
+
+    if entries == previous_entries {
+        return Ok(());
+    }
+
+    for entry_validator in validators.iter() {
+        if entries
+            .iter()
+            .any(|entry| entry.validator_address == *entry_validator)
+        {
+            UNBONDING_DELEGATORS.save(deps.storage, (entry_validator, delegator), &Empty {})?;
+        } else {
+            UNBONDING_DELEGATORS.remove(deps.storage, (entry_validator, delegator));
+        }
+    }
+
+    if entries.is_empty() {
+        UNBONDING_ENTRIES.remove(deps.storage, delegator, env.block.height)?;
+    } else {
+        UNBONDING_ENTRIES.save(deps.storage, delegator, &entries, env.block.height)?;
+    }
+
+    Ok(())
+}
+
+/// Converts the bond status of a validator in the staking module.
+fn to_bond_status(status: i32) -> BondStatus {
+    match status {
+        3 => BondStatus::Bonded,
+        2 => BondStatus::Unbonding,
+        _ => BondStatus::Unbonded,
+    }
+}
+
+fn to_timestamp(timestamp: ProtoTimestamp) -> Timestamp {
+    Timestamp::from_seconds(timestamp.seconds as u64).plus_nanos(timestamp.nanos as u64)
+}
+
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
     match msg {
@@ -497,12 +1016,56 @@ pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
             to_json_binary(&query_total_stake_at_height(deps, env, height)?)
         }
         QueryMsg::Config {} => to_json_binary(&CONFIG.load(deps.storage)?),
+        QueryMsg::ImportSealed {} => {
+            to_json_binary(&IMPORT_SEALED.may_load(deps.storage)?.unwrap_or_default())
+        }
+        QueryMsg::BondedValidators { height } => {
+            to_json_binary(&query_bonded_validators(deps, env, height)?)
+        }
+        QueryMsg::Validator { validator, height } => {
+            to_json_binary(&query_validator(deps, env, validator, height)?)
+        }
         QueryMsg::ListValidators { start_after, limit } => {
             to_json_binary(&query_list_validators(deps, start_after, limit)?)
         }
         QueryMsg::ListDelegations { start_after, limit } => {
             to_json_binary(&query_list_delegations(deps, start_after, limit)?)
         }
+        QueryMsg::ValidatorAtHeight { validator, height } => {
+            to_json_binary(&query_validator_at_height(deps, env, validator, height)?)
+        }
+        QueryMsg::ValidatorSlashes {
+            validator,
+            start_after,
+            limit,
+        } => to_json_binary(&query_validator_slashes(
+            deps,
+            validator,
+            start_after,
+            limit,
+        )?),
+        QueryMsg::DelegatorDelegations { delegator, height } => {
+            to_json_binary(&query_delegator_delegations(deps, env, delegator, height)?)
+        }
+        QueryMsg::ValidatorDelegators {
+            validator,
+            height,
+            start_after,
+            limit,
+        } => to_json_binary(&query_validator_delegators(
+            deps,
+            env,
+            validator,
+            height,
+            start_after,
+            limit,
+        )?),
+        QueryMsg::PendingUnbondingSyncs { start_after, limit } => {
+            to_json_binary(&query_pending_unbonding_syncs(deps, start_after, limit)?)
+        }
+        QueryMsg::UnbondingEntries { delegator, height } => {
+            to_json_binary(&query_unbonding_entries(deps, env, delegator, height)?)
+        }
     }
 }
 
@@ -591,6 +1154,30 @@ pub fn query_total_stake_at_height(
     Ok(total_stake)
 }
 
+pub fn query_bonded_validators(deps: Deps, env: Env, height: Option<u64>) -> StdResult<Vec<Addr>> {
+    let height = height.unwrap_or(env.block.height);
+
+    let bonded_vals = BONDED_VALIDATORS_SET
+        .may_load_at_height(deps.storage, height)?
+        .unwrap_or_default()
+        .into_iter()
+        .map(Addr::unchecked)
+        .collect();
+
+    Ok(bonded_vals)
+}
+
+pub fn query_validator(
+    deps: Deps,
+    env: Env,
+    validator: String,
+    height: Option<u64>,
+) -> StdResult<Option<Validator>> {
+    let height = height.unwrap_or(env.block.height);
+
+    VALIDATORS.may_load_at_height(deps.storage, &Addr::unchecked(validator), height)
+}
+
 fn query_list_validators(
     deps: Deps,
     start_after: Option<Addr>,
@@ -640,11 +1227,244 @@ pub fn query_list_delegations(
     Ok(page)
 }
 
+/// Converts delegation shares to tokens at the validator's exchange rate.
+///
+/// Uses `Uint256` for intermediate calculations to avoid overflow.
+fn shares_to_tokens(shares: Uint128, validator: &Validator) -> StdResult<Uint128> {
+    if validator.total_shares.is_zero() {
+        return Ok(Uint128::zero());
+    }
+
+    let tokens_256 = Uint256::from(shares)
+        .checked_mul(Uint256::from(validator.total_tokens))?
+        .checked_div(Uint256::from(validator.total_shares))?;
+
+    Ok(Uint128::try_from(tokens_256)?)
+}
+
+pub fn query_delegator_delegations(
+    deps: Deps,
+    env: Env,
+    delegator: String,
+    height: Option<u64>,
+) -> StdResult<Vec<DelegationTokens>> {
+    let height = height.unwrap_or(env.block.height);
+    let delegator = deps.api.addr_validate(&delegator)?;
+
+    // SnapshotMap cannot iterate over previous heights, so the delegation to every validator the
+    // delegator has ever delegated to is loaded at the height
+    let validators = DELEGATOR_VALIDATORS
+        .prefix(&delegator)
+        .keys(deps.storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<Addr>>>()?;
+
+    let mut delegations = vec![];
+    for validator_addr in validators {
+        let Some(delegation) = DELEGATIONS
+            .may_load_at_height(deps.storage, (&delegator, &validator_addr), height)?
+            .filter(|delegation| !delegation.shares.is_zero())
+        else {
+            continue;
+        };
+        let tokens = match VALIDATORS.may_load_at_height(deps.storage, &validator_addr, height)? {
+            Some(validator) => shares_to_tokens(delegation.shares, &validator)?,
+            None => Uint128::zero(),
+        };
+
+        delegations.push(DelegationTokens {
+            delegator: delegator.clone(),
+            validator: validator_addr,
+            shares: delegation.shares,
+            tokens,
+        });
+    }
+
+    Ok(delegations)
+}
+
+pub fn query_validator_delegators(
+    deps: Deps,
+    env: Env,
+    validator: String,
+    height: Option<u64>,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<ValidatorDelegatorsResponse> {
+    let height = height.unwrap_or(env.block.height);
+    let validator_addr = Addr::unchecked(validator);
+    let start_after = start_after
+        .map(|delegator| deps.api.addr_validate(&delegator))
+        .transpose()?;
+    let limit = limit
+        .unwrap_or(VALIDATOR_DELEGATORS_PAGE_LIMIT)
+        .min(VALIDATOR_DELEGATORS_PAGE_LIMIT) as usize;
+
+    let Some(validator) = VALIDATORS.may_load_at_height(deps.storage, &validator_addr, height)?
+    else {
+        return Ok(ValidatorDelegatorsResponse {
+            delegations: vec![],
+            last_delegator: None,
+        });
+    };
+
+    let mut delegators = VALIDATOR_DELEGATORS
+        .prefix(&validator_addr)
+        .keys(
+            deps.storage,
+            start_after.as_ref().map(Bound::exclusive),
+            None,
+            Order::Ascending,
+        )
+        .take(limit + 1)
+        .collect::<StdResult<Vec<Addr>>>()?;
+    let more_delegators = delegators.len() > limit;
+    delegators.truncate(limit);
+    let last_delegator = delegators.last().cloned().filter(|_| more_delegators);
+
+    let mut delegations = vec![];
+    for delegator in delegators {
+        // The delegator might have had no delegation at the height
+        let Some(delegation) = DELEGATIONS
+            .may_load_at_height(deps.storage, (&delegator, &validator_addr), height)?
+            .filter(|delegation| !delegation.shares.is_zero())
+        else {
+            continue;
+        };
+
+        delegations.push(DelegationTokens {
+            delegator,
+            validator: validator_addr.clone(),
+            shares: delegation.shares,
+            tokens: shares_to_tokens(delegation.shares, &validator)?,
+        });
+    }
+
+    Ok(ValidatorDelegatorsResponse {
+        delegations,
+        last_delegator,
+    })
+}
+
+pub fn query_validator_at_height(
+    deps: Deps,
+    env: Env,
+    validator: String,
+    height: Option<u64>,
+) -> StdResult<Option<ValidatorAtHeightResponse>> {
+    let height = height.unwrap_or(env.block.height);
+    let validator_addr = Addr::unchecked(validator);
+
+    let Some(validator) = VALIDATORS.may_load_at_height(deps.storage, &validator_addr, height)?
+    else {
+        return Ok(None);
+    };
+
+    let mut status =
+        match VALIDATOR_STATUSES.may_load_at_height(deps.storage, &validator_addr, height)? {
+            Some(status) => status,
+            // Statuses are recorded since the validator's first status change after the upgrade,
+            // before that the validator is either bonded or not
+            None => {
+                let bonded = BONDED_VALIDATORS_SET
+                    .may_load_at_height(deps.storage, height)?
+                    .unwrap_or_default()
+                    .contains(&validator_addr.to_string());
+                ValidatorStatus {
+                    bond_status: if bonded {
+                        BondStatus::Bonded
+                    } else {
+                        BondStatus::Unbonded
+                    },
+                    jailed: false,
+                    unbonding_completion_time: None,
+                }
+            }
+        };
+
+    // The staking module completes the unbonding of validators without calling a hook, so the
+    // status is derived from the completion time. The time of past blocks is unknown, so only the
+    // current one is checked.
+    if status.bond_status == BondStatus::Unbonding
+        && height >= env.block.height
+        && status
+            .unbonding_completion_time
+            .is_some_and(|completion_time| completion_time <= env.block.time)
+    {
+        status.bond_status = BondStatus::Unbonded;
+    }
+
+    Ok(Some(ValidatorAtHeightResponse { validator, status }))
+}
+
+pub fn query_validator_slashes(
+    deps: Deps,
+    validator: String,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<Vec<ValidatorSlash>> {
+    let validator_addr = Addr::unchecked(validator);
+    let limit = limit.map(|l| l as usize);
+    let range_min = start_after.map(Bound::exclusive);
+    let list = VALIDATOR_SLASHES
+        .prefix(&validator_addr)
+        .range(deps.storage, range_min, None, Order::Ascending)
+        .map(|r| r.map(|(_, slashes)| slashes));
+
+    let page: Vec<Vec<ValidatorSlash>> = if let Some(limit) = limit {
+        list.take(limit).collect::<StdResult<_>>()?
+    } else {
+        list.collect::<StdResult<_>>()?
+    };
+
+    Ok(page.into_iter().flatten().collect())
+}
+
+pub fn query_pending_unbonding_syncs(
+    deps: Deps,
+    start_after: Option<Addr>,
+    limit: Option<u32>,
+) -> StdResult<Vec<Addr>> {
+    let limit = limit.map(|l| l as usize);
+    let range_min = start_after.as_ref().map(Bound::exclusive);
+    let list = UNBONDING_SYNC_CURSORS.keys(deps.storage, range_min, None, Order::Ascending);
+    let page = if let Some(limit) = limit {
+        list.take(limit).collect::<StdResult<_>>()?
+    } else {
+        list.collect::<StdResult<_>>()?
+    };
+
+    Ok(page)
+}
+
+pub fn query_unbonding_entries(
+    deps: Deps,
+    env: Env,
+    delegator: String,
+    height: Option<u64>,
+) -> StdResult<Vec<UnbondingEntry>> {
+    let height = height.unwrap_or(env.block.height);
+    let delegator = deps.api.addr_validate(&delegator)?;
+
+    let mut entries = UNBONDING_ENTRIES
+        .may_load_at_height(deps.storage, &delegator, height)?
+        .unwrap_or_default();
+    // The stored entries are only updated by the hooks, so the entries that have matured since
+    // are dropped here. The time of past blocks is unknown, so only the current one is filtered.
+    if height >= env.block.height {
+        entries.retain(|entry| entry.completion_time > env.block.time);
+    }
+
+    Ok(entries)
+}
+
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn migrate(deps: DepsMut, _env: Env, _msg: MigrateMsg) -> Result<Response, ContractError> {
     // Set contract to version to latest
     set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
 
+    // The delegators of the existing delegations are indexed by validator with
+    // `ExecuteMsg::BackfillValidatorDelegators`, since there are too many to index here
+
     // Remove validator
     let validator_addr = Addr::unchecked("neutronvaloper1v9xys5c4zdr89tvwq983ycnj3j4pekpjwr0raa");
 
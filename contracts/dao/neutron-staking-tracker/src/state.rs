@@ -1,6 +1,8 @@
-use cosmwasm_std::Addr;
-use cw_storage_plus::{Item, SnapshotItem, SnapshotMap, Strategy};
-use neutron_staking_tracker_common::types::{Config, Delegation, Validator};
+use cosmwasm_std::{Addr, Empty};
+use cw_storage_plus::{Item, Map, SnapshotItem, SnapshotMap, Strategy};
+use neutron_staking_tracker_common::types::{
+    Config, Delegation, UnbondingEntry, Validator, ValidatorSlash, ValidatorStatus,
+};
 
 /// Storage mapping for all validators, indexed by the **operator address (`valoper`)**.
 ///
@@ -16,6 +18,26 @@ pub const VALIDATORS: SnapshotMap<&Addr, Validator> = SnapshotMap::new(
     Strategy::EveryBlock,
 );
 
+/// Storage mapping for validator statuses, indexed by the **operator address (`valoper`)**.
+///
+/// - **Key:** `&Addr` → Validator's **operator address** (`valoper`).
+/// - **Value:** `ValidatorStatus` struct containing the bond status and the jailed flag.
+///
+/// We use `SnapshotMap` to enable querying historical validator statuses at any height.
+/// Validators without a recorded status fall back to `BONDED_VALIDATORS_SET`.
+pub const VALIDATOR_STATUSES: SnapshotMap<&Addr, ValidatorStatus> = SnapshotMap::new(
+    "validator_statuses",
+    "validator_statuses__checkpoints",
+    "validator_statuses__changelog",
+    Strategy::EveryBlock,
+);
+
+/// Storage mapping for the slashing log, indexed by **(validator operator address, height)**.
+///
+/// - **Key:** `(&Addr, u64)` → **(validator operator address (`valoper`), slashing height)**.
+/// - **Value:** `Vec<ValidatorSlash>` containing the slashings of the validator at the height.
+pub const VALIDATOR_SLASHES: Map<(&Addr, u64), Vec<ValidatorSlash>> = Map::new("validator_slashes");
+
 /// Stores the list of bonded validators.
 /// The point of storing `BONDED_VALIDATORS_SET` is to avoid (potentially big) iteration over all validators.
 /// This can happen since SnapshotMap cannot iterate over previous heights,
@@ -46,7 +68,64 @@ pub const DELEGATIONS: SnapshotMap<(&Addr, &Addr), Delegation> = SnapshotMap::ne
     Strategy::EveryBlock,
 );
 
+/// Index of the delegators that have delegated to a validator, used to list the validator's
+/// delegations at any height. Entries aren't removed with the delegations, since the delegations
+/// are still there at earlier heights.
+///
+/// - **Key:** `(&Addr, &Addr)` → **(validator operator address (`valoper`), delegator address)**.
+pub const VALIDATOR_DELEGATORS: Map<(&Addr, &Addr), Empty> = Map::new("validator_delegators");
+
+/// Index of the validators a delegator has delegated to, used to list the delegator's
+/// delegations at any height, including the ones to validators removed since then. Entries
+/// aren't removed with the delegations, like the ones of `VALIDATOR_DELEGATORS`.
+///
+/// - **Key:** `(&Addr, &Addr)` → **(delegator address, validator operator address (`valoper`))**.
+pub const DELEGATOR_VALIDATORS: Map<(&Addr, &Addr), Empty> = Map::new("delegator_validators");
+
+/// The maximum number of `VALIDATOR_DELEGATORS` keys read by one `QueryMsg::ValidatorDelegators`
+/// page, whether or not the delegators had a delegation at the queried height.
+pub const VALIDATOR_DELEGATORS_PAGE_LIMIT: u32 = 100;
+
+/// The maximum number of `DELEGATIONS` changelog keys read by one
+/// `ExecuteMsg::BackfillValidatorDelegators` call.
+pub const VALIDATOR_DELEGATORS_BACKFILL_LIMIT: u32 = 100;
+
+/// Storage mapping for unbonding entries, indexed by **delegator address**.
+///
+/// - **Key:** `&Addr` → **delegator address**.
+/// - **Value:** `Vec<UnbondingEntry>` containing the delegator's unbonding entries of all validators.
+///
+/// We use `SnapshotMap` to allow querying a delegator's unbonding entries at any height.
+pub const UNBONDING_ENTRIES: SnapshotMap<&Addr, Vec<UnbondingEntry>> = SnapshotMap::new(
+    "unbonding_entries",
+    "unbonding_entries__checkpoints",
+    "unbonding_entries__changelog",
+    Strategy::EveryBlock,
+);
+
+/// Index of the delegators having unbonding entries of a validator, used to update the entries
+/// when the validator gets slashed.
+///
+/// - **Key:** `(&Addr, &Addr)` → **(validator operator address (`valoper`), delegator address)**.
+pub const UNBONDING_DELEGATORS: Map<(&Addr, &Addr), Empty> = Map::new("unbonding_delegators");
+
+/// The maximum number of unbonding delegators whose entries are updated in one call, either by
+/// the slashing hook or by `ExecuteMsg::SyncUnbondingEntries`.
+pub const UNBONDING_SYNC_LIMIT: u32 = 30;
+
+/// Cursors of the slashed validators whose unbonding entries haven't all been updated yet.
+///
+/// - **Key:** `&Addr` → **validator operator address (`valoper`)**.
+/// - **Value:** `Addr` → the last delegator whose entries have been updated.
+pub const UNBONDING_SYNC_CURSORS: Map<&Addr, Addr> = Map::new("unbonding_sync_cursors");
+
 /// Stores the core **configuration** of the contract.
 ///
 /// Contains metadata such as the contract's **name, description, owner, and token denom**.
 pub const CONFIG: Item<Config> = Item::new("config");
+
+/// Whether the import of the existing staking module state is sealed.
+///
+/// Until then, the owner can import validators and delegations with `ExecuteMsg::ImportState`.
+/// Missing value means the import isn't sealed.
+pub const IMPORT_SEALED: Item<bool> = Item::new("import_sealed");
@@ -5,10 +5,13 @@ use cosmwasm_std::{
     from_json, Binary, ContractResult, GrpcQuery, OwnedDeps, Querier, QuerierResult, QueryRequest,
     SystemError, SystemResult, Uint128,
 };
+use neutron_std::shim::Duration;
 use neutron_std::types::cosmos::staking::v1beta1::{
-    Delegation, DelegationResponse, QueryDelegationRequest, QueryDelegationResponse,
+    Delegation, DelegationResponse, Params, QueryDelegationRequest, QueryDelegationResponse,
+    QueryParamsResponse, QueryUnbondingDelegationRequest, QueryUnbondingDelegationResponse,
     QueryValidatorDelegationsRequest, QueryValidatorDelegationsResponse, QueryValidatorRequest,
-    QueryValidatorResponse, QueryValidatorsRequest, QueryValidatorsResponse, Validator,
+    QueryValidatorResponse, QueryValidatorsRequest, QueryValidatorsResponse, UnbondingDelegation,
+    UnbondingDelegationEntry, Validator,
 };
 use prost::Message;
 
@@ -27,8 +30,13 @@ pub struct WasmMockQuerier {
     base: MockQuerier,
     validators: HashMap<String, Validator>,
     pub delegations: HashMap<(String, String), Uint128>, // (delegator, validator) -> shares
+    // (delegator, validator) -> entries
+    unbonding_delegations: HashMap<(String, String), Vec<UnbondingDelegationEntry>>,
 }
 
+/// The unbonding time returned by the mock staking params query.
+pub const UNBONDING_TIME_SECONDS: u64 = 21 * 24 * 60 * 60;
+
 impl Querier for WasmMockQuerier {
     fn raw_query(&self, bin_request: &[u8]) -> QuerierResult {
         let request: QueryRequest = match from_json(bin_request) {
@@ -50,6 +58,7 @@ impl WasmMockQuerier {
             base,
             validators: HashMap::new(),
             delegations: HashMap::new(),
+            unbonding_delegations: HashMap::new(),
         }
     }
 
@@ -73,6 +82,12 @@ impl WasmMockQuerier {
                         Message::decode(&data[..]).unwrap();
                     self.handle_validator_delegations_query(request)
                 }
+                "/cosmos.staking.v1beta1.Query/UnbondingDelegation" => {
+                    let request: QueryUnbondingDelegationRequest =
+                        Message::decode(&data[..]).unwrap();
+                    self.handle_unbonding_delegation_query(request)
+                }
+                "/cosmos.staking.v1beta1.Query/Params" => self.handle_params_query(),
                 _ => {
                     println!("❌ Unsupported GRPC Query: {}", path);
                     self.base.handle_query(request)
@@ -219,6 +234,65 @@ impl WasmMockQuerier {
 
         SystemResult::Ok(ContractResult::Ok(Binary::from(buf)))
     }
+    fn handle_unbonding_delegation_query(
+        &self,
+        request: QueryUnbondingDelegationRequest,
+    ) -> QuerierResult {
+        let key = (
+            request.delegator_addr.clone(),
+            request.validator_addr.clone(),
+        );
+        let Some(entries) = self.unbonding_delegations.get(&key) else {
+            return SystemResult::Err(SystemError::InvalidRequest {
+                error: format!(
+                    "Unbonding delegation not found: {}, {}",
+                    request.delegator_addr, request.validator_addr
+                ),
+                request: Binary::new(Vec::from("")),
+            });
+        };
+
+        let response = QueryUnbondingDelegationResponse {
+            unbond: Some(UnbondingDelegation {
+                delegator_address: request.delegator_addr,
+                validator_address: request.validator_addr,
+                entries: entries.clone(),
+            }),
+        };
+
+        let mut buf = Vec::new();
+        if let Err(e) = response.encode(&mut buf) {
+            return SystemResult::Err(SystemError::InvalidResponse {
+                error: format!("Failed to encode Protobuf response: {}", e),
+                response: Default::default(),
+            });
+        }
+
+        SystemResult::Ok(ContractResult::Ok(Binary::from(buf)))
+    }
+
+    fn handle_params_query(&self) -> QuerierResult {
+        let response = QueryParamsResponse {
+            params: Some(Params {
+                unbonding_time: Some(Duration {
+                    seconds: UNBONDING_TIME_SECONDS as i64,
+                    nanos: 0,
+                }),
+                ..Default::default()
+            }),
+        };
+
+        let mut buf = Vec::new();
+        if let Err(e) = response.encode(&mut buf) {
+            return SystemResult::Err(SystemError::InvalidResponse {
+                error: format!("Failed to encode Protobuf response: {}", e),
+                response: Default::default(),
+            });
+        }
+
+        SystemResult::Ok(ContractResult::Ok(Binary::from(buf)))
+    }
+
     /// Allows setting **mock validators** for testing.
     pub fn with_validators(&mut self, validators: Vec<Validator>) {
         self.validators = validators
@@ -234,4 +308,12 @@ impl WasmMockQuerier {
                 .insert((delegator_addr.clone(), validator_addr.clone()), *shares);
         }
     }
+
+    /// Allows setting **mock unbonding delegation entries** for testing.
+    pub fn with_unbonding_delegations(
+        &mut self,
+        unbonding_delegations: HashMap<(String, String), Vec<UnbondingDelegationEntry>>,
+    ) {
+        self.unbonding_delegations = unbonding_delegations;
+    }
 }
@@ -1,21 +1,32 @@
 #[cfg(test)]
 use crate::contract::{
     after_delegation_modified, after_validator_begin_unbonding, after_validator_bonded,
-    before_validator_slashed, execute, instantiate, query_stake_at_height,
-    query_total_stake_at_height,
+    before_delegation_removed, before_validator_slashed, execute, instantiate,
+    query_bonded_validators, query_delegator_delegations, query_pending_unbonding_syncs,
+    query_stake_at_height, query_total_stake_at_height, query_unbonding_entries, query_validator,
+    query_validator_at_height, query_validator_delegators, query_validator_slashes,
 };
 use crate::contract::{after_validator_created, after_validator_removed, migrate};
-use crate::state::{BONDED_VALIDATORS_SET, CONFIG, DELEGATIONS, VALIDATORS};
-use crate::testing::mock_querier::mock_dependencies as dependencies;
+use crate::state::{
+    BONDED_VALIDATORS_SET, CONFIG, DELEGATIONS, IMPORT_SEALED, UNBONDING_DELEGATORS,
+    UNBONDING_ENTRIES, VALIDATORS, VALIDATOR_STATUSES,
+};
+use crate::testing::mock_querier::{mock_dependencies as dependencies, UNBONDING_TIME_SECONDS};
 use cosmwasm_std::testing::message_info;
 use cosmwasm_std::{
     testing::{mock_dependencies, mock_env},
-    to_json_binary, Addr, Decimal256, Uint128,
+    to_json_binary, Addr, Decimal256, Deps, Empty, Env, Uint128,
+};
+use neutron_staking_tracker_common::error::ContractError;
+use neutron_staking_tracker_common::msg::{
+    DelegationTokens, ExecuteMsg, InstantiateMsg, MigrateMsg, ValidatorDelegatorsResponse,
 };
-use neutron_staking_tracker_common::msg::{ExecuteMsg, InstantiateMsg, MigrateMsg};
-use neutron_staking_tracker_common::types::{Config, Delegation, Validator};
+use neutron_staking_tracker_common::types::{
+    BondStatus, Config, Delegation, UnbondingEntry, Validator, ValidatorSlash, ValidatorStatus,
+};
+use neutron_std::shim::Timestamp as ProtoTimestamp;
 use neutron_std::types::cosmos::staking::v1beta1::{
-    QueryValidatorResponse, Validator as CosmosValidator,
+    QueryValidatorResponse, UnbondingDelegationEntry, Validator as CosmosValidator,
 };
 use std::collections::HashMap;
 
@@ -394,6 +405,14 @@ fn test_after_validator_bonded_with_mock_query() {
         .contains(&updated_validator.oper_address.to_string()));
     assert_eq!(updated_validator.total_tokens, Uint128::new(1000));
     assert_eq!(updated_validator.total_shares, Uint128::new(1000));
+    assert_eq!(
+        query_bonded_validators(deps.as_ref(), env.clone(), None).unwrap(),
+        vec![oper_addr.clone()]
+    );
+    assert_eq!(
+        query_validator(deps.as_ref(), env.clone(), oper_addr.to_string(), None).unwrap(),
+        Some(updated_validator)
+    );
 
     // Ensure response attributes match expected values
     let response = res.unwrap();
@@ -473,6 +492,7 @@ fn test_before_validator_slashed_with_self_bonded_only() {
         ("total_tokens", "900"),   // 10% slashed from 1000 → 900
         ("total_shares", "1000"),  // Shares remain unchanged
         ("tokens_to_burn", "100"), // 10% slashing
+        ("unbonding_sync_pending", "false"),
     ];
 
     // Convert response attributes for assertion
@@ -599,6 +619,7 @@ fn test_before_validator_slashed() {
         ("total_tokens", "450"),
         ("total_shares", "500"),
         ("tokens_to_burn", "50"),
+        ("unbonding_sync_pending", "false"),
     ];
 
     // Convert `response.attributes` from `Vec<Attribute>` to `Vec<(&str, &str)>`
@@ -796,6 +817,7 @@ fn test_before_validator_slashed_stake_drops() {
         ("total_tokens", "900"),
         ("total_shares", "1000"),
         ("tokens_to_burn", "100"), // Use the stored string
+        ("unbonding_sync_pending", "false"),
     ];
 
     assert_eq!(actual_attributes, expected_attributes);
@@ -1357,3 +1379,1131 @@ fn test_after_validator_begin_unbonding() {
         ]
     );
 }
+
+#[test]
+fn test_unbonding_entries() {
+    let mut deps = dependencies();
+    deps.api = deps.api.with_prefix("neutron");
+    let mut env = mock_env();
+
+    let delegator = deps.api.addr_make("delegator1");
+    let oper_addr = Addr::unchecked("neutronvaloper1xyz");
+
+    let config = Config {
+        name: String::from("Test Config"),
+        description: String::from("Testing unbonding entries"),
+        owner: deps.api.addr_make("admin"),
+        staking_proxy_info_contract_address: None,
+    };
+    CONFIG.save(deps.as_mut().storage, &config).unwrap();
+
+    BONDED_VALIDATORS_SET
+        .save(
+            deps.as_mut().storage,
+            &vec![oper_addr.to_string()],
+            env.block.height,
+        )
+        .unwrap();
+    VALIDATORS
+        .save(
+            deps.as_mut().storage,
+            &oper_addr,
+            &Validator {
+                oper_address: oper_addr.clone(),
+                total_tokens: Uint128::new(1000),
+                total_shares: Uint128::new(1000),
+            },
+            env.block.height,
+        )
+        .unwrap();
+    DELEGATIONS
+        .save(
+            deps.as_mut().storage,
+            (&delegator, &oper_addr),
+            &Delegation {
+                delegator_address: delegator.clone(),
+                validator_address: oper_addr.clone(),
+                shares: Uint128::new(200),
+            },
+            env.block.height,
+        )
+        .unwrap();
+
+    env.block.height += 5;
+    let unbonding_height = env.block.height;
+    let completion_time = env.block.time.plus_seconds(UNBONDING_TIME_SECONDS);
+
+    // The delegator undelegates 50 tokens
+    deps.querier.with_delegations(HashMap::from([(
+        (delegator.to_string(), oper_addr.to_string()),
+        Uint128::new(150),
+    )]));
+    after_delegation_modified(
+        deps.as_mut(),
+        env.clone(),
+        delegator.to_string(),
+        oper_addr.to_string(),
+    )
+    .unwrap();
+
+    let entry = |initial_balance: u128, balance: u128| UnbondingEntry {
+        validator_address: oper_addr.clone(),
+        creation_height: unbonding_height,
+        completion_time,
+        initial_balance: Uint128::new(initial_balance),
+        balance: Uint128::new(balance),
+    };
+    let unbonding_entries = query_unbonding_entries(
+        deps.as_ref(),
+        env.clone(),
+        delegator.to_string(),
+        Some(unbonding_height + 1),
+    )
+    .unwrap();
+    assert_eq!(unbonding_entries, vec![entry(50, 50)]);
+    // No entries before the undelegation
+    let unbonding_entries = query_unbonding_entries(
+        deps.as_ref(),
+        env.clone(),
+        delegator.to_string(),
+        Some(unbonding_height),
+    )
+    .unwrap();
+    assert!(unbonding_entries.is_empty());
+
+    // The delegator undelegates 50 more tokens in the same block, which the staking module merges
+    // with the entry it has created for the first undelegation
+    let chain_entry = |initial_balance: u128, balance: u128| UnbondingDelegationEntry {
+        creation_height: unbonding_height as i64,
+        completion_time: Some(ProtoTimestamp {
+            seconds: completion_time.seconds() as i64,
+            nanos: completion_time.subsec_nanos() as i32,
+        }),
+        initial_balance: initial_balance.to_string(),
+        balance: balance.to_string(),
+        ..Default::default()
+    };
+    deps.querier.with_unbonding_delegations(HashMap::from([(
+        (delegator.to_string(), oper_addr.to_string()),
+        vec![chain_entry(50, 50)],
+    )]));
+    deps.querier.with_delegations(HashMap::from([(
+        (delegator.to_string(), oper_addr.to_string()),
+        Uint128::new(100),
+    )]));
+    after_delegation_modified(
+        deps.as_mut(),
+        env.clone(),
+        delegator.to_string(),
+        oper_addr.to_string(),
+    )
+    .unwrap();
+    let unbonding_entries = query_unbonding_entries(
+        deps.as_ref(),
+        env.clone(),
+        delegator.to_string(),
+        Some(unbonding_height + 1),
+    )
+    .unwrap();
+    assert_eq!(unbonding_entries, vec![entry(100, 100)]);
+
+    // The staking module slashes the unbonding entry before calling the hook
+    env.block.height += 5;
+    deps.querier.with_unbonding_delegations(HashMap::from([(
+        (delegator.to_string(), oper_addr.to_string()),
+        vec![chain_entry(100, 90)],
+    )]));
+    before_validator_slashed(
+        deps.as_mut(),
+        env.clone(),
+        oper_addr.to_string(),
+        Decimal256::percent(10),
+        Uint128::new(90),
+    )
+    .unwrap();
+    let unbonding_entries = query_unbonding_entries(
+        deps.as_ref(),
+        env.clone(),
+        delegator.to_string(),
+        Some(env.block.height + 1),
+    )
+    .unwrap();
+    assert_eq!(unbonding_entries, vec![entry(100, 90)]);
+
+    // The delegator undelegates the rest, while the earlier entry has completed
+    env.block.height += 5;
+    deps.querier.with_unbonding_delegations(HashMap::new());
+    before_delegation_removed(
+        deps.as_mut(),
+        env.clone(),
+        delegator.to_string(),
+        oper_addr.to_string(),
+    )
+    .unwrap();
+    let unbonding_entries = query_unbonding_entries(
+        deps.as_ref(),
+        env.clone(),
+        delegator.to_string(),
+        Some(env.block.height + 1),
+    )
+    .unwrap();
+    assert_eq!(
+        unbonding_entries,
+        vec![UnbondingEntry {
+            validator_address: oper_addr.clone(),
+            creation_height: env.block.height,
+            completion_time: env.block.time.plus_seconds(UNBONDING_TIME_SECONDS),
+            initial_balance: Uint128::new(90),
+            balance: Uint128::new(90),
+        }]
+    );
+    // The history is preserved
+    let unbonding_entries = query_unbonding_entries(
+        deps.as_ref(),
+        env.clone(),
+        delegator.to_string(),
+        Some(unbonding_height + 1),
+    )
+    .unwrap();
+    assert_eq!(unbonding_entries, vec![entry(100, 100)]);
+
+    let removal_entry = UnbondingEntry {
+        validator_address: oper_addr.clone(),
+        creation_height: env.block.height,
+        completion_time: env.block.time.plus_seconds(UNBONDING_TIME_SECONDS),
+        initial_balance: Uint128::new(90),
+        balance: Uint128::new(90),
+    };
+    deps.querier.with_unbonding_delegations(HashMap::from([(
+        (delegator.to_string(), oper_addr.to_string()),
+        vec![UnbondingDelegationEntry {
+            creation_height: removal_entry.creation_height as i64,
+            completion_time: Some(ProtoTimestamp {
+                seconds: removal_entry.completion_time.seconds() as i64,
+                nanos: removal_entry.completion_time.subsec_nanos() as i32,
+            }),
+            initial_balance: "90".to_string(),
+            balance: "90".to_string(),
+            ..Default::default()
+        }],
+    )]));
+
+    // The delegator redelegates from another validator to the first one. The hook of the source
+    // validator records an unbonding entry, which the hook of the destination validator drops
+    // since the staking module has no such entry.
+    env.block.height += 5;
+    let other_oper_addr = Addr::unchecked("neutronvaloper1abc");
+    VALIDATORS
+        .save(
+            deps.as_mut().storage,
+            &other_oper_addr,
+            &Validator {
+                oper_address: other_oper_addr.clone(),
+                total_tokens: Uint128::new(1000),
+                total_shares: Uint128::new(1000),
+            },
+            env.block.height,
+        )
+        .unwrap();
+    DELEGATIONS
+        .save(
+            deps.as_mut().storage,
+            (&delegator, &other_oper_addr),
+            &Delegation {
+                delegator_address: delegator.clone(),
+                validator_address: other_oper_addr.clone(),
+                shares: Uint128::new(100),
+            },
+            env.block.height,
+        )
+        .unwrap();
+    before_delegation_removed(
+        deps.as_mut(),
+        env.clone(),
+        delegator.to_string(),
+        other_oper_addr.to_string(),
+    )
+    .unwrap();
+    let unbonding_entries = query_unbonding_entries(
+        deps.as_ref(),
+        env.clone(),
+        delegator.to_string(),
+        Some(env.block.height + 1),
+    )
+    .unwrap();
+    assert_eq!(unbonding_entries.len(), 2);
+
+    deps.querier.with_validators(vec![CosmosValidator {
+        operator_address: oper_addr.to_string(),
+        consensus_pubkey: None,
+        jailed: false,
+        status: 3, // Bonded
+        tokens: "1010".to_string(),
+        delegator_shares: "1010".to_string(),
+        description: None,
+        unbonding_height: 0,
+        unbonding_time: None,
+        commission: None,
+        min_self_delegation: "1".to_string(),
+        unbonding_on_hold_ref_count: 0,
+        unbonding_ids: vec![],
+    }]);
+    deps.querier.with_delegations(HashMap::from([(
+        (delegator.to_string(), oper_addr.to_string()),
+        Uint128::new(100),
+    )]));
+    after_delegation_modified(
+        deps.as_mut(),
+        env.clone(),
+        delegator.to_string(),
+        oper_addr.to_string(),
+    )
+    .unwrap();
+    let unbonding_entries = query_unbonding_entries(
+        deps.as_ref(),
+        env.clone(),
+        delegator.to_string(),
+        Some(env.block.height + 1),
+    )
+    .unwrap();
+    assert_eq!(unbonding_entries, vec![removal_entry.clone()]);
+    assert!(!UNBONDING_DELEGATORS.has(deps.as_ref().storage, (&other_oper_addr, &delegator)));
+
+    // The entry is no longer reported once it has matured
+    env.block.time = removal_entry.completion_time;
+    let unbonding_entries =
+        query_unbonding_entries(deps.as_ref(), env.clone(), delegator.to_string(), None).unwrap();
+    assert!(unbonding_entries.is_empty());
+}
+
+/// Tests that the entries of an undelegation from an unbonding validator complete with the
+/// validator's unbonding, and that no entry is recorded for an unbonded validator.
+#[test]
+fn test_unbonding_entries_validator_status() {
+    let mut deps = dependencies();
+    deps.api = deps.api.with_prefix("neutron");
+    let mut env = mock_env();
+
+    let delegator = deps.api.addr_make("delegator1");
+    let oper_addr = Addr::unchecked("neutronvaloper1xyz");
+    let config = Config {
+        name: String::from("Test Config"),
+        description: String::from("Testing unbonding entries of unbonding validators"),
+        owner: deps.api.addr_make("admin"),
+        staking_proxy_info_contract_address: None,
+    };
+    CONFIG.save(deps.as_mut().storage, &config).unwrap();
+    VALIDATORS
+        .save(
+            deps.as_mut().storage,
+            &oper_addr,
+            &Validator {
+                oper_address: oper_addr.clone(),
+                total_tokens: Uint128::new(1000),
+                total_shares: Uint128::new(1000),
+            },
+            env.block.height,
+        )
+        .unwrap();
+    DELEGATIONS
+        .save(
+            deps.as_mut().storage,
+            (&delegator, &oper_addr),
+            &Delegation {
+                delegator_address: delegator.clone(),
+                validator_address: oper_addr.clone(),
+                shares: Uint128::new(200),
+            },
+            env.block.height,
+        )
+        .unwrap();
+
+    let validator_unbonding_height = env.block.height;
+    let validator_unbonding_time = env.block.time.plus_seconds(100);
+    let chain_validator = |status: i32| CosmosValidator {
+        operator_address: oper_addr.to_string(),
+        consensus_pubkey: None,
+        jailed: false,
+        status,
+        tokens: "1000".to_string(),
+        delegator_shares: "1000".to_string(),
+        description: None,
+        unbonding_height: validator_unbonding_height as i64,
+        unbonding_time: Some(ProtoTimestamp {
+            seconds: validator_unbonding_time.seconds() as i64,
+            nanos: validator_unbonding_time.subsec_nanos() as i32,
+        }),
+        commission: None,
+        min_self_delegation: "1".to_string(),
+        unbonding_on_hold_ref_count: 0,
+        unbonding_ids: vec![],
+    };
+
+    // The delegator undelegates 50 tokens from the unbonding validator
+    env.block.height += 5;
+    deps.querier.with_validators(vec![chain_validator(2)]);
+    deps.querier.with_delegations(HashMap::from([(
+        (delegator.to_string(), oper_addr.to_string()),
+        Uint128::new(150),
+    )]));
+    after_delegation_modified(
+        deps.as_mut(),
+        env.clone(),
+        delegator.to_string(),
+        oper_addr.to_string(),
+    )
+    .unwrap();
+    let unbonding_entry = UnbondingEntry {
+        validator_address: oper_addr.clone(),
+        creation_height: validator_unbonding_height,
+        completion_time: validator_unbonding_time,
+        initial_balance: Uint128::new(50),
+        balance: Uint128::new(50),
+    };
+    let unbonding_entries = query_unbonding_entries(
+        deps.as_ref(),
+        env.clone(),
+        delegator.to_string(),
+        Some(env.block.height + 1),
+    )
+    .unwrap();
+    assert_eq!(unbonding_entries, vec![unbonding_entry.clone()]);
+
+    // The validator is unbonded, and the tokens undelegated from it are returned right away
+    env.block.height += 5;
+    env.block.time = validator_unbonding_time.plus_seconds(5);
+    deps.querier.with_validators(vec![chain_validator(1)]);
+    deps.querier.with_delegations(HashMap::from([(
+        (delegator.to_string(), oper_addr.to_string()),
+        Uint128::new(100),
+    )]));
+    after_delegation_modified(
+        deps.as_mut(),
+        env.clone(),
+        delegator.to_string(),
+        oper_addr.to_string(),
+    )
+    .unwrap();
+    let unbonding_entries = query_unbonding_entries(
+        deps.as_ref(),
+        env.clone(),
+        delegator.to_string(),
+        Some(env.block.height + 1),
+    )
+    .unwrap();
+    assert!(unbonding_entries.is_empty());
+    assert!(!UNBONDING_DELEGATORS.has(deps.as_ref().storage, (&oper_addr, &delegator)));
+}
+
+#[test]
+fn test_unbonding_entries_sync() {
+    let mut deps = dependencies();
+    deps.api = deps.api.with_prefix("neutron");
+    let mut env = mock_env();
+
+    let oper_addr = Addr::unchecked("neutronvaloper1xyz");
+    let config = Config {
+        name: String::from("Test Config"),
+        description: String::from("Testing unbonding entries sync"),
+        owner: deps.api.addr_make("admin"),
+        staking_proxy_info_contract_address: None,
+    };
+    CONFIG.save(deps.as_mut().storage, &config).unwrap();
+    VALIDATORS
+        .save(
+            deps.as_mut().storage,
+            &oper_addr,
+            &Validator {
+                oper_address: oper_addr.clone(),
+                total_tokens: Uint128::new(1000),
+                total_shares: Uint128::new(1000),
+            },
+            env.block.height,
+        )
+        .unwrap();
+
+    // 31 delegators have unbonding entries of the validator, which the staking module slashes
+    let creation_height = env.block.height;
+    let completion_time = env.block.time.plus_seconds(UNBONDING_TIME_SECONDS);
+    let entry = |balance: u128| UnbondingEntry {
+        validator_address: oper_addr.clone(),
+        creation_height,
+        completion_time,
+        initial_balance: Uint128::new(100),
+        balance: Uint128::new(balance),
+    };
+    let delegators: Vec<Addr> = (0..31)
+        .map(|i| deps.api.addr_make(&format!("delegator{i}")))
+        .collect();
+    let mut chain_entries = HashMap::new();
+    for delegator in delegators.iter() {
+        UNBONDING_ENTRIES
+            .save(
+                deps.as_mut().storage,
+                delegator,
+                &vec![entry(100)],
+                env.block.height,
+            )
+            .unwrap();
+        UNBONDING_DELEGATORS
+            .save(deps.as_mut().storage, (&oper_addr, delegator), &Empty {})
+            .unwrap();
+        chain_entries.insert(
+            (delegator.to_string(), oper_addr.to_string()),
+            vec![UnbondingDelegationEntry {
+                creation_height: creation_height as i64,
+                completion_time: Some(ProtoTimestamp {
+                    seconds: completion_time.seconds() as i64,
+                    nanos: completion_time.subsec_nanos() as i32,
+                }),
+                initial_balance: "100".to_string(),
+                balance: "90".to_string(),
+                ..Default::default()
+            }],
+        );
+    }
+    deps.querier.with_unbonding_delegations(chain_entries);
+
+    // The slashing hook updates the entries of 30 delegators
+    env.block.height += 1;
+    let res = before_validator_slashed(
+        deps.as_mut(),
+        env.clone(),
+        oper_addr.to_string(),
+        Decimal256::percent(10),
+        Uint128::new(100),
+    )
+    .unwrap();
+    assert!(res
+        .attributes
+        .iter()
+        .any(|attr| attr.key == "unbonding_sync_pending" && attr.value == "true"));
+    let slashed_entries = |deps: Deps, env: Env| {
+        delegators
+            .iter()
+            .filter(|delegator| {
+                query_unbonding_entries(
+                    deps,
+                    env.clone(),
+                    delegator.to_string(),
+                    Some(env.block.height + 1),
+                )
+                .unwrap()
+                    == vec![entry(90)]
+            })
+            .count()
+    };
+    assert_eq!(slashed_entries(deps.as_ref(), env.clone()), 30);
+    assert_eq!(
+        query_pending_unbonding_syncs(deps.as_ref(), None, None).unwrap(),
+        vec![oper_addr.clone()]
+    );
+
+    // Anyone can update the rest
+    let anyone = deps.api.addr_make("anyone");
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        message_info(&anyone, &[]),
+        ExecuteMsg::SyncUnbondingEntries {
+            validator: oper_addr.to_string(),
+            limit: None,
+        },
+    )
+    .unwrap();
+    assert!(res
+        .attributes
+        .iter()
+        .any(|attr| attr.key == "unbonding_sync_pending" && attr.value == "false"));
+    assert_eq!(slashed_entries(deps.as_ref(), env.clone()), 31);
+    assert!(query_pending_unbonding_syncs(deps.as_ref(), None, None)
+        .unwrap()
+        .is_empty());
+}
+
+#[test]
+fn test_validator_status_and_slashes() {
+    let mut deps = dependencies();
+    let mut env = mock_env();
+
+    let config = Config {
+        name: String::from("Test Config"),
+        description: String::from("Testing validator statuses"),
+        owner: deps.api.addr_make("admin"),
+        staking_proxy_info_contract_address: None,
+    };
+    CONFIG.save(deps.as_mut().storage, &config).unwrap();
+    BONDED_VALIDATORS_SET
+        .save(deps.as_mut().storage, &Vec::new(), env.block.height)
+        .unwrap();
+
+    let oper_addr = Addr::unchecked("neutronvaloper1xyz");
+    let proto_validator =
+        |status: i32, jailed: bool, unbonding_time: Option<ProtoTimestamp>| CosmosValidator {
+            operator_address: oper_addr.to_string(),
+            consensus_pubkey: None,
+            status,
+            tokens: "1000".to_string(),
+            jailed,
+            delegator_shares: "1000".to_string(),
+            description: None,
+            unbonding_height: 0,
+            unbonding_time,
+            commission: None,
+            min_self_delegation: "1".to_string(),
+            unbonding_on_hold_ref_count: 0,
+            unbonding_ids: vec![],
+        };
+
+    // The validator is created unbonded
+    let created_height = env.block.height;
+    deps.querier
+        .with_validators(vec![proto_validator(1, false, None)]);
+    after_validator_created(deps.as_mut(), env.clone(), oper_addr.to_string()).unwrap();
+
+    // The validator gets bonded
+    env.block.height += 5;
+    let bonded_height = env.block.height;
+    deps.querier
+        .with_validators(vec![proto_validator(3, false, None)]);
+    after_validator_bonded(deps.as_mut(), env.clone(), oper_addr.to_string()).unwrap();
+
+    // The validator gets slashed twice at the same height and jailed
+    env.block.height += 5;
+    let slashed_height = env.block.height;
+    before_validator_slashed(
+        deps.as_mut(),
+        env.clone(),
+        oper_addr.to_string(),
+        Decimal256::percent(1),
+        Uint128::new(10),
+    )
+    .unwrap();
+    before_validator_slashed(
+        deps.as_mut(),
+        env.clone(),
+        oper_addr.to_string(),
+        Decimal256::percent(5),
+        Uint128::new(50),
+    )
+    .unwrap();
+    let unbonding_completion_time = env.block.time.plus_seconds(UNBONDING_TIME_SECONDS);
+    deps.querier.with_validators(vec![proto_validator(
+        2,
+        true,
+        Some(ProtoTimestamp {
+            seconds: unbonding_completion_time.seconds() as i64,
+            nanos: unbonding_completion_time.subsec_nanos() as i32,
+        }),
+    )]);
+    after_validator_begin_unbonding(deps.as_mut(), env.clone(), oper_addr.to_string()).unwrap();
+
+    // The validator gets slashed once more later
+    env.block.height += 5;
+    before_validator_slashed(
+        deps.as_mut(),
+        env.clone(),
+        oper_addr.to_string(),
+        Decimal256::percent(10),
+        Uint128::new(94),
+    )
+    .unwrap();
+
+    let status_at = |deps: Deps, height: u64| {
+        query_validator_at_height(deps, mock_env(), oper_addr.to_string(), Some(height))
+            .unwrap()
+            .map(|resp| resp.status)
+    };
+    assert_eq!(status_at(deps.as_ref(), created_height), None);
+    assert_eq!(
+        status_at(deps.as_ref(), created_height + 1),
+        Some(ValidatorStatus {
+            bond_status: BondStatus::Unbonded,
+            jailed: false,
+            unbonding_completion_time: None,
+        })
+    );
+    assert_eq!(
+        status_at(deps.as_ref(), bonded_height + 1),
+        Some(ValidatorStatus {
+            bond_status: BondStatus::Bonded,
+            jailed: false,
+            unbonding_completion_time: None,
+        })
+    );
+    assert_eq!(
+        status_at(deps.as_ref(), slashed_height + 1),
+        Some(ValidatorStatus {
+            bond_status: BondStatus::Unbonding,
+            jailed: true,
+            unbonding_completion_time: Some(unbonding_completion_time),
+        })
+    );
+    let resp = query_validator_at_height(
+        deps.as_ref(),
+        env.clone(),
+        oper_addr.to_string(),
+        Some(env.block.height + 1),
+    )
+    .unwrap()
+    .unwrap();
+    assert_eq!(
+        resp.validator.total_tokens,
+        Uint128::new(1000 - 10 - 50 - 94)
+    );
+
+    let slash = |height: u64, fraction: u64, tokens_burned: u128| ValidatorSlash {
+        height,
+        fraction: Decimal256::percent(fraction),
+        tokens_burned: Uint128::new(tokens_burned),
+    };
+    let slashes =
+        query_validator_slashes(deps.as_ref(), oper_addr.to_string(), None, None).unwrap();
+    assert_eq!(
+        slashes,
+        vec![
+            slash(slashed_height, 1, 10),
+            slash(slashed_height, 5, 50),
+            slash(env.block.height, 10, 94),
+        ]
+    );
+    let slashes = query_validator_slashes(
+        deps.as_ref(),
+        oper_addr.to_string(),
+        Some(slashed_height),
+        Some(10),
+    )
+    .unwrap();
+    assert_eq!(slashes, vec![slash(env.block.height, 10, 94)]);
+
+    // The validator is unbonded once the unbonding completes
+    env.block.height += 5;
+    env.block.time = unbonding_completion_time;
+    let resp = query_validator_at_height(deps.as_ref(), env.clone(), oper_addr.to_string(), None)
+        .unwrap()
+        .unwrap();
+    assert_eq!(
+        resp.status,
+        ValidatorStatus {
+            bond_status: BondStatus::Unbonded,
+            jailed: true,
+            unbonding_completion_time: Some(unbonding_completion_time),
+        }
+    );
+
+    // The status is kept when the validator is removed
+    let removed_height = env.block.height;
+    after_validator_removed(deps.as_mut(), env.clone(), oper_addr.to_string()).unwrap();
+    assert_eq!(status_at(deps.as_ref(), removed_height + 1), None);
+    assert_eq!(
+        VALIDATOR_STATUSES
+            .may_load_at_height(deps.as_ref().storage, &oper_addr, removed_height + 1)
+            .unwrap(),
+        Some(ValidatorStatus {
+            bond_status: BondStatus::Unbonded,
+            jailed: true,
+            unbonding_completion_time: Some(unbonding_completion_time),
+        })
+    );
+}
+
+#[test]
+fn test_delegator_and_validator_delegations() {
+    let mut deps = dependencies();
+    deps.api = deps.api.with_prefix("neutron");
+    let mut env = mock_env();
+
+    let validator1 = Addr::unchecked("neutronvaloper1aaa");
+    let validator2 = Addr::unchecked("neutronvaloper1bbb");
+    let mut delegators = [
+        deps.api.addr_make("delegator1"),
+        deps.api.addr_make("delegator2"),
+    ];
+    delegators.sort();
+    let [delegator1, delegator2] = delegators;
+
+    // validator1 has 2 tokens per share, validator2 has 1 token per share
+    let delegated_height = env.block.height;
+    for (validator, total_tokens) in [(&validator1, 2000u128), (&validator2, 1000u128)] {
+        let validator = Validator {
+            oper_address: validator.clone(),
+            total_tokens: Uint128::new(total_tokens),
+            total_shares: Uint128::new(1000),
+        };
+        VALIDATORS
+            .save(
+                deps.as_mut().storage,
+                &validator.oper_address,
+                &validator,
+                env.block.height,
+            )
+            .unwrap();
+    }
+    for (delegator, validator, shares) in [
+        (&delegator1, &validator1, 100u128),
+        (&delegator1, &validator2, 50u128),
+        (&delegator2, &validator1, 300u128),
+    ] {
+        let delegation = Delegation {
+            delegator_address: delegator.clone(),
+            validator_address: validator.clone(),
+            shares: Uint128::new(shares),
+        };
+        DELEGATIONS
+            .save(
+                deps.as_mut().storage,
+                (delegator, validator),
+                &delegation,
+                env.block.height,
+            )
+            .unwrap();
+    }
+
+    // delegator1 undelegates everything from validator1 later
+    env.block.height += 10;
+    let undelegated_height = env.block.height;
+    DELEGATIONS
+        .remove(
+            deps.as_mut().storage,
+            (&delegator1, &validator1),
+            env.block.height,
+        )
+        .unwrap();
+
+    // The delegations stored before the upgrade get indexed by validator and by delegator by
+    // anyone in pages
+    let anyone = deps.api.addr_make("anyone");
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        message_info(&anyone, &[]),
+        ExecuteMsg::BackfillValidatorDelegators {
+            start_after: None,
+            limit: Some(2),
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![
+            ("action", "backfill_validator_delegators"),
+            ("backfill_pending", "true"),
+            ("last_delegator", delegator1.as_str()),
+            ("last_validator", validator1.as_str()),
+        ]
+    );
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        message_info(&anyone, &[]),
+        ExecuteMsg::BackfillValidatorDelegators {
+            start_after: Some((delegator1.to_string(), validator1.to_string())),
+            limit: Some(2),
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![
+            ("action", "backfill_validator_delegators"),
+            ("backfill_pending", "false"),
+        ]
+    );
+
+    let delegation =
+        |delegator: &Addr, validator: &Addr, shares: u128, tokens: u128| DelegationTokens {
+            delegator: delegator.clone(),
+            validator: validator.clone(),
+            shares: Uint128::new(shares),
+            tokens: Uint128::new(tokens),
+        };
+
+    let delegator_delegations = |deps: Deps, delegator: &Addr, height: u64| {
+        query_delegator_delegations(deps, mock_env(), delegator.to_string(), Some(height)).unwrap()
+    };
+    assert_eq!(
+        delegator_delegations(deps.as_ref(), &delegator1, delegated_height),
+        vec![]
+    );
+    assert_eq!(
+        delegator_delegations(deps.as_ref(), &delegator1, delegated_height + 1),
+        vec![
+            delegation(&delegator1, &validator1, 100, 200),
+            delegation(&delegator1, &validator2, 50, 50),
+        ]
+    );
+    assert_eq!(
+        delegator_delegations(deps.as_ref(), &delegator1, undelegated_height + 1),
+        vec![delegation(&delegator1, &validator2, 50, 50)]
+    );
+
+    let validator_delegators =
+        |deps: Deps, height: u64, start_after: Option<&Addr>, limit: Option<u32>| {
+            query_validator_delegators(
+                deps,
+                mock_env(),
+                validator1.to_string(),
+                Some(height),
+                start_after.map(|addr| addr.to_string()),
+                limit,
+            )
+            .unwrap()
+        };
+    assert_eq!(
+        validator_delegators(deps.as_ref(), delegated_height + 1, None, None),
+        ValidatorDelegatorsResponse {
+            delegations: vec![
+                delegation(&delegator1, &validator1, 100, 200),
+                delegation(&delegator2, &validator1, 300, 600),
+            ],
+            last_delegator: None,
+        }
+    );
+    assert_eq!(
+        validator_delegators(deps.as_ref(), delegated_height + 1, None, Some(1)),
+        ValidatorDelegatorsResponse {
+            delegations: vec![delegation(&delegator1, &validator1, 100, 200)],
+            last_delegator: Some(delegator1.clone()),
+        }
+    );
+    assert_eq!(
+        validator_delegators(
+            deps.as_ref(),
+            delegated_height + 1,
+            Some(&delegator1),
+            Some(1)
+        ),
+        ValidatorDelegatorsResponse {
+            delegations: vec![delegation(&delegator2, &validator1, 300, 600)],
+            last_delegator: None,
+        }
+    );
+    // A page counts the delegators without a delegation at the height too
+    assert_eq!(
+        validator_delegators(deps.as_ref(), undelegated_height + 1, None, Some(1)),
+        ValidatorDelegatorsResponse {
+            delegations: vec![],
+            last_delegator: Some(delegator1.clone()),
+        }
+    );
+    assert_eq!(
+        validator_delegators(
+            deps.as_ref(),
+            undelegated_height + 1,
+            Some(&delegator1),
+            Some(1)
+        ),
+        ValidatorDelegatorsResponse {
+            delegations: vec![delegation(&delegator2, &validator1, 300, 600)],
+            last_delegator: None,
+        }
+    );
+
+    // The delegations to a validator removed later are still listed at earlier heights
+    env.block.height += 10;
+    DELEGATIONS
+        .remove(
+            deps.as_mut().storage,
+            (&delegator1, &validator2),
+            env.block.height,
+        )
+        .unwrap();
+    VALIDATORS
+        .remove(deps.as_mut().storage, &validator2, env.block.height)
+        .unwrap();
+    assert_eq!(
+        delegator_delegations(deps.as_ref(), &delegator1, undelegated_height + 1),
+        vec![delegation(&delegator1, &validator2, 50, 50)]
+    );
+    assert_eq!(
+        delegator_delegations(deps.as_ref(), &delegator1, env.block.height + 1),
+        vec![]
+    );
+}
+
+#[test]
+fn test_import_state() {
+    let mut deps = dependencies();
+    deps.api = deps.api.with_prefix("neutron");
+    let env = mock_env();
+
+    let owner = deps.api.addr_make("owner");
+    let delegator = deps.api.addr_make("delegator");
+    let msg = InstantiateMsg {
+        name: "Test DAO".to_string(),
+        description: "A test DAO contract".to_string(),
+        owner: owner.to_string(),
+        staking_proxy_info_contract_address: None,
+    };
+    instantiate(deps.as_mut(), env.clone(), message_info(&owner, &[]), msg).unwrap();
+
+    let oper_addr = Addr::unchecked("neutronvaloper1xyz");
+    deps.querier.with_validators(vec![CosmosValidator {
+        operator_address: oper_addr.to_string(),
+        consensus_pubkey: None,
+        status: 3, // Bonded
+        tokens: "2000".to_string(),
+        jailed: false,
+        delegator_shares: "1000".to_string(),
+        description: None,
+        unbonding_height: 0,
+        unbonding_time: None,
+        commission: None,
+        min_self_delegation: "1".to_string(),
+        unbonding_on_hold_ref_count: 0,
+        unbonding_ids: vec![],
+    }]);
+    deps.querier.with_delegations(HashMap::from([(
+        (delegator.to_string(), oper_addr.to_string()),
+        Uint128::new(100),
+    )]));
+
+    let validator = Validator {
+        oper_address: oper_addr.clone(),
+        total_tokens: Uint128::new(2000),
+        total_shares: Uint128::new(1000),
+    };
+    let delegation = Delegation {
+        delegator_address: delegator.clone(),
+        validator_address: oper_addr.clone(),
+        shares: Uint128::new(100),
+    };
+    let import_msg =
+        |validators: Vec<Validator>, delegations: Vec<Delegation>| ExecuteMsg::ImportState {
+            validators,
+            delegations,
+        };
+
+    // Only the owner can import
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        message_info(&delegator, &[]),
+        import_msg(vec![validator.clone()], vec![]),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    // Entries not matching the staking module are rejected
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        message_info(&owner, &[]),
+        import_msg(
+            vec![Validator {
+                total_tokens: Uint128::new(1000),
+                ..validator.clone()
+            }],
+            vec![],
+        ),
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::ImportedValidatorMismatch {
+            address: oper_addr.to_string(),
+        }
+    );
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        message_info(&owner, &[]),
+        import_msg(vec![], vec![delegation.clone()]),
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::ImportedDelegationValidatorNotFound {
+            delegator: delegator.to_string(),
+            validator: oper_addr.to_string(),
+        }
+    );
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        message_info(&owner, &[]),
+        import_msg(
+            vec![validator.clone()],
+            vec![Delegation {
+                shares: Uint128::new(50),
+                ..delegation.clone()
+            }],
+        ),
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::ImportedDelegationMismatch {
+            delegator: delegator.to_string(),
+            validator: oper_addr.to_string(),
+        }
+    );
+
+    // The import is done in batches
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        message_info(&owner, &[]),
+        import_msg(vec![validator.clone()], vec![]),
+    )
+    .unwrap();
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        message_info(&owner, &[]),
+        import_msg(vec![], vec![delegation.clone()]),
+    )
+    .unwrap();
+
+    assert_eq!(
+        VALIDATORS.load(deps.as_ref().storage, &oper_addr).unwrap(),
+        validator
+    );
+    assert_eq!(
+        DELEGATIONS
+            .load(deps.as_ref().storage, (&delegator, &oper_addr))
+            .unwrap(),
+        delegation
+    );
+    assert_eq!(
+        query_bonded_validators(deps.as_ref(), env.clone(), Some(env.block.height + 1)).unwrap(),
+        vec![oper_addr.clone()]
+    );
+    assert_eq!(
+        query_stake_at_height(
+            deps.as_ref(),
+            env.clone(),
+            delegator.to_string(),
+            Some(env.block.height + 1)
+        )
+        .unwrap(),
+        Uint128::new(200)
+    );
+
+    // After sealing, the import is closed
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        message_info(&delegator, &[]),
+        ExecuteMsg::SealImport {},
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        message_info(&owner, &[]),
+        ExecuteMsg::SealImport {},
+    )
+    .unwrap();
+    assert!(IMPORT_SEALED.load(deps.as_ref().storage).unwrap());
+
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        message_info(&owner, &[]),
+        import_msg(vec![validator], vec![delegation]),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::ImportSealed {});
+}
@@ -1,4 +1,6 @@
-use cosmwasm_std::{CheckedMultiplyFractionError, StdError};
+use cosmwasm_std::{
+    CheckedFromRatioError, CheckedMultiplyFractionError, Decimal, OverflowError, StdError,
+};
 use thiserror::Error;
 
 #[derive(Error, Debug, PartialEq)]
@@ -27,9 +29,61 @@ pub enum ContractError {
     #[error("Invalid annual reward rate bps: {bps} must be in [0;10000] range")]
     InvalidBPS { bps: u64 },
 
+    #[error("Reward rate schedule segments must have strictly increasing start heights")]
+    UnsortedRateSchedule {},
+
+    #[error("Reward rate schedule segment starts in the past: {start_height}")]
+    RateSegmentInThePast { start_height: u64 },
+
+    #[error("Invalid validator multiplier: {multiplier} must be in [0;10] range")]
+    InvalidValidatorMultiplier { multiplier: Decimal },
+
+    #[error("Too many vesting entries: at most {max} claims can be vesting at a time")]
+    TooManyVestingEntries { max: u32 },
+
+    #[error("Too many users: at most {max} users can be compounded at a time")]
+    TooManyCompoundUsers { max: u32 },
+
+    #[error("Auto-compounding is disabled while claimed rewards vest")]
+    AutoCompoundWithVesting {},
+
+    #[error("Too many users: at most {max} reward multipliers can be refreshed at a time")]
+    TooManyRefreshUsers { max: u32 },
+
+    #[error("Validator not found: {address}")]
+    ValidatorNotFound { address: String },
+
+    #[error("Invalid height range: from_height must not exceed to_height")]
+    InvalidHeightRange {},
+
+    #[error("Reward history is not available at height {height}")]
+    HistoryNotAvailable { height: u64 },
+
     #[error("CheckedMultiplyRatioError error: {0}")]
     CheckedMultiplyFractionError(#[from] CheckedMultiplyFractionError),
 
+    #[error("CheckedFromRatioError error: {0}")]
+    CheckedFromRatioError(#[from] CheckedFromRatioError),
+
+    #[error("{0}")]
+    OverflowError(#[from] OverflowError),
+
+    #[error("Exactly one non-zero coin must be sent to fund a campaign")]
+    InvalidCampaignFunds {},
+
+    #[error(
+        "Invalid campaign heights: must start at {current_height} or later and end after start"
+    )]
+    InvalidCampaignHeights { current_height: u64 },
+
+    #[error(
+        "Insufficient campaign funds: a campaign must be funded with at least one of [{min_funds}]"
+    )]
+    InsufficientCampaignFunds { min_funds: String },
+
+    #[error("Too many active campaigns: at most {max} campaigns can be active at a time")]
+    TooManyActiveCampaigns { max: u32 },
+
     #[error("TriedGetGlobalIndexInThePast error: current height is {current_block}, height to update is {last_global_update_block}")]
     TriedGetGlobalIndexInThePast {
         current_block: u64,
@@ -38,4 +92,10 @@ pub enum ContractError {
 
     #[error("Action is denied, the contract is on pause temporarily")]
     ContractPaused {},
+
+    #[error("No totals backfill is in progress")]
+    NoTotalsBackfill {},
+
+    #[error("The contract can't be unpaused until the totals are backfilled")]
+    TotalsBackfillInProgress {},
 }
@@ -1,7 +1,10 @@
 use cosmwasm_schema::{cw_serde, QueryResponses};
-use cosmwasm_std::Coin;
+use cosmwasm_std::{Coin, Decimal, Uint128};
 
-use crate::types::SlashingEvent;
+use crate::types::{
+    AutoCompoundSettings, ClaimSettings, RateSegment, RewardCampaign, SlashingEvent,
+    ValidatorMultiplier, VestingEntry,
+};
 
 #[cw_serde]
 pub struct InstantiateMsg {
@@ -12,6 +15,20 @@ pub struct InstantiateMsg {
     pub staking_info_proxy: String,
     pub staking_denom: String,
     pub security_address: String,
+    /// An optional schedule of reward rate changes, sorted by start height.
+    pub reward_rate_schedule: Option<Vec<RateSegment>>,
+    /// Whether to pause reward accrual while the contract is insolvent.
+    pub pause_accrual_on_insolvency: Option<bool>,
+    /// The staking tracker used to compute the users' reward multipliers.
+    pub staking_tracker: Option<String>,
+    /// The number of blocks claimed staking rewards unlock linearly over, zero by default,
+    /// i.e. the rewards are paid out immediately.
+    pub claim_vesting_blocks: Option<u64>,
+    /// Whether unstaking forfeits the locked rewards to the DAO.
+    pub forfeit_unvested_on_unstake: Option<bool>,
+    /// The denoms campaigns can be funded with, along with their minimum funding amounts.
+    /// No campaigns can be created by default.
+    pub campaign_min_funds: Option<Vec<Coin>>,
 }
 
 #[cw_serde]
@@ -24,20 +41,80 @@ pub enum ExecuteMsg {
         staking_info_proxy: Option<String>,
         staking_denom: Option<String>,
         security_address: Option<String>,
+        /// Replaces the schedule of future reward rate changes.
+        reward_rate_schedule: Option<Vec<RateSegment>>,
+        pause_accrual_on_insolvency: Option<bool>,
+        staking_tracker: Option<String>,
+        /// Applies to the claims made after the update.
+        claim_vesting_blocks: Option<u64>,
+        forfeit_unvested_on_unstake: Option<bool>,
+        /// Applies to the campaigns created after the update.
+        campaign_min_funds: Option<Vec<Coin>>,
     },
     /// Called by the (authorized) Staking Info Proxy whenever a user’s stake changes.
     UpdateStake { user: String },
     /// Called by the (authorized) Staking Info Proxy whenever a validator gets slashed.
     Slashing {},
     /// Called by a user to claim their accrued rewards. Allows to specify an optional
-    /// address to which the rewards should be sent.
+    /// address to which the rewards should be sent, the user's reward recipient is used otherwise.
     ClaimRewards { to_address: Option<String> },
+    /// Called by a user to withdraw their unlocked vesting rewards. Allows to specify an optional
+    /// address to which the rewards should be sent, the user's reward recipient is used otherwise.
+    WithdrawVested { to_address: Option<String> },
+    /// Called by the authorized claimer of a user to claim the user's rewards, which are sent
+    /// to the user's reward recipient.
+    ClaimRewardsFor { user: String },
+    /// Sets the address the caller's rewards are sent to by default, or resets it to the caller.
+    SetRewardRecipient { recipient: Option<String> },
+    /// Sets the address allowed to claim the caller's rewards on their behalf, or removes it.
+    SetAuthorizedClaimer { claimer: Option<String> },
+    /// Opts the caller in or out of auto-compounding. The caller's staking rewards are delegated
+    /// to `validator` if specified, to the bonded validators the caller delegates to otherwise.
+    /// The caller must grant the contract an authz authorization to delegate on their behalf.
+    /// Users can't opt in while `claim_vesting_blocks` is set.
+    SetAutoCompound {
+        enabled: bool,
+        validator: Option<String>,
+    },
+    /// Claims the staking rewards of the given opted-in users and delegates them on the users'
+    /// behalf. Can be called by anyone, and fails while `claim_vesting_blocks` is set.
+    Compound { users: Vec<String> },
+    /// Sends the compounded rewards to the user and delegates them to the given validators on
+    /// the user's behalf, so that a failed delegation reverts the transfer. Can only be called
+    /// by the contract itself.
+    DelegateCompounded {
+        user: String,
+        delegations: Vec<(String, Uint128)>,
+    },
 
     /// Pauses the contract, can be called either by the owner or security_address
     Pause {},
 
     /// Unpauses the contract, can be called either by the owner or security_address
     Unpause {},
+
+    /// Sets the reward multipliers of validators, a multiplier of one removes the boost.
+    /// Must be called by `owner`. The users' multipliers are recomputed on their next stake
+    /// update or with `RefreshRewardMultipliers`.
+    SetValidatorMultipliers {
+        multipliers: Vec<ValidatorMultiplier>,
+    },
+    /// Recomputes the reward multipliers of the given users from their current delegations,
+    /// settling the rewards accrued so far with the previous multipliers. Can be called by
+    /// anyone.
+    RefreshRewardMultipliers { users: Vec<String> },
+    /// Adds the next `limit` users to the totals computed after the migration from the version
+    /// that didn't track them, and restores the pause state once all of them are added. Can be
+    /// called by anyone.
+    BackfillTotals { limit: Option<u32> },
+
+    /// Creates a reward campaign distributing the sent coin pro-rata to stake between
+    /// `start_height` and `end_height`. Can be called by anyone with at least the minimum
+    /// funds of an allowed denom.
+    CreateCampaign { start_height: u64, end_height: u64 },
+    /// Sends the campaign rewards of the blocks without any stake back to the funder. Must be
+    /// called by the funder of the campaign.
+    ReclaimCampaignFunds { id: u64 },
 }
 
 #[cw_serde]
@@ -54,12 +131,69 @@ pub enum QueryMsg {
     /// Returns the user's current pending rewards.
     #[returns(RewardsResponse)]
     Rewards { user: String },
-    // Returns slashing events recorded by the contract
+    /// Returns the staking rewards accrued to the user after `from_height` up to and including
+    /// `to_height`, whether claimed or not.
+    #[returns(RewardsAccruedResponse)]
+    RewardsAccrued {
+        user: String,
+        from_height: u64,
+        to_height: u64,
+    },
+    /// Returns the global reward index at the end of the given block.
+    #[returns(GlobalIndexResponse)]
+    GlobalIndexAtHeight { height: u64 },
+    /// Returns the user's vesting rewards: the unlocked ones that can be withdrawn and the
+    /// locked ones.
+    #[returns(VestedRewardsResponse)]
+    VestedRewards { user: String },
+    /// Returns the user's vesting entries ordered by claim height.
+    #[returns(VestingEntriesResponse)]
+    VestingEntries {
+        user: String,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+    /// Returns the user's reward recipient and authorized claimer.
+    #[returns(ClaimSettings)]
+    ClaimSettings { user: String },
+    /// Returns the user's auto-compound settings along with the last compounding result, or
+    /// `None` if the user isn't opted in.
+    #[returns(Option<AutoCompoundSettings>)]
+    AutoCompound { user: String },
+    // Returns slashing events recorded by the contract at `from_height` or later, ordered by
+    // height. The next page starts at the height of the last returned event plus one.
     #[returns(SlashingEventsResponse)]
-    SlashingEvents { from_height: u64 },
+    SlashingEvents {
+        from_height: u64,
+        limit: Option<u32>,
+    },
     /// Returns true if the contract is paused, false otherwise
     #[returns(bool)]
     IsPaused {},
+    /// Returns the annual reward rate in effect at the given height (the current
+    /// height by default), according to the reward rate schedule at that height.
+    /// Future heights use the current schedule.
+    #[returns(RewardRateResponse)]
+    RewardRate { height: Option<u64> },
+    /// Returns the balance available to pay staking rewards, the accrued rewards, and the
+    /// estimated number of blocks until the accrued rewards exceed the balance.
+    #[returns(SolvencyResponse)]
+    Solvency {},
+    /// Returns a reward campaign by its id.
+    #[returns(RewardCampaign)]
+    Campaign { id: u64 },
+    /// Returns reward campaigns ordered by id.
+    #[returns(CampaignsResponse)]
+    Campaigns {
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+    /// Returns the validator reward multipliers ordered by validator address.
+    #[returns(ValidatorMultipliersResponse)]
+    ValidatorMultipliers {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
 }
 
 /// Response for `QueryMsg::Config`
@@ -71,6 +205,12 @@ pub struct ConfigResponse {
     pub annual_reward_rate_bps: u64,
     pub blocks_per_year: u64,
     pub staking_denom: String,
+    pub reward_rate_schedule: Vec<RateSegment>,
+    pub pause_accrual_on_insolvency: bool,
+    pub staking_tracker: Option<String>,
+    pub claim_vesting_blocks: u64,
+    pub forfeit_unvested_on_unstake: bool,
+    pub campaign_min_funds: Vec<Coin>,
 }
 
 /// Response for `QueryMsg::State`
@@ -86,10 +226,74 @@ pub struct SlashingEventsResponse {
     pub slashing_events: Vec<SlashingEvent>,
 }
 
+/// Response for `QueryMsg::RewardRate`
+#[cw_serde]
+pub struct RewardRateResponse {
+    pub height: u64,
+    pub annual_reward_rate_bps: u64,
+}
+
+/// Response for `QueryMsg::Solvency`
+#[cw_serde]
+pub struct SolvencyResponse {
+    /// The contract balance in the staking denom, excluding unclaimed campaign funds.
+    pub balance: Uint128,
+    /// Rewards accrued to users but not claimed yet.
+    pub liabilities: Uint128,
+    /// The number of blocks until the liabilities exceed the balance at the current rate and
+    /// stake, or `None` if no rewards accrue.
+    pub runway_blocks: Option<u64>,
+    pub accrual_paused: bool,
+}
+
+/// Response for `QueryMsg::Campaigns`
+#[cw_serde]
+pub struct CampaignsResponse {
+    pub campaigns: Vec<RewardCampaign>,
+}
+
+/// Response for `QueryMsg::RewardsAccrued`
+#[cw_serde]
+pub struct RewardsAccruedResponse {
+    pub from_height: u64,
+    pub to_height: u64,
+    pub rewards: Coin,
+}
+
+/// Response for `QueryMsg::GlobalIndexAtHeight`
+#[cw_serde]
+pub struct GlobalIndexResponse {
+    pub height: u64,
+    pub global_reward_index: Decimal,
+}
+
+/// Response for `QueryMsg::VestedRewards`
+#[cw_serde]
+pub struct VestedRewardsResponse {
+    pub withdrawable: Coin,
+    pub locked: Coin,
+}
+
+/// Response for `QueryMsg::VestingEntries`
+#[cw_serde]
+pub struct VestingEntriesResponse {
+    pub entries: Vec<VestingEntry>,
+}
+
+/// Response for `QueryMsg::ValidatorMultipliers`
+#[cw_serde]
+pub struct ValidatorMultipliersResponse {
+    pub multipliers: Vec<ValidatorMultiplier>,
+}
+
 /// Response for `QueryMsg::Rewards`
 #[cw_serde]
 pub struct RewardsResponse {
     pub pending_rewards: Coin,
+    /// Pending rewards of reward campaigns, aggregated by denom.
+    pub campaign_rewards: Vec<Coin>,
+    /// The multiplier applied to the user's stake for the staking rewards.
+    pub reward_multiplier: Decimal,
 }
 
 #[cw_serde]
@@ -1,6 +1,6 @@
 use crate::error::ContractError;
 use cosmwasm_schema::cw_serde;
-use cosmwasm_std::{Addr, Coin, Decimal};
+use cosmwasm_std::{Addr, CheckedMultiplyFractionError, Coin, Decimal, Uint128};
 
 /// Configuration.
 #[cw_serde]
@@ -13,6 +13,36 @@ pub struct Config {
     pub staking_denom: String,
     // an address that is allowed to pause/unpause the contract
     pub security_address: Addr,
+    // segments of the reward rate schedule, sorted by start height;
+    // `annual_reward_rate_bps` applies before the first segment
+    #[serde(default)]
+    pub reward_rate_schedule: Vec<RateSegment>,
+    // pause reward accrual while the accrued rewards exceed the funds available to pay them
+    #[serde(default)]
+    pub pause_accrual_on_insolvency: bool,
+    // the staking tracker whose delegations are used to compute the users' reward multipliers
+    #[serde(default)]
+    pub staking_tracker: Option<Addr>,
+    // the number of blocks claimed staking rewards unlock linearly over, zero pays them out
+    // immediately
+    #[serde(default)]
+    pub claim_vesting_blocks: u64,
+    // forfeit the locked part of the vesting rewards to the DAO in proportion to the unstaked
+    // tokens
+    #[serde(default)]
+    pub forfeit_unvested_on_unstake: bool,
+    // the denoms campaigns can be funded with, along with their minimum funding amounts; no
+    // campaigns can be created if empty
+    #[serde(default)]
+    pub campaign_min_funds: Vec<Coin>,
+}
+
+/// A segment of the reward rate schedule: starting from `start_height`, rewards
+/// accrue at `rate_bps` annually until the start of the next segment.
+#[cw_serde]
+pub struct RateSegment {
+    pub start_height: u64,
+    pub rate_bps: u64,
 }
 
 impl Config {
@@ -31,31 +61,76 @@ impl Config {
             });
         }
 
+        for segment in self.reward_rate_schedule.iter() {
+            if segment.rate_bps > 10_000 {
+                return Err(ContractError::InvalidBPS {
+                    bps: segment.rate_bps,
+                });
+            }
+        }
+
+        if self
+            .reward_rate_schedule
+            .windows(2)
+            .any(|w| w[0].start_height >= w[1].start_height)
+        {
+            return Err(ContractError::UnsortedRateSchedule {});
+        }
+
         Ok(())
     }
+
+    /// Returns the annual reward rate in bps in effect at the given height.
+    pub fn reward_rate_bps_at(&self, height: u64) -> u64 {
+        self.reward_rate_schedule
+            .iter()
+            .rev()
+            .find(|segment| segment.start_height <= height)
+            .map_or(self.annual_reward_rate_bps, |segment| segment.rate_bps)
+    }
+
+    /// Returns the height of the first rate change after the given height, if any.
+    pub fn next_rate_change_height(&self, height: u64) -> Option<u64> {
+        self.reward_rate_schedule
+            .iter()
+            .find(|segment| segment.start_height > height)
+            .map(|segment| segment.start_height)
+    }
+
+    /// Makes the rate of the last started segment the base rate, and removes the
+    /// started segments from the schedule.
+    pub fn apply_started_rate_segments(&mut self, height: u64) {
+        self.annual_reward_rate_bps = self.reward_rate_bps_at(height);
+        self.reward_rate_schedule
+            .retain(|segment| segment.start_height > height);
+    }
+}
+
+/// The largest reward multiplier that can be set for a validator.
+pub const MAX_VALIDATOR_MULTIPLIER: u64 = 10;
+
+/// A reward multiplier applied to the stake delegated to a validator.
+#[cw_serde]
+pub struct ValidatorMultiplier {
+    /// The validator operator address.
+    pub validator: String,
+    pub multiplier: Decimal,
 }
 
 pub type SlashingEvent = (GlobalRewardIndex, u64);
-type GlobalRewardIndex = Decimal;
+pub type GlobalRewardIndex = Decimal;
 
 /// Frequently updated reward-related data.
 #[cw_serde]
 pub struct State {
     pub global_reward_index: GlobalRewardIndex,
     pub global_update_height: u64,
-    pub slashing_events: Vec<SlashingEvent>,
-}
-
-impl State {
-    pub fn load_unprocessed_slashing_events(&self, from_height: u64) -> Vec<SlashingEvent> {
-        let events = self
-            .slashing_events
-            .iter()
-            .skip_while(|&&(_, event_height)| event_height < from_height)
-            .cloned()
-            .collect();
-        events
-    }
+    /// Rewards accrued to users but not claimed yet, in the staking denom.
+    #[serde(default)]
+    pub total_accrued_rewards: Decimal,
+    /// Whether reward accrual is paused because the contract is insolvent.
+    #[serde(default)]
+    pub accrual_paused: bool,
 }
 
 /// Per-user info about stake, reward index, and accrued rewards.
@@ -65,6 +140,113 @@ pub struct UserInfo {
     pub user_reward_index: Decimal,
     pub last_update_block: u64,
     pub pending_rewards: Coin,
+    /// The multiplier of the user's stake computed from the validator multipliers at the last
+    /// stake update, `None` if the stake is not boosted.
+    #[serde(default)]
+    pub reward_multiplier: Option<Decimal>,
+    /// Staking rewards accrued to the user since the reward history is recorded, including the
+    /// claimed ones.
+    #[serde(default)]
+    pub cumulative_rewards: Uint128,
+}
+
+impl UserInfo {
+    /// Returns the stake the staking rewards accrue on, i.e. the stake with the reward
+    /// multiplier applied.
+    pub fn reward_weight(&self) -> Result<Uint128, CheckedMultiplyFractionError> {
+        match self.reward_multiplier {
+            Some(multiplier) => self.stake.amount.checked_mul_floor(multiplier),
+            None => Ok(self.stake.amount),
+        }
+    }
+}
+
+/// Per-user claim settings.
+#[cw_serde]
+pub struct ClaimSettings {
+    /// The address the user's rewards are sent to, unless a claim specifies another one.
+    pub recipient: Option<Addr>,
+    /// The address allowed to claim the user's rewards on their behalf. The rewards claimed by it
+    /// are sent to the recipient.
+    pub claimer: Option<Addr>,
+}
+
+/// Claimed staking rewards unlocking linearly from the claim until `end_height`.
+#[cw_serde]
+pub struct VestingEntry {
+    /// The height of the claim.
+    pub start_height: u64,
+    pub end_height: u64,
+    /// Rewards that haven't unlocked yet.
+    pub locked: Uint128,
+    /// Unlocked rewards that haven't been withdrawn yet.
+    pub vested: Uint128,
+    /// The height `locked` and `vested` are computed at.
+    pub update_height: u64,
+}
+
+impl VestingEntry {
+    /// Unlocks the rewards linearly up to the given height.
+    pub fn vest(&mut self, height: u64) {
+        let to_height = height.min(self.end_height);
+        if to_height <= self.update_height {
+            return;
+        }
+
+        let newly_vested = self.locked.multiply_ratio(
+            to_height - self.update_height,
+            self.end_height - self.update_height,
+        );
+        self.locked -= newly_vested;
+        self.vested += newly_vested;
+        self.update_height = to_height;
+    }
+}
+
+/// Per-user auto-compound settings, the user is opted in while they exist.
+#[cw_serde]
+pub struct AutoCompoundSettings {
+    /// The validator the rewards are delegated to. If not set, the rewards are split between the
+    /// bonded validators the user delegates to, pro-rata to the delegated tokens.
+    pub validator: Option<Addr>,
+    /// The result of the last compounding of the user's rewards.
+    pub last_result: Option<CompoundResult>,
+}
+
+/// The result of compounding a user's rewards.
+#[cw_serde]
+pub struct CompoundResult {
+    pub height: u64,
+    /// The staking rewards claimed to be delegated.
+    pub amount: Uint128,
+    /// The reason the rewards weren't delegated. The claimed rewards are left pending if the
+    /// delegation failed.
+    pub error: Option<String>,
+}
+
+/// A reward stream in an arbitrary denom, distributed pro-rata to stake over a height range.
+#[cw_serde]
+pub struct RewardCampaign {
+    pub id: u64,
+    pub funder: Addr,
+    /// The total amount distributed over the campaign.
+    pub reward: Coin,
+    pub start_height: u64,
+    pub end_height: u64,
+    /// Rewards accrued per unit of stake since the campaign start.
+    pub reward_index: Decimal,
+    /// The height up to which `reward_index` is calculated.
+    pub last_update_height: u64,
+    /// Rewards of the blocks without any stake that the funder hasn't reclaimed yet.
+    #[serde(default)]
+    pub undistributed: Uint128,
+}
+
+/// Per-user reward data of a campaign.
+#[cw_serde]
+pub struct UserCampaignRewards {
+    pub reward_index: Decimal,
+    pub pending_rewards: Uint128,
 }
 
 #[cfg(test)]
@@ -90,6 +272,12 @@ mod tests {
                     annual_reward_rate_bps: 500, // 5%
                     blocks_per_year: 5_256_000,  // Approximately 6 seconds per block
                     staking_denom: "ustake".to_string(),
+                    reward_rate_schedule: vec![],
+                    pause_accrual_on_insolvency: false,
+                    staking_tracker: None,
+                    claim_vesting_blocks: 0,
+                    forfeit_unvested_on_unstake: false,
+                    campaign_min_funds: vec![],
                 },
                 expected_result: Ok(()),
             },
@@ -103,6 +291,12 @@ mod tests {
                     annual_reward_rate_bps: 500,
                     blocks_per_year: 5_256_000,
                     staking_denom: "".to_string(),
+                    reward_rate_schedule: vec![],
+                    pause_accrual_on_insolvency: false,
+                    staking_tracker: None,
+                    claim_vesting_blocks: 0,
+                    forfeit_unvested_on_unstake: false,
+                    campaign_min_funds: vec![],
                 },
                 expected_result: Err(ContractError::EmptyStakeDenom {}),
             },
@@ -116,6 +310,12 @@ mod tests {
                     annual_reward_rate_bps: 500,
                     blocks_per_year: 0,
                     staking_denom: "ustake".to_string(),
+                    reward_rate_schedule: vec![],
+                    pause_accrual_on_insolvency: false,
+                    staking_tracker: None,
+                    claim_vesting_blocks: 0,
+                    forfeit_unvested_on_unstake: false,
+                    campaign_min_funds: vec![],
                 },
                 expected_result: Err(ContractError::ZeroBlocksPerYear {}),
             },
@@ -129,6 +329,12 @@ mod tests {
                     annual_reward_rate_bps: 12_000,
                     blocks_per_year: 5_256_000,
                     staking_denom: "ustake".to_string(),
+                    reward_rate_schedule: vec![],
+                    pause_accrual_on_insolvency: false,
+                    staking_tracker: None,
+                    claim_vesting_blocks: 0,
+                    forfeit_unvested_on_unstake: false,
+                    campaign_min_funds: vec![],
                 },
                 expected_result: Err(ContractError::InvalidBPS { bps: 12_000 }),
             },
@@ -142,9 +348,65 @@ mod tests {
                     annual_reward_rate_bps: 10_000,
                     blocks_per_year: 5_256_000,
                     staking_denom: "ustake".to_string(),
+                    reward_rate_schedule: vec![],
+                    pause_accrual_on_insolvency: false,
+                    staking_tracker: None,
+                    claim_vesting_blocks: 0,
+                    forfeit_unvested_on_unstake: false,
+                    campaign_min_funds: vec![],
                 },
                 expected_result: Ok(()),
             },
+            TestCase {
+                name: "invalid BPS in the reward rate schedule",
+                config: Config {
+                    owner: Addr::unchecked("owner"),
+                    dao_address: Addr::unchecked("dao"),
+                    security_address: Addr::unchecked("security"),
+                    staking_info_proxy: Addr::unchecked("proxy"),
+                    annual_reward_rate_bps: 500,
+                    blocks_per_year: 5_256_000,
+                    staking_denom: "ustake".to_string(),
+                    reward_rate_schedule: vec![RateSegment {
+                        start_height: 100,
+                        rate_bps: 10_001,
+                    }],
+                    pause_accrual_on_insolvency: false,
+                    staking_tracker: None,
+                    claim_vesting_blocks: 0,
+                    forfeit_unvested_on_unstake: false,
+                    campaign_min_funds: vec![],
+                },
+                expected_result: Err(ContractError::InvalidBPS { bps: 10_001 }),
+            },
+            TestCase {
+                name: "unsorted reward rate schedule",
+                config: Config {
+                    owner: Addr::unchecked("owner"),
+                    dao_address: Addr::unchecked("dao"),
+                    security_address: Addr::unchecked("security"),
+                    staking_info_proxy: Addr::unchecked("proxy"),
+                    annual_reward_rate_bps: 500,
+                    blocks_per_year: 5_256_000,
+                    staking_denom: "ustake".to_string(),
+                    reward_rate_schedule: vec![
+                        RateSegment {
+                            start_height: 200,
+                            rate_bps: 400,
+                        },
+                        RateSegment {
+                            start_height: 200,
+                            rate_bps: 300,
+                        },
+                    ],
+                    pause_accrual_on_insolvency: false,
+                    staking_tracker: None,
+                    claim_vesting_blocks: 0,
+                    forfeit_unvested_on_unstake: false,
+                    campaign_min_funds: vec![],
+                },
+                expected_result: Err(ContractError::UnsortedRateSchedule {}),
+            },
             TestCase {
                 name: "minimum valid blocks per year (1)",
                 config: Config {
@@ -155,6 +417,12 @@ mod tests {
                     annual_reward_rate_bps: 500,
                     blocks_per_year: 1,
                     staking_denom: "ustake".to_string(),
+                    reward_rate_schedule: vec![],
+                    pause_accrual_on_insolvency: false,
+                    staking_tracker: None,
+                    claim_vesting_blocks: 0,
+                    forfeit_unvested_on_unstake: false,
+                    campaign_min_funds: vec![],
                 },
                 expected_result: Ok(()),
             },
@@ -53,4 +53,25 @@ pub enum ContractError {
 
     #[error("ValidatorNotBonded: {address}")]
     ValidatorNotBonded { address: String },
+
+    #[error("Unbonding time not found in staking module params.")]
+    UnbondingTimeNotFound {},
+
+    #[error("State import is sealed.")]
+    ImportSealed {},
+
+    #[error("Imported validator doesn't match the staking module: {address}")]
+    ImportedValidatorMismatch { address: String },
+
+    #[error("Imported delegation doesn't match the staking module: {delegator} to {validator}")]
+    ImportedDelegationMismatch {
+        delegator: String,
+        validator: String,
+    },
+
+    #[error("Imported delegation's validator hasn't been imported: {delegator} to {validator}")]
+    ImportedDelegationValidatorNotFound {
+        delegator: String,
+        validator: String,
+    },
 }
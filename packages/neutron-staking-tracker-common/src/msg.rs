@@ -1,4 +1,4 @@
-use crate::types::{Delegation, Validator};
+use crate::types::{Delegation, UnbondingEntry, Validator, ValidatorSlash, ValidatorStatus};
 use cosmwasm_schema::{cw_serde, QueryResponses};
 use cosmwasm_std::{Addr, Decimal256, Uint128};
 use schemars::JsonSchema;
@@ -24,6 +24,37 @@ pub enum ExecuteMsg {
         owner: Option<String>,
         staking_proxy_info_contract_address: Option<String>,
     },
+
+    /// Imports a batch of validators and delegations, as returned by the staking module queries,
+    /// to bootstrap the contract on a chain with existing stake. Every entry is checked against
+    /// the live staking module state, so the whole batch fails if any entry is outdated.
+    /// Validators have to be imported before their delegations, in an earlier or the same batch.
+    /// Only the owner can import, and only until the import is sealed.
+    ImportState {
+        validators: Vec<Validator>,
+        delegations: Vec<Delegation>,
+    },
+
+    /// Seals the import, after which the state is only updated by the staking hooks.
+    /// Only the owner can seal the import.
+    SealImport {},
+
+    /// Indexes by validator and by delegator the delegations stored before
+    /// `QueryMsg::ValidatorDelegators` was added, up to `limit` delegation history entries after
+    /// `start_after` (a delegator and validator pair) at a time. Can be called by anyone, and
+    /// should be called after the migration until `backfill_pending` is `false`, passing the
+    /// `last_delegator` and `last_validator` attributes as `start_after`.
+    BackfillValidatorDelegators {
+        start_after: Option<(String, String)>,
+        limit: Option<u32>,
+    },
+
+    /// Continues updating the unbonding entries of a slashed validator that the slashing hook
+    /// didn't get to, up to `limit` delegators at a time. Can be called by anyone.
+    SyncUnbondingEntries {
+        validator: String,
+        limit: Option<u32>,
+    },
 }
 
 #[cw_serde]
@@ -71,6 +102,10 @@ pub enum QueryMsg {
     #[returns(crate::types::Config)]
     Config {},
 
+    /// Returns whether the state import is sealed.
+    #[returns(bool)]
+    ImportSealed {},
+
     /// Gets the staked (bonded) tokens for given `address` at given `height`.
     /// Stake of unbonded validators does not count.
     #[returns(Uint128)]
@@ -91,12 +126,100 @@ pub enum QueryMsg {
         limit: Option<u32>,
     },
 
+    /// Returns the operator addresses of the bonded validators at given `height`.
+    #[returns(Vec<Addr>)]
+    BondedValidators { height: Option<u64> },
+
+    /// Returns the validator at given `height`, or `None` if the validator didn't exist at that
+    /// height.
+    #[returns(Option<Validator>)]
+    Validator {
+        validator: String,
+        height: Option<u64>,
+    },
+
     /// Returns list of all validators.
     #[returns(Vec<Validator>)]
     ListValidators {
         start_after: Option<Addr>,
         limit: Option<u32>,
     },
+
+    /// Returns the validator along with its status at given `height`, or `None` if the validator
+    /// didn't exist at that height. An unbonding validator is reported unbonded at the current
+    /// height once its `unbonding_completion_time` has passed.
+    #[returns(Option<ValidatorAtHeightResponse>)]
+    ValidatorAtHeight {
+        validator: String,
+        height: Option<u64>,
+    },
+
+    /// Returns the slashings of given `validator` ordered by height.
+    #[returns(Vec<ValidatorSlash>)]
+    ValidatorSlashes {
+        validator: String,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+
+    /// Returns the delegations of given `delegator` at given `height`, ordered by validator, with
+    /// the shares converted to tokens at the validator's exchange rate at that height.
+    /// Delegations to unbonded validators are included.
+    #[returns(Vec<DelegationTokens>)]
+    DelegatorDelegations {
+        delegator: String,
+        height: Option<u64>,
+    },
+
+    /// Returns the delegations to given `validator` at given `height`, ordered by delegator, with
+    /// the shares converted to tokens at the validator's exchange rate at that height.
+    /// Every page reads up to `limit` delegators that have ever delegated to the validator, so a
+    /// page can hold fewer delegations than that. The next page starts after `last_delegator`.
+    #[returns(ValidatorDelegatorsResponse)]
+    ValidatorDelegators {
+        validator: String,
+        height: Option<u64>,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+
+    /// Returns the slashed validators whose unbonding entries still have to be updated with
+    /// `ExecuteMsg::SyncUnbondingEntries`.
+    #[returns(Vec<Addr>)]
+    PendingUnbondingSyncs {
+        start_after: Option<Addr>,
+        limit: Option<u32>,
+    },
+
+    /// Returns the unbonding entries of given `delegator` at given `height`, ordered by
+    /// creation height. Entries that have matured are left out at the current height.
+    #[returns(Vec<UnbondingEntry>)]
+    UnbondingEntries {
+        delegator: String,
+        height: Option<u64>,
+    },
+}
+
+#[cw_serde]
+pub struct DelegationTokens {
+    pub delegator: Addr,
+    pub validator: Addr,
+    pub shares: Uint128,
+    pub tokens: Uint128,
+}
+
+/// - `delegations`: The delegations of the page.
+/// - `last_delegator`: The last delegator read, or `None` if there are no more delegators.
+#[cw_serde]
+pub struct ValidatorDelegatorsResponse {
+    pub delegations: Vec<DelegationTokens>,
+    pub last_delegator: Option<Addr>,
+}
+
+#[cw_serde]
+pub struct ValidatorAtHeightResponse {
+    pub validator: Validator,
+    pub status: ValidatorStatus,
 }
 
 #[cw_serde]
@@ -1,5 +1,5 @@
 use crate::error::ContractError;
-use cosmwasm_std::{Addr, Uint128};
+use cosmwasm_std::{Addr, Decimal256, Timestamp, Uint128};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
@@ -73,22 +73,58 @@ pub struct Validator {
 }
 
 impl Validator {
-    pub fn remove_del_shares(&mut self, shares: Uint128) -> Result<(), ContractError> {
+    /// Removes the delegator shares from the validator and returns the undelegated tokens.
+    pub fn remove_del_shares(&mut self, shares: Uint128) -> Result<Uint128, ContractError> {
         let remaining_shares = self.total_shares.checked_sub(shares)?;
 
-        if remaining_shares.is_zero() {
-            self.total_tokens = Uint128::zero();
+        let undelegated_tokens = if remaining_shares.is_zero() {
+            self.total_tokens
         } else {
-            let undelegated_tokens = shares.multiply_ratio(self.total_tokens, self.total_shares);
-            self.total_tokens = self.total_tokens.checked_sub(undelegated_tokens)?;
-        }
+            shares.multiply_ratio(self.total_tokens, self.total_shares)
+        };
+        self.total_tokens = self.total_tokens.checked_sub(undelegated_tokens)?;
 
         self.total_shares = remaining_shares;
 
-        Ok(())
+        Ok(undelegated_tokens)
     }
 }
 
+/// The bond status of a validator in the staking module.
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, JsonSchema, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum BondStatus {
+    Unbonded,
+    Unbonding,
+    Bonded,
+}
+
+/// Represents the status of a validator.
+///
+/// - `bond_status`: Whether the validator is bonded, unbonding or unbonded.
+/// - `jailed`: Whether the validator is jailed. Jailed validators are unbonding or unbonded.
+/// - `unbonding_completion_time`: The time an unbonding validator becomes unbonded. The staking
+///   module doesn't call a hook at that point, so `bond_status` is only recorded as `Unbonded`
+///   when the validator is removed, and is otherwise derived from this time at the current height.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, JsonSchema, Debug)]
+pub struct ValidatorStatus {
+    pub bond_status: BondStatus,
+    pub jailed: bool,
+    pub unbonding_completion_time: Option<Timestamp>,
+}
+
+/// Represents a slashing of a validator.
+///
+/// - `height`: The height the validator got slashed at.
+/// - `fraction`: The effective fraction of the validator's tokens slashed.
+/// - `tokens_burned`: The validator's tokens burned by the slashing.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, JsonSchema, Debug)]
+pub struct ValidatorSlash {
+    pub height: u64,
+    pub fraction: Decimal256,
+    pub tokens_burned: Uint128,
+}
+
 /// Represents a delegation made by a user to a validator.
 ///
 /// A delegation means that a **delegator** (user) has assigned their stake to a **validator**.
@@ -115,6 +151,24 @@ pub struct Delegation {
     pub shares: Uint128,
 }
 
+/// Represents tokens a delegator is unbonding from a validator.
+///
+/// Unbonding tokens don't count as stake, but they can still be slashed for infractions
+/// committed before `creation_height` until `completion_time`.
+/// - `validator_address`: The operator address (`valoper`) of the validator the tokens unbond from.
+/// - `creation_height`: The height the unbonding started at.
+/// - `completion_time`: The time the tokens are returned to the delegator.
+/// - `initial_balance`: The unbonding tokens at `creation_height`.
+/// - `balance`: The unbonding tokens left after slashing.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, JsonSchema, Debug)]
+pub struct UnbondingEntry {
+    pub validator_address: Addr,
+    pub creation_height: u64,
+    pub completion_time: Timestamp,
+    pub initial_balance: Uint128,
+    pub balance: Uint128,
+}
+
 #[cfg(test)]
 mod tests {
     use super::Config;